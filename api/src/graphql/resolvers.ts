@@ -1,6 +1,7 @@
 import { PubSub } from 'graphql-subscriptions';
 import { PrismaClient } from '@prisma/client';
 import Redis from 'ioredis';
+import { toBase58 } from '../lib/base58';
 
 const pubsub = new PubSub();
 
@@ -26,6 +27,8 @@ export const resolvers = {
 
       const tx = await prisma.$queryRaw<RawTx[]>`
         SELECT t.signature, t.slot, t.block_time, t.success, t.fee, t.compute_units,
+               t.compute_unit_limit, t.compute_unit_price::text, t.priority_fee::text, t.base_fee::text,
+               t.jito_tip::text,
                t.accounts, t.log_messages,
                COALESCE(
                  json_agg(DISTINCT jsonb_build_object(
@@ -78,6 +81,8 @@ export const resolvers = {
 
       const rows = await prisma.$queryRaw<RawTx[]>`
         SELECT t.signature, t.slot, t.block_time, t.success, t.fee, t.compute_units,
+               t.compute_unit_limit, t.compute_unit_price::text, t.priority_fee::text, t.base_fee::text,
+               t.jito_tip::text,
                t.accounts, t.log_messages,
                '[]'::json AS instructions,
                '[]'::json AS token_transfers
@@ -141,6 +146,52 @@ export const resolvers = {
       };
     },
 
+    async solTransfers(
+      _: unknown,
+      args: { account: string; first?: number; after?: string },
+      { prisma }: Context,
+    ) {
+      const limit = Math.min(args.first ?? 20, 100);
+
+      const rows = await prisma.$queryRaw<RawSolTransfer[]>`
+        SELECT id::text, signature, slot, block_time, source, destination, lamports::text
+        FROM sol_transfers
+        WHERE (source = ${args.account} OR destination = ${args.account})
+        ORDER BY block_time DESC
+        LIMIT ${limit + 1}
+      `;
+
+      const hasNextPage = rows.length > limit;
+      const edges = rows.slice(0, limit).map((row) => ({
+        cursor: row.id,
+        node: mapSolTransfer(row),
+      }));
+
+      return {
+        edges,
+        pageInfo: {
+          hasNextPage,
+          hasPreviousPage: !!args.after,
+          startCursor: edges[0]?.cursor,
+          endCursor: edges[edges.length - 1]?.cursor,
+        },
+      };
+    },
+
+    async priorityFeeEstimate(
+      _: unknown,
+      { program }: { program?: string },
+      { prisma }: Context,
+    ) {
+      const rows = await prisma.$queryRaw<RawPriorityFeeEstimate[]>`
+        SELECT program_id, txs, cu_price_p25, cu_price_p50, cu_price_p75, cu_price_p90,
+               cu_price_p99, priority_fee_p50
+        FROM priority_fee_estimates
+        WHERE program_id IS NOT DISTINCT FROM ${program ?? null}
+      `;
+      return rows.length ? mapPriorityFeeEstimate(rows[0]) : null;
+    },
+
     async account(
       _: unknown,
       { pubkey }: { pubkey: string },
@@ -148,10 +199,8 @@ export const resolvers = {
     ) {
       const rows = await prisma.$queryRaw<RawAccount[]>`
         SELECT pubkey, slot, owner, lamports::text, executable, rent_epoch
-        FROM account_states
+        FROM accounts_latest
         WHERE pubkey = ${pubkey}
-        ORDER BY slot DESC
-        LIMIT 1
       `;
       return rows[0] ? mapAccount(rows[0]) : null;
     },
@@ -176,6 +225,201 @@ export const resolvers = {
       `;
       return rows.map(mapAccount);
     },
+
+    async dexTrades(
+      _: unknown,
+      args: { pool?: string; mint?: string; trader?: string; first?: number; after?: string },
+      { prisma }: Context,
+    ) {
+      const limit = Math.min(args.first ?? 20, 100);
+
+      const rows = await prisma.$queryRaw<RawDexTrade[]>`
+        SELECT id::text, signature, slot, block_time, program, pool, route, trader, side,
+               base_mint, quote_mint, base_amount::text, quote_amount::text, price
+        FROM dex_trades
+        WHERE TRUE
+          ${args.pool ? prisma.$queryRaw`AND pool = ${args.pool}` : prisma.$queryRaw``}
+          ${args.mint ? prisma.$queryRaw`AND (base_mint = ${args.mint} OR quote_mint = ${args.mint})` : prisma.$queryRaw``}
+          ${args.trader ? prisma.$queryRaw`AND trader = ${args.trader}` : prisma.$queryRaw``}
+          ${args.after ? prisma.$queryRaw`AND slot < ${parseInt(args.after, 10)}` : prisma.$queryRaw``}
+        ORDER BY block_time DESC, slot DESC
+        LIMIT ${limit + 1}
+      `;
+
+      const hasNextPage = rows.length > limit;
+      const edges = rows.slice(0, limit).map((row) => ({
+        cursor: String(row.slot),
+        node: mapDexTrade(row),
+      }));
+
+      return {
+        edges,
+        pageInfo: {
+          hasNextPage,
+          hasPreviousPage: !!args.after,
+          startCursor: edges[0]?.cursor,
+          endCursor: edges[edges.length - 1]?.cursor,
+        },
+      };
+    },
+
+    async pool(
+      _: unknown,
+      { address }: { address: string },
+      { prisma }: Context,
+    ) {
+      const rows = await prisma.$queryRaw<RawPool[]>`
+        SELECT address, program, base_mint, quote_mint, base_decimals, quote_decimals, fee_bps
+        FROM pools
+        WHERE address = ${address}
+        LIMIT 1
+      `;
+      return rows[0] ? mapPool(rows[0]) : null;
+    },
+
+    async nftMints(
+      _: unknown,
+      args: { collection?: string; owner?: string; first?: number },
+      { prisma }: Context,
+    ) {
+      const limit = Math.min(args.first ?? 20, 100);
+      const rows = await prisma.$queryRaw<RawNftMint[]>`
+        SELECT id::text, signature, slot, block_time, mint, owner, metadata_uri, collection
+        FROM nft_mints
+        WHERE TRUE
+          ${args.collection ? prisma.$queryRaw`AND collection = ${args.collection}` : prisma.$queryRaw``}
+          ${args.owner ? prisma.$queryRaw`AND owner = ${args.owner}` : prisma.$queryRaw``}
+        ORDER BY block_time DESC
+        LIMIT ${limit}
+      `;
+      return rows.map(mapNftMint);
+    },
+
+    async nftTransfers(
+      _: unknown,
+      args: { mint?: string; account?: string; first?: number },
+      { prisma }: Context,
+    ) {
+      const limit = Math.min(args.first ?? 20, 100);
+      const rows = await prisma.$queryRaw<RawNftTransfer[]>`
+        SELECT id::text, signature, slot, block_time, mint, source, destination, marketplace
+        FROM nft_transfers
+        WHERE TRUE
+          ${args.mint ? prisma.$queryRaw`AND mint = ${args.mint}` : prisma.$queryRaw``}
+          ${args.account ? prisma.$queryRaw`AND (source = ${args.account} OR destination = ${args.account})` : prisma.$queryRaw``}
+        ORDER BY block_time DESC
+        LIMIT ${limit}
+      `;
+      return rows.map(mapNftTransfer);
+    },
+
+    async compressedAsset(
+      _: unknown,
+      { id }: { id: string },
+      { prisma }: Context,
+    ) {
+      const rows = await prisma.$queryRaw<RawCnftLeaf[]>`
+        SELECT asset_id, tree, leaf_index, owner, delegate, name, symbol, uri,
+               collection, collection_verified, burned, updated_slot
+        FROM cnft_leaves
+        WHERE asset_id = ${id}
+        LIMIT 1
+      `;
+      return rows[0] ? mapCnftLeaf(rows[0]) : null;
+    },
+
+    async compressedAssetsByOwner(
+      _: unknown,
+      { owner, first }: { owner: string; first?: number },
+      { prisma }: Context,
+    ) {
+      const limit = Math.min(first ?? 20, 100);
+      const rows = await prisma.$queryRaw<RawCnftLeaf[]>`
+        SELECT asset_id, tree, leaf_index, owner, delegate, name, symbol, uri,
+               collection, collection_verified, burned, updated_slot
+        FROM cnft_leaves
+        WHERE owner = ${owner} AND NOT burned
+        ORDER BY updated_slot DESC
+        LIMIT ${limit}
+      `;
+      return rows.map(mapCnftLeaf);
+    },
+
+    async assetProof(
+      _: unknown,
+      { id }: { id: string },
+      { prisma }: Context,
+    ) {
+      // Sibling of the node at each level on the leaf → root path; nodes that
+      // were never written are empty subtrees of that height.
+      const rows = await prisma.$queryRaw<RawProofRow[]>`
+        WITH leaf AS (
+          SELECT l.tree, l.leaf_index, l.leaf_hash, t.max_depth, t.root
+          FROM cnft_leaves l
+          JOIN cnft_trees t ON t.tree = l.tree
+          WHERE l.asset_id = ${id}
+        ),
+        siblings AS (
+          SELECT leaf.tree, lvl.level,
+                 (((1::BIGINT << leaf.max_depth) + leaf.leaf_index) >> lvl.level) # 1 AS node_index
+          FROM leaf, generate_series(0, leaf.max_depth - 1) AS lvl(level)
+        )
+        SELECT leaf.tree, leaf.leaf_index, leaf.max_depth, leaf.root, leaf.leaf_hash,
+               s.level, COALESCE(n.hash, e.hash) AS hash
+        FROM leaf
+        JOIN siblings s ON s.tree = leaf.tree
+        LEFT JOIN cnft_tree_nodes n ON n.tree = s.tree AND n.node_index = s.node_index
+        LEFT JOIN cnft_empty_nodes e ON e.level = s.level
+        ORDER BY s.level
+      `;
+      if (!rows.length) return null;
+
+      const { tree, leaf_index, max_depth, root, leaf_hash } = rows[0];
+      return {
+        root: toBase58(root),
+        proof: rows.map((r) => toBase58(r.hash)),
+        nodeIndex: 2 ** max_depth + Number(leaf_index),
+        leaf: toBase58(leaf_hash),
+        treeId: tree,
+      };
+    },
+
+    async coreAsset(
+      _: unknown,
+      { id }: { id: string },
+      { prisma }: Context,
+    ) {
+      const rows = await prisma.$queryRaw<RawCoreAsset[]>`
+        SELECT a.address, a.owner, a.update_authority, a.collection, a.name, a.uri,
+               a.burned, a.updated_slot,
+               COALESCE(json_object_agg(p.plugin_type, p.data) FILTER (WHERE p.plugin_type IS NOT NULL), '{}') AS plugins
+        FROM core_assets a
+        LEFT JOIN core_plugins p ON p.address = a.address
+        WHERE a.address = ${id}
+        GROUP BY a.address
+      `;
+      return rows[0] ? mapCoreAsset(rows[0]) : null;
+    },
+
+    async coreAssetsByOwner(
+      _: unknown,
+      { owner, first }: { owner: string; first?: number },
+      { prisma }: Context,
+    ) {
+      const limit = Math.min(first ?? 20, 100);
+      const rows = await prisma.$queryRaw<RawCoreAsset[]>`
+        SELECT a.address, a.owner, a.update_authority, a.collection, a.name, a.uri,
+               a.burned, a.updated_slot,
+               COALESCE(json_object_agg(p.plugin_type, p.data) FILTER (WHERE p.plugin_type IS NOT NULL), '{}') AS plugins
+        FROM core_assets a
+        LEFT JOIN core_plugins p ON p.address = a.address
+        WHERE a.owner = ${owner} AND NOT a.burned
+        GROUP BY a.address
+        ORDER BY a.updated_slot DESC
+        LIMIT ${limit}
+      `;
+      return rows.map(mapCoreAsset);
+    },
   },
 
   Subscription: {
@@ -197,6 +441,14 @@ export const resolvers = {
       subscribe: (_: unknown, { pubkey }: { pubkey: string }) =>
         pubsub.asyncIterator(`account:${pubkey}`),
     },
+    dexTrade: {
+      subscribe: (_: unknown, { pool }: { pool: string }) =>
+        pubsub.asyncIterator(`swap:${pool}`),
+    },
+    nftActivity: {
+      subscribe: (_: unknown, { collection }: { collection: string }) =>
+        pubsub.asyncIterator(`nft:${collection}`),
+    },
   },
 };
 
@@ -209,6 +461,11 @@ interface RawTx {
   success: boolean;
   fee: bigint;
   compute_units: bigint;
+  compute_unit_limit: bigint | null;
+  compute_unit_price: string | null;
+  priority_fee: string | null;
+  base_fee: string | null;
+  jito_tip: string | null;
   accounts: string[];
   log_messages: string[];
   instructions: unknown[];
@@ -226,6 +483,27 @@ interface RawTransfer {
   decimals: number;
 }
 
+interface RawSolTransfer {
+  id: string;
+  signature: string;
+  slot: bigint;
+  block_time: Date;
+  source: string;
+  destination: string;
+  lamports: string;
+}
+
+interface RawPriorityFeeEstimate {
+  program_id: string | null;
+  txs: bigint | null;
+  cu_price_p25: number | null;
+  cu_price_p50: number | null;
+  cu_price_p75: number | null;
+  cu_price_p90: number | null;
+  cu_price_p99: number | null;
+  priority_fee_p50: number | null;
+}
+
 interface RawAccount {
   pubkey: string;
   slot: bigint;
@@ -235,6 +513,92 @@ interface RawAccount {
   rent_epoch: bigint;
 }
 
+interface RawDexTrade {
+  id: string;
+  signature: string;
+  slot: bigint;
+  block_time: Date;
+  program: string;
+  pool: string | null;
+  route: string | null;
+  trader: string;
+  side: string;
+  base_mint: string;
+  quote_mint: string;
+  base_amount: string;
+  quote_amount: string;
+  price: number | null;
+}
+
+interface RawPool {
+  address: string;
+  program: string;
+  base_mint: string;
+  quote_mint: string;
+  base_decimals: number | null;
+  quote_decimals: number | null;
+  fee_bps: number | null;
+}
+
+interface RawNftMint {
+  id: string;
+  signature: string;
+  slot: bigint;
+  block_time: Date;
+  mint: string;
+  owner: string;
+  metadata_uri: string | null;
+  collection: string | null;
+}
+
+interface RawNftTransfer {
+  id: string;
+  signature: string;
+  slot: bigint;
+  block_time: Date;
+  mint: string;
+  source: string;
+  destination: string;
+  marketplace: string | null;
+}
+
+interface RawCnftLeaf {
+  asset_id: string;
+  tree: string;
+  leaf_index: bigint;
+  owner: string | null;
+  delegate: string | null;
+  name: string | null;
+  symbol: string | null;
+  uri: string | null;
+  collection: string | null;
+  collection_verified: boolean;
+  burned: boolean;
+  updated_slot: bigint;
+}
+
+interface RawCoreAsset {
+  address: string;
+  owner: string;
+  update_authority: string;
+  collection: string | null;
+  name: string;
+  uri: string;
+  burned: boolean;
+  updated_slot: bigint;
+  plugins: unknown;
+}
+
+interface RawProofRow {
+  tree: string;
+  leaf_index: bigint;
+  max_depth: number;
+  root: Uint8Array;
+  leaf_hash: Uint8Array;
+  level: number;
+  hash: Uint8Array;
+}
+
 function mapTx(row: RawTx) {
   return {
     signature: row.signature,
@@ -243,6 +607,11 @@ function mapTx(row: RawTx) {
     success: row.success,
     fee: Number(row.fee),
     computeUnits: Number(row.compute_units),
+    computeUnitLimit: row.compute_unit_limit === null ? null : Number(row.compute_unit_limit),
+    computeUnitPrice: row.compute_unit_price,
+    priorityFee: row.priority_fee,
+    baseFee: row.base_fee,
+    jitoTip: row.jito_tip,
     accounts: row.accounts,
     logMessages: row.log_messages,
     instructions: row.instructions ?? [],
@@ -263,6 +632,31 @@ function mapTransfer(row: RawTransfer) {
   };
 }
 
+function mapSolTransfer(row: RawSolTransfer) {
+  return {
+    id: row.id,
+    signature: row.signature,
+    slot: Number(row.slot),
+    blockTime: row.block_time,
+    source: row.source,
+    destination: row.destination,
+    lamports: row.lamports,
+  };
+}
+
+function mapPriorityFeeEstimate(row: RawPriorityFeeEstimate) {
+  return {
+    program: row.program_id,
+    txs: Number(row.txs ?? 0),
+    cuPriceP25: row.cu_price_p25,
+    cuPriceP50: row.cu_price_p50,
+    cuPriceP75: row.cu_price_p75,
+    cuPriceP90: row.cu_price_p90,
+    cuPriceP99: row.cu_price_p99,
+    priorityFeeP50: row.priority_fee_p50,
+  };
+}
+
 function mapAccount(row: RawAccount) {
   return {
     pubkey: row.pubkey,
@@ -273,3 +667,91 @@ function mapAccount(row: RawAccount) {
     rentEpoch: String(row.rent_epoch),
   };
 }
+
+function mapDexTrade(row: RawDexTrade) {
+  return {
+    id: row.id,
+    signature: row.signature,
+    slot: Number(row.slot),
+    blockTime: row.block_time,
+    program: row.program,
+    pool: row.pool,
+    route: row.route,
+    trader: row.trader,
+    side: row.side,
+    baseMint: row.base_mint,
+    quoteMint: row.quote_mint,
+    baseAmount: row.base_amount,
+    quoteAmount: row.quote_amount,
+    price: row.price,
+  };
+}
+
+function mapPool(row: RawPool) {
+  return {
+    address: row.address,
+    program: row.program,
+    baseMint: row.base_mint,
+    quoteMint: row.quote_mint,
+    baseDecimals: row.base_decimals,
+    quoteDecimals: row.quote_decimals,
+    feeBps: row.fee_bps,
+  };
+}
+
+function mapNftMint(row: RawNftMint) {
+  return {
+    id: row.id,
+    signature: row.signature,
+    slot: Number(row.slot),
+    blockTime: row.block_time,
+    mint: row.mint,
+    owner: row.owner,
+    metadataUri: row.metadata_uri,
+    collection: row.collection,
+  };
+}
+
+function mapNftTransfer(row: RawNftTransfer) {
+  return {
+    id: row.id,
+    signature: row.signature,
+    slot: Number(row.slot),
+    blockTime: row.block_time,
+    mint: row.mint,
+    source: row.source,
+    destination: row.destination,
+    marketplace: row.marketplace,
+  };
+}
+
+function mapCnftLeaf(row: RawCnftLeaf) {
+  return {
+    id: row.asset_id,
+    tree: row.tree,
+    leafIndex: Number(row.leaf_index),
+    owner: row.owner,
+    delegate: row.delegate,
+    name: row.name,
+    symbol: row.symbol,
+    uri: row.uri,
+    collection: row.collection,
+    collectionVerified: row.collection_verified,
+    burned: row.burned,
+    slot: Number(row.updated_slot),
+  };
+}
+
+function mapCoreAsset(row: RawCoreAsset) {
+  return {
+    id: row.address,
+    owner: row.owner,
+    updateAuthority: row.update_authority,
+    collection: row.collection,
+    name: row.name,
+    uri: row.uri,
+    burned: row.burned,
+    plugins: row.plugins,
+    slot: Number(row.updated_slot),
+  };
+}
@@ -22,6 +22,14 @@ export const typeDefs = gql`
     success: Boolean!
     fee: Int!
     computeUnits: Int!
+    "Requested compute units"
+    computeUnitLimit: Int
+    "Compute unit price in micro-lamports"
+    computeUnitPrice: String
+    priorityFee: String
+    baseFee: String
+    "Lamports tipped to Jito"
+    jitoTip: String
     accounts: [String!]!
     logMessages: [String!]!
     instructions: [Instruction!]!
@@ -70,6 +78,39 @@ export const typeDefs = gql`
     totalCount: Int
   }
 
+  type SolTransfer {
+    id: ID!
+    signature: String!
+    slot: Int!
+    blockTime: DateTime!
+    source: String!
+    destination: String!
+    lamports: String!
+  }
+
+  type SolTransferEdge {
+    cursor: String!
+    node: SolTransfer!
+  }
+
+  type SolTransferConnection {
+    edges: [SolTransferEdge!]!
+    pageInfo: PageInfo!
+    totalCount: Int
+  }
+
+  "CU price percentiles (micro-lamports) over the last 5 minutes"
+  type PriorityFeeEstimate {
+    program: String
+    txs: Int!
+    cuPriceP25: Float
+    cuPriceP50: Float
+    cuPriceP75: Float
+    cuPriceP90: Float
+    cuPriceP99: Float
+    priorityFeeP50: Float
+  }
+
   type Account {
     pubkey: String!
     owner: String
@@ -97,6 +138,102 @@ export const typeDefs = gql`
     rentEpoch: String!
   }
 
+  type DexTrade {
+    id: ID!
+    signature: String!
+    slot: Int!
+    blockTime: DateTime!
+    program: String!
+    pool: String
+    "Aggregator whose route this pool swap is a hop of; excluded from volume"
+    route: String
+    trader: String!
+    side: String!
+    baseMint: String!
+    quoteMint: String!
+    baseAmount: String!
+    quoteAmount: String!
+    price: Float
+  }
+
+  type DexTradeEdge {
+    cursor: String!
+    node: DexTrade!
+  }
+
+  type DexTradeConnection {
+    edges: [DexTradeEdge!]!
+    pageInfo: PageInfo!
+    totalCount: Int
+  }
+
+  type Pool {
+    address: String!
+    program: String!
+    baseMint: String!
+    quoteMint: String!
+    baseDecimals: Int
+    quoteDecimals: Int
+    feeBps: Int
+  }
+
+  type NftMint {
+    id: ID!
+    signature: String!
+    slot: Int!
+    blockTime: DateTime!
+    mint: String!
+    owner: String!
+    metadataUri: String
+    collection: String
+  }
+
+  type NftTransfer {
+    id: ID!
+    signature: String!
+    slot: Int!
+    blockTime: DateTime!
+    mint: String!
+    source: String
+    destination: String!
+    marketplace: String
+  }
+
+  type CompressedAsset {
+    id: String!
+    tree: String!
+    leafIndex: Int!
+    owner: String
+    delegate: String
+    name: String
+    symbol: String
+    uri: String
+    collection: String
+    collectionVerified: Boolean!
+    burned: Boolean!
+    slot: Int!
+  }
+
+  type CoreAsset {
+    id: String!
+    owner: String!
+    updateAuthority: String!
+    collection: String
+    name: String!
+    uri: String!
+    burned: Boolean!
+    plugins: JSON!
+    slot: Int!
+  }
+
+  type AssetProof {
+    root: String!
+    proof: [String!]!
+    nodeIndex: Int!
+    leaf: String!
+    treeId: String!
+  }
+
   # ─── Root types ──────────────────────────────────────────────────────────────
 
   type Query {
@@ -117,13 +254,43 @@ export const typeDefs = gql`
       after: String
     ): TokenTransferConnection!
 
+    solTransfers(
+      account: String!
+      first: Int
+      after: String
+    ): SolTransferConnection!
+
+    "Overall estimate when program is omitted"
+    priorityFeeEstimate(program: String): PriorityFeeEstimate
+
     account(pubkey: String!): Account
     accountHistory(pubkey: String!, startSlot: Int, endSlot: Int): [AccountSnapshot!]!
+
+    dexTrades(
+      pool: String
+      mint: String
+      trader: String
+      first: Int
+      after: String
+    ): DexTradeConnection!
+    pool(address: String!): Pool
+
+    nftMints(collection: String, owner: String, first: Int): [NftMint!]!
+    nftTransfers(mint: String, account: String, first: Int): [NftTransfer!]!
+
+    compressedAsset(id: String!): CompressedAsset
+    compressedAssetsByOwner(owner: String!, first: Int): [CompressedAsset!]!
+    assetProof(id: String!): AssetProof
+
+    coreAsset(id: String!): CoreAsset
+    coreAssetsByOwner(owner: String!, first: Int): [CoreAsset!]!
   }
 
   type Subscription {
     transactionAdded(account: String, program: String): Transaction!
     tokenTransfer(account: String, mint: String): TokenTransfer!
     accountChanged(pubkey: String!): AccountUpdate!
+    dexTrade(pool: String!): DexTrade!
+    nftActivity(collection: String!): JSON!
   }
 `;
@@ -22,6 +22,7 @@ import { metricsHandler, requestMetricsMiddleware } from './middleware/metrics';
 import { logger } from './lib/logger';
 import { prisma } from './lib/prisma';
 import { redis } from './lib/redis';
+import { startWebhookDispatcher, startWebhookWorker } from './services/webhookService';
 
 const PORT = parseInt(process.env.PORT ?? '4000', 10);
 
@@ -93,6 +94,10 @@ async function main() {
   // REST endpoints (also behind auth)
   app.use('/v1', authMiddleware, restRouter);
 
+  // Webhook delivery
+  startWebhookWorker();
+  await startWebhookDispatcher();
+
   await new Promise<void>((resolve) => httpServer.listen(PORT, resolve));
   logger.info(`🚀  API server ready at http://localhost:${PORT}/graphql`);
 }
@@ -2,6 +2,7 @@ import { Router, RequestHandler } from 'express';
 import { z } from 'zod';
 import { prisma } from '../lib/prisma';
 import { logger } from '../lib/logger';
+import { invalidateWebhookCache } from '../services/webhookService';
 
 export const restRouter: ReturnType<typeof Router> = Router();
 
@@ -60,10 +61,8 @@ restRouter.get('/accounts/:pubkey', async (req, res) => {
   try {
     const rows = await prisma.$queryRaw<unknown[]>`
       SELECT pubkey, slot, owner, lamports, executable, rent_epoch
-      FROM account_states
+      FROM accounts_latest
       WHERE pubkey = ${pubkey}
-      ORDER BY slot DESC
-      LIMIT 1
     `;
     if (!rows.length) return res.status(404).json({ error: 'Not found' });
     return res.json(rows[0]);
@@ -95,17 +94,32 @@ restRouter.get('/tokens/:mint/transfers', async (req, res) => {
 
 restRouter.get('/tokens/:mint/holders', async (req, res) => {
   const { mint } = req.params;
+  const limit = Math.min(Number(req.query['limit'] ?? 100), 1000);
   try {
-    // Aggregate net transfers per destination to approximate current holders
     const rows = await prisma.$queryRaw<unknown[]>`
-      SELECT destination AS holder,
-             SUM(amount) AS total_received,
-             COUNT(*)    AS transfer_count
-      FROM token_transfers
+      SELECT owner AS holder, amount, token_accounts, last_slot
+      FROM token_holders
+      WHERE mint = ${mint} AND amount > 0
+      ORDER BY amount DESC
+      LIMIT ${limit}
+    `;
+    return res.json({ data: rows });
+  } catch (err) {
+    logger.error(err);
+    return res.status(500).json({ error: 'Internal server error' });
+  }
+});
+
+restRouter.get('/tokens/:mint/holders/history', async (req, res) => {
+  const { mint } = req.params;
+  const limit = Math.min(Number(req.query['limit'] ?? 168), 1000);
+  try {
+    const rows = await prisma.$queryRaw<unknown[]>`
+      SELECT time, holders, total_amount, top10_amount, hhi, slot
+      FROM token_holder_snapshots
       WHERE mint = ${mint}
-      GROUP BY destination
-      ORDER BY total_received DESC
-      LIMIT 100
+      ORDER BY time DESC
+      LIMIT ${limit}
     `;
     return res.json({ data: rows });
   } catch (err) {
@@ -145,6 +159,7 @@ restRouter.post('/webhooks', async (req, res) => {
       filters: (parsed.data.filters ?? {}) as object,
     },
   });
+  invalidateWebhookCache();
   return res.status(201).json(webhook);
 });
 
@@ -163,6 +178,7 @@ restRouter.put('/webhooks/:id', async (req, res) => {
         filters: parsed.data.filters as object | undefined,
       },
     });
+    invalidateWebhookCache();
     return res.json(webhook);
   } catch {
     return res.status(404).json({ error: 'Not found' });
@@ -174,6 +190,7 @@ restRouter.delete('/webhooks/:id', async (req, res) => {
   if (!userId) return res.status(401).json({ error: 'Unauthorized' });
   try {
     await prisma.webhook.delete({ where: { id: req.params['id'], userId } });
+    invalidateWebhookCache();
     return res.status(204).send();
   } catch {
     return res.status(404).json({ error: 'Not found' });
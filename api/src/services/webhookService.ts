@@ -4,10 +4,13 @@
  */
 
 import { Queue, Worker, Job } from 'bullmq';
+import type { Webhook } from '@prisma/client';
+import type Redis from 'ioredis';
 import axios from 'axios';
 import { createHmac } from 'crypto';
 import { prisma } from '../lib/prisma';
 import { logger } from '../lib/logger';
+import { redis } from '../lib/redis';
 
 interface WebhookJob {
   webhookId: string;
@@ -33,18 +36,44 @@ const redisConnection = {
 const webhookQueue = new Queue<WebhookJob>(QUEUE_NAME, {
   connection: redisConnection,
   defaultJobOptions: {
-    removeOnComplete: 100,
+    // Kept for a day so their job ids dedupe re-published events
+    removeOnComplete: { age: 24 * 60 * 60 },
     removeOnFail: 200,
   },
 });
 
+const WEBHOOK_CACHE_TTL_MS = 30_000;
+const webhookCache = new Map<string, { expires: number; webhooks: Promise<Webhook[]> }>();
+
+/**
+ * Active webhooks for an event. The dispatcher looks them up for every
+ * indexer message, so the list is cached briefly.
+ */
+function activeWebhooks(event: string): Promise<Webhook[]> {
+  const cached = webhookCache.get(event);
+  if (cached && cached.expires > Date.now()) return cached.webhooks;
+
+  const webhooks = prisma.webhook.findMany({ where: { event, active: true } });
+  webhookCache.set(event, { expires: Date.now() + WEBHOOK_CACHE_TTL_MS, webhooks });
+  webhooks.catch(() => webhookCache.delete(event));
+  return webhooks;
+}
+
+/**
+ * Drop the cached webhook lists; called when webhooks are created, updated
+ * or deleted.
+ */
+export function invalidateWebhookCache(): void {
+  webhookCache.clear();
+}
+
 /**
  * Enqueue webhook deliveries for all active webhooks matching event + filters.
+ * With a `dedupeKey`, a delivery already queued for the same key and webhook
+ * is not queued again.
  */
-export async function triggerWebhooks(event: string, data: unknown): Promise<void> {
-  const webhooks = await prisma.webhook.findMany({
-    where: { event, active: true },
-  });
+export async function triggerWebhooks(event: string, data: unknown, dedupeKey?: string): Promise<void> {
+  const webhooks = await activeWebhooks(event);
 
   for (const wh of webhooks) {
     const filters = wh.filters as Record<string, string>;
@@ -62,6 +91,7 @@ export async function triggerWebhooks(event: string, data: unknown): Promise<voi
       data,
       attempt: 1,
     }, {
+      jobId: dedupeKey ? `${dedupeKey}:${wh.id}` : undefined,
       attempts: MAX_ATTEMPTS,
       backoff: { type: 'exponential', delay: 1000 },
     });
@@ -116,3 +146,37 @@ export function startWebhookWorker(): Worker {
 
   return worker;
 }
+
+/** Identifying fields of a lending event published by the indexer. */
+interface LendingMessage {
+  signature: string;
+  instruction_index: number;
+  inner_index: number | null;
+}
+
+/**
+ * Bridge indexer pub/sub channels to webhook events. Lending events are
+ * published on `lending:<program>`; subscribers filter on the payload, e.g.
+ * `{ "action": "liquidate" }`.
+ */
+export async function startWebhookDispatcher(): Promise<Redis> {
+  const sub = redis.duplicate();
+  sub.on('pmessage', (_pattern: string, _channel: string, message: string) => {
+    let data: LendingMessage;
+    try {
+      data = JSON.parse(message);
+    } catch {
+      return;
+    }
+    // Re-published events (e.g. replayed dead letters) are delivered once per
+    // webhook. BullMQ only accepts ':' in custom job ids of exactly three parts.
+    const position = data.inner_index == null
+      ? `${data.instruction_index}`
+      : `${data.instruction_index}.${data.inner_index}`;
+    triggerWebhooks('lending', data, `${data.signature}:${position}`).catch((err) =>
+      logger.error({ err }, 'Failed to enqueue lending webhooks'),
+    );
+  });
+  await sub.psubscribe('lending:*');
+  return sub;
+}
@@ -19,7 +19,7 @@ const MOCK_WEBHOOKS: Webhook[] = [
   { id: '3', name: 'Jupiter Swaps', url: 'https://backend.myapp.io/swaps', event: 'swap', filters: {}, active: false, deliveredLast24h: 0, failedLast24h: 0 },
 ];
 
-const EVENTS = ['token_transfer', 'account_update', 'transaction', 'swap', 'nft_mint', 'nft_transfer'];
+const EVENTS = ['token_transfer', 'account_update', 'transaction', 'swap', 'nft_mint', 'nft_transfer', 'lending'];
 
 export default function WebhooksPage() {
   const [webhooks, setWebhooks] = useState<Webhook[]>(MOCK_WEBHOOKS);
@@ -0,0 +1,54 @@
+-- Normalised DEX trades and pool registry
+
+-- ============================================================
+-- Pool registry
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS pools (
+    address         TEXT PRIMARY KEY,
+    program         TEXT NOT NULL,
+    base_mint       TEXT NOT NULL,
+    quote_mint      TEXT NOT NULL,
+    base_vault      TEXT,
+    quote_vault     TEXT,
+    base_decimals   SMALLINT,
+    quote_decimals  SMALLINT,
+    fee_bps         INT,
+    created_slot    BIGINT,
+    updated_slot    BIGINT NOT NULL,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_pools_pair    ON pools (base_mint, quote_mint);
+CREATE INDEX IF NOT EXISTS idx_pools_program ON pools (program);
+
+-- ============================================================
+-- DEX trades (base/quote oriented)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS dex_trades (
+    id                BIGSERIAL,
+    signature         TEXT NOT NULL,
+    slot              BIGINT NOT NULL,
+    block_time        TIMESTAMPTZ NOT NULL,
+    program           TEXT NOT NULL,
+    pool              TEXT,
+    trader            TEXT NOT NULL,
+    instruction_index INT  NOT NULL,
+    inner_index       INT,
+    side              TEXT NOT NULL CHECK (side IN ('buy', 'sell')),
+    base_mint         TEXT NOT NULL,
+    quote_mint        TEXT NOT NULL,
+    base_amount       BIGINT NOT NULL,
+    quote_amount      BIGINT NOT NULL,
+    price             DOUBLE PRECISION,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('dex_trades', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_dex_trades_ix
+    ON dex_trades (signature, instruction_index, COALESCE(inner_index, -1), block_time);
+CREATE INDEX IF NOT EXISTS idx_dt_pair   ON dex_trades (base_mint, quote_mint, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_dt_pool   ON dex_trades (pool, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_dt_trader ON dex_trades (trader, block_time DESC);
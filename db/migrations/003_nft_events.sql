@@ -0,0 +1,38 @@
+-- NFT mint and transfer hypertables
+
+CREATE TABLE IF NOT EXISTS nft_mints (
+    id            BIGSERIAL,
+    signature     TEXT NOT NULL,
+    slot          BIGINT NOT NULL,
+    block_time    TIMESTAMPTZ NOT NULL,
+    mint          TEXT NOT NULL,
+    owner         TEXT NOT NULL,
+    metadata_uri  TEXT,
+    collection    TEXT,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('nft_mints', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_nft_mints_sig_mint ON nft_mints (signature, mint, block_time);
+CREATE INDEX IF NOT EXISTS idx_nm_mint       ON nft_mints (mint);
+CREATE INDEX IF NOT EXISTS idx_nm_collection ON nft_mints (collection, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_nm_owner      ON nft_mints (owner, block_time DESC);
+
+CREATE TABLE IF NOT EXISTS nft_transfers (
+    id           BIGSERIAL,
+    signature    TEXT NOT NULL,
+    slot         BIGINT NOT NULL,
+    block_time   TIMESTAMPTZ NOT NULL,
+    mint         TEXT NOT NULL,
+    source       TEXT NOT NULL,
+    destination  TEXT NOT NULL,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('nft_transfers', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_nft_transfers_sig_mint ON nft_transfers (signature, mint, block_time);
+CREATE INDEX IF NOT EXISTS idx_nt_mint        ON nft_transfers (mint, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_nt_source      ON nft_transfers (source, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_nt_destination ON nft_transfers (destination, block_time DESC);
@@ -0,0 +1,24 @@
+-- Decoded Metaplex Token Metadata, keyed by metadata account
+
+CREATE TABLE IF NOT EXISTS nft_metadata (
+    metadata                 TEXT PRIMARY KEY,
+    mint                     TEXT,
+    update_authority         TEXT,
+    name                     TEXT,
+    symbol                   TEXT,
+    uri                      TEXT,
+    seller_fee_basis_points  INT,
+    creators                 JSONB NOT NULL DEFAULT '[]',
+    collection               TEXT,
+    collection_verified      BOOLEAN NOT NULL DEFAULT FALSE,
+    token_standard           SMALLINT,
+    is_mutable               BOOLEAN,
+    burned                   BOOLEAN NOT NULL DEFAULT FALSE,
+    created_slot             BIGINT,
+    updated_slot             BIGINT NOT NULL,
+    updated_at               TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_nft_metadata_mint ON nft_metadata (mint) WHERE mint IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_nmd_collection ON nft_metadata (collection) WHERE collection_verified;
+CREATE INDEX IF NOT EXISTS idx_nmd_creators   ON nft_metadata USING GIN (creators jsonb_path_ops);
@@ -0,0 +1,95 @@
+-- Compressed NFT (Bubblegum) state: Merkle trees, nodes and leaves
+
+-- ============================================================
+-- Concurrent Merkle trees (spl-account-compression)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS cnft_trees (
+    tree          TEXT PRIMARY KEY,
+    max_depth     SMALLINT NOT NULL,
+    seq           BIGINT NOT NULL,
+    root          BYTEA,
+    updated_slot  BIGINT NOT NULL
+);
+
+-- Latest hash of every node written by a change log. Root is node 1; leaf i
+-- of a depth-d tree is node 2^d + i. Nodes never written are empty subtrees.
+CREATE TABLE IF NOT EXISTS cnft_tree_nodes (
+    tree        TEXT   NOT NULL,
+    node_index  BIGINT NOT NULL,
+    hash        BYTEA  NOT NULL,
+    seq         BIGINT NOT NULL,
+    slot        BIGINT NOT NULL,
+    PRIMARY KEY (tree, node_index)
+);
+
+-- Hash of an empty subtree of the given height: keccak256(h(level-1) || h(level-1)).
+CREATE TABLE IF NOT EXISTS cnft_empty_nodes (
+    level  SMALLINT PRIMARY KEY,
+    hash   BYTEA NOT NULL
+);
+
+INSERT INTO cnft_empty_nodes (level, hash) VALUES
+    ( 0, '\x0000000000000000000000000000000000000000000000000000000000000000'),
+    ( 1, '\xad3228b676f7d3cd4284a5443f17f1962b36e491b30a40b2405849e597ba5fb5'),
+    ( 2, '\xb4c11951957c6f8f642c4af61cd6b24640fec6dc7fc607ee8206a99e92410d30'),
+    ( 3, '\x21ddb9a356815c3fac1026b6dec5df3124afbadb485c9ba5a3e3398a04b7ba85'),
+    ( 4, '\xe58769b32a1beaf1ea27375a44095a0d1fb664ce2dd358e7fcbfb78c26a19344'),
+    ( 5, '\x0eb01ebfc9ed27500cd4dfc979272d1f0913cc9f66540d7e8005811109e1cf2d'),
+    ( 6, '\x887c22bd8750d34016ac3c66b5ff102dacdd73f6b014e710b51e8022af9a1968'),
+    ( 7, '\xffd70157e48063fc33c97a050f7f640233bf646cc98d9524c6b92bcf3ab56f83'),
+    ( 8, '\x9867cc5f7f196b93bae1e27e6320742445d290f2263827498b54fec539f756af'),
+    ( 9, '\xcefad4e508c098b9a7e1d8feb19955fb02ba9675585078710969d3440f5054e0'),
+    (10, '\xf9dc3e7fe016e050eff260334f18a5d4fe391d82092319f5964f2e2eb7c1c3a5'),
+    (11, '\xf8b13a49e282f609c317a833fb8d976d11517c571d1221a265d25af778ecf892'),
+    (12, '\x3490c6ceeb450aecdc82e28293031d10c7d73bf85e57bf041a97360aa2c5d99c'),
+    (13, '\xc1df82d9c4b87413eae2ef048f94b4d3554cea73d92b0f7af96e0271c691e2bb'),
+    (14, '\x5c67add7c6caf302256adedf7ab114da0acfe870d449a3a489f781d659e8becc'),
+    (15, '\xda7bce9f4e8618b6bd2f4132ce798cdc7a60e7e1460a7299e3c6342a579626d2'),
+    (16, '\x2733e50f526ec2fa19a22b31e8ed50f23cd1fdf94c9154ed3a7609a2f1ff981f'),
+    (17, '\xe1d3b5c807b281e4683cc6d6315cf95b9ade8641defcb32372f1c126e398ef7a'),
+    (18, '\x5a2dce0a8a7f68bb74560f8f71837c2c2ebbcbf7fffb42ae1896f13f7c7479a0'),
+    (19, '\xb46a28b6f55540f89444f63de0378e3d121be09e06cc9ded1c20e65876d36aa0'),
+    (20, '\xc65e9645644786b620e2dd2ad648ddfcbf4a7e5b1a3a4ecfe7f64667a3f0b7e2'),
+    (21, '\xf4418588ed35a2458cffeb39b93d26f18d2ab13bdce6aee58e7b99359ec2dfd9'),
+    (22, '\x5a9c16dc00d6ef18b7933a6f8dc65ccb55667138776f7dea101070dc8796e377'),
+    (23, '\x4df84f40ae0c8229d0d6069e5c8f39a7c299677a09d367fc7b05e3bc380ee652'),
+    (24, '\xcdc72595f74c7b1043d0e1ffbab734648c838dfb0527d971b602bc216c9619ef'),
+    (25, '\x0abf5ac974a1ed57f4050aa510dd9c74f508277b39d7973bb2dfccc5eeb0618d'),
+    (26, '\xb8cd74046ff337f0a7bf2c8e03e10f642c1886798d71806ab1e888d9e5ee87d0'),
+    (27, '\x838c5655cb21c6cb83313b5a631175dff4963772cce9108188b34ac87c81c41e'),
+    (28, '\x662ee4dd2dd7b2bc707961b1e646c4047669dcb6584f0d8d770daf5d7e7deb2e'),
+    (29, '\x388ab20e2573d171a88108e79d820e98f26c0b84aa8b2f4aa4968dbb818ea322'),
+    (30, '\x93237c50ba75ee485f4c22adf2f741400bdf8d6a9cc7df7ecae576221665d735')
+ON CONFLICT (level) DO NOTHING;
+
+-- ============================================================
+-- Leaves (one row per asset)
+-- ============================================================
+
+CREATE TABLE IF NOT EXISTS cnft_leaves (
+    tree                 TEXT    NOT NULL,
+    leaf_index           BIGINT  NOT NULL,
+    asset_id             TEXT,
+    owner                TEXT,
+    delegate             TEXT,
+    nonce                BIGINT,
+    data_hash            BYTEA,
+    creator_hash         BYTEA,
+    leaf_hash            BYTEA,
+    name                 TEXT,
+    symbol               TEXT,
+    uri                  TEXT,
+    collection           TEXT,
+    collection_verified  BOOLEAN NOT NULL DEFAULT FALSE,
+    burned               BOOLEAN NOT NULL DEFAULT FALSE,
+    last_action          TEXT    NOT NULL,
+    seq                  BIGINT  NOT NULL,
+    created_slot         BIGINT  NOT NULL,
+    updated_slot         BIGINT  NOT NULL,
+    PRIMARY KEY (tree, leaf_index)
+);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_cnft_leaves_asset ON cnft_leaves (asset_id) WHERE asset_id IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_cl_owner      ON cnft_leaves (owner) WHERE NOT burned;
+CREATE INDEX IF NOT EXISTS idx_cl_collection ON cnft_leaves (collection) WHERE NOT burned;
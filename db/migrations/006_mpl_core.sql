@@ -0,0 +1,38 @@
+-- Metaplex Core assets, collections and plugins
+
+CREATE TABLE IF NOT EXISTS core_assets (
+    address           TEXT PRIMARY KEY,
+    owner             TEXT NOT NULL,
+    update_authority  TEXT NOT NULL,
+    collection        TEXT,
+    name              TEXT NOT NULL,
+    uri               TEXT NOT NULL,
+    burned            BOOLEAN NOT NULL DEFAULT FALSE,
+    created_slot      BIGINT NOT NULL,
+    updated_slot      BIGINT NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_ca_owner      ON core_assets (owner) WHERE NOT burned;
+CREATE INDEX IF NOT EXISTS idx_ca_collection ON core_assets (collection) WHERE NOT burned;
+
+CREATE TABLE IF NOT EXISTS core_collections (
+    address           TEXT PRIMARY KEY,
+    update_authority  TEXT NOT NULL,
+    name              TEXT NOT NULL,
+    uri               TEXT NOT NULL,
+    burned            BOOLEAN NOT NULL DEFAULT FALSE,
+    created_slot      BIGINT NOT NULL,
+    updated_slot      BIGINT NOT NULL
+);
+
+-- Plugins attached to an asset or a collection
+CREATE TABLE IF NOT EXISTS core_plugins (
+    address       TEXT  NOT NULL,
+    plugin_type   TEXT  NOT NULL,
+    authority     TEXT,
+    data          JSONB NOT NULL DEFAULT '{}',
+    updated_slot  BIGINT NOT NULL,
+    PRIMARY KEY (address, plugin_type)
+);
+
+CREATE INDEX IF NOT EXISTS idx_cp_type ON core_plugins (plugin_type);
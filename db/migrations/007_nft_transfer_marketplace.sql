@@ -0,0 +1,8 @@
+-- Marketplace attribution for NFT transfers detected from token movements
+
+ALTER TABLE nft_transfers
+    ADD COLUMN IF NOT EXISTS marketplace     TEXT,
+    ADD COLUMN IF NOT EXISTS marketplace_ix  INT;
+
+CREATE INDEX IF NOT EXISTS idx_nt_marketplace ON nft_transfers (marketplace, block_time DESC)
+    WHERE marketplace IS NOT NULL;
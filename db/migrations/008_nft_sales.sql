@@ -0,0 +1,27 @@
+-- NFT marketplace sales hypertable (amounts in lamports)
+
+CREATE TABLE IF NOT EXISTS nft_sales (
+    id                 BIGSERIAL,
+    signature          TEXT NOT NULL,
+    slot               BIGINT NOT NULL,
+    block_time         TIMESTAMPTZ NOT NULL,
+    marketplace        TEXT NOT NULL,
+    instruction_index  INT  NOT NULL,
+    mint               TEXT NOT NULL,
+    collection         TEXT,
+    buyer              TEXT NOT NULL,
+    seller             TEXT NOT NULL,
+    price              BIGINT NOT NULL,
+    marketplace_fee    BIGINT NOT NULL DEFAULT 0,
+    royalty            BIGINT NOT NULL DEFAULT 0,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('nft_sales', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_nft_sales_ix ON nft_sales (signature, instruction_index, mint, block_time);
+CREATE INDEX IF NOT EXISTS idx_ns_mint        ON nft_sales (mint, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_ns_collection  ON nft_sales (collection, block_time DESC) WHERE collection IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_ns_buyer       ON nft_sales (buyer, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_ns_seller      ON nft_sales (seller, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_ns_marketplace ON nft_sales (marketplace, block_time DESC);
@@ -0,0 +1,46 @@
+-- Native SOL transfers and System program account events
+
+CREATE TABLE IF NOT EXISTS sol_transfers (
+    id                 BIGSERIAL,
+    signature          TEXT NOT NULL,
+    slot               BIGINT NOT NULL,
+    block_time         TIMESTAMPTZ NOT NULL,
+    instruction_index  INT  NOT NULL,
+    inner_index        INT,
+    source             TEXT NOT NULL,
+    destination        TEXT NOT NULL,
+    lamports           BIGINT NOT NULL,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('sol_transfers', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_sol_transfers_ix
+    ON sol_transfers (signature, instruction_index, COALESCE(inner_index, -1), block_time);
+CREATE INDEX IF NOT EXISTS idx_st_source      ON sol_transfers (source, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_st_destination ON sol_transfers (destination, block_time DESC);
+
+CREATE TABLE IF NOT EXISTS system_account_events (
+    id                 BIGSERIAL,
+    signature          TEXT NOT NULL,
+    slot               BIGINT NOT NULL,
+    block_time         TIMESTAMPTZ NOT NULL,
+    instruction_index  INT  NOT NULL,
+    inner_index        INT,
+    action             TEXT NOT NULL,
+    account            TEXT NOT NULL,
+    owner              TEXT,
+    space              BIGINT,
+    lamports           BIGINT,
+    base               TEXT,
+    seed               TEXT,
+    authority          TEXT,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('system_account_events', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_system_account_events_ix
+    ON system_account_events (signature, instruction_index, COALESCE(inner_index, -1), block_time);
+CREATE INDEX IF NOT EXISTS idx_sae_account ON system_account_events (account, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_sae_owner   ON system_account_events (owner, block_time DESC) WHERE owner IS NOT NULL;
@@ -0,0 +1,67 @@
+-- Stake program instruction history and current delegations
+
+CREATE TABLE IF NOT EXISTS stake_events (
+    id                 BIGSERIAL,
+    signature          TEXT NOT NULL,
+    slot               BIGINT NOT NULL,
+    block_time         TIMESTAMPTZ NOT NULL,
+    epoch              BIGINT NOT NULL,
+    instruction_index  INT  NOT NULL,
+    inner_index        INT,
+    action             TEXT NOT NULL,
+    stake_account      TEXT NOT NULL,
+    vote_account       TEXT,
+    staker             TEXT,
+    withdrawer         TEXT,
+    related_account    TEXT,
+    lamports           BIGINT,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('stake_events', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_stake_events_ix
+    ON stake_events (signature, instruction_index, COALESCE(inner_index, -1), block_time);
+CREATE INDEX IF NOT EXISTS idx_se_stake_account ON stake_events (stake_account, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_se_vote_account  ON stake_events (vote_account, block_time DESC) WHERE vote_account IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_se_staker        ON stake_events (staker, block_time DESC) WHERE staker IS NOT NULL;
+
+-- One row per live stake account; closed or uninitialized accounts are removed.
+CREATE TABLE IF NOT EXISTS stake_delegations (
+    stake_account        TEXT PRIMARY KEY,
+    staker               TEXT,
+    withdrawer           TEXT,
+    vote_account         TEXT,
+    delegated_stake      BIGINT,
+    lamports             BIGINT,
+    rent_exempt_reserve  BIGINT,
+    activation_epoch     BIGINT,
+    deactivation_epoch   BIGINT,
+    updated_epoch        BIGINT NOT NULL,
+    updated_slot         BIGINT NOT NULL,
+    updated_at           TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_sd_vote_account ON stake_delegations (vote_account) WHERE vote_account IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_sd_staker       ON stake_delegations (staker);
+CREATE INDEX IF NOT EXISTS idx_sd_withdrawer   ON stake_delegations (withdrawer);
+
+-- Active delegations rolled up per validator vote account and per staker
+CREATE OR REPLACE VIEW validator_delegations AS
+SELECT vote_account,
+       COUNT(*)                          AS stake_accounts,
+       COALESCE(SUM(delegated_stake), 0) AS delegated_stake,
+       MAX(updated_epoch)                AS updated_epoch
+FROM stake_delegations
+WHERE vote_account IS NOT NULL AND deactivation_epoch IS NULL
+GROUP BY vote_account;
+
+CREATE OR REPLACE VIEW staker_delegations AS
+SELECT staker,
+       vote_account,
+       COUNT(*)                          AS stake_accounts,
+       COALESCE(SUM(delegated_stake), 0) AS delegated_stake,
+       MAX(updated_epoch)                AS updated_epoch
+FROM stake_delegations
+WHERE vote_account IS NOT NULL AND deactivation_epoch IS NULL
+GROUP BY staker, vote_account;
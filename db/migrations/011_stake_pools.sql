@@ -0,0 +1,25 @@
+-- Liquid staking deposits and withdrawals (SPL Stake Pool / Jito, Marinade)
+
+CREATE TABLE IF NOT EXISTS stake_pool_events (
+    id                 BIGSERIAL,
+    signature          TEXT NOT NULL,
+    slot               BIGINT NOT NULL,
+    block_time         TIMESTAMPTZ NOT NULL,
+    instruction_index  INT  NOT NULL,
+    inner_index        INT,
+    program            TEXT NOT NULL,
+    pool               TEXT NOT NULL,
+    action             TEXT NOT NULL,
+    user_account       TEXT NOT NULL,
+    sol_amount         BIGINT,
+    lst_mint           TEXT,
+    lst_amount         BIGINT,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('stake_pool_events', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_stake_pool_events_ix
+    ON stake_pool_events (signature, instruction_index, COALESCE(inner_index, -1), block_time);
+CREATE INDEX IF NOT EXISTS idx_spe_pool ON stake_pool_events (pool, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_spe_user ON stake_pool_events (user_account, block_time DESC);
@@ -0,0 +1,32 @@
+-- Lending protocol events (Kamino, MarginFi, Solend)
+
+CREATE TABLE IF NOT EXISTS lending_events (
+    id                  BIGSERIAL,
+    signature           TEXT NOT NULL,
+    slot                BIGINT NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    instruction_index   INT  NOT NULL,
+    inner_index         INT,
+    program             TEXT NOT NULL,
+    action              TEXT NOT NULL CHECK (action IN ('deposit', 'withdraw', 'borrow', 'repay', 'liquidate')),
+    reserve             TEXT NOT NULL,
+    obligation          TEXT NOT NULL,
+    owner               TEXT,
+    mint                TEXT,
+    amount              BIGINT,
+    liquidator          TEXT,
+    liquidatee          TEXT,
+    collateral_reserve  TEXT,
+    collateral_mint     TEXT,
+    collateral_amount   BIGINT,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('lending_events', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_lending_events_ix
+    ON lending_events (signature, instruction_index, COALESCE(inner_index, -1), block_time);
+CREATE INDEX IF NOT EXISTS idx_le_obligation   ON lending_events (obligation, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_le_reserve      ON lending_events (reserve, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_le_owner        ON lending_events (owner, block_time DESC) WHERE owner IS NOT NULL;
+CREATE INDEX IF NOT EXISTS idx_le_liquidations ON lending_events (program, block_time DESC) WHERE action = 'liquidate';
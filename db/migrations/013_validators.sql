@@ -0,0 +1,96 @@
+-- Block rewards, votes, vote credits and leader schedules
+
+-- Per-account rewards from the block meta (fee and rent rewards to the leader,
+-- staking and voting rewards at epoch boundaries)
+CREATE TABLE IF NOT EXISTS block_rewards (
+    id            BIGSERIAL,
+    slot          BIGINT NOT NULL,
+    block_time    TIMESTAMPTZ NOT NULL,
+    pubkey        TEXT   NOT NULL,
+    reward_type   TEXT,
+    lamports      BIGINT NOT NULL,
+    post_balance  BIGINT NOT NULL,
+    commission    SMALLINT,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('block_rewards', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_block_rewards
+    ON block_rewards (slot, pubkey, COALESCE(reward_type, ''), block_time);
+CREATE INDEX IF NOT EXISTS idx_br_pubkey ON block_rewards (pubkey, block_time DESC);
+
+-- Landed votes; latency = landed slot - newest voted slot
+CREATE TABLE IF NOT EXISTS vote_events (
+    id            BIGSERIAL,
+    signature     TEXT   NOT NULL,
+    slot          BIGINT NOT NULL,
+    block_time    TIMESTAMPTZ NOT NULL,
+    vote_account  TEXT   NOT NULL,
+    authority     TEXT,
+    voted_slot    BIGINT NOT NULL,
+    root_slot     BIGINT,
+    latency       INT    NOT NULL,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('vote_events', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_vote_events
+    ON vote_events (signature, vote_account, block_time);
+CREATE INDEX IF NOT EXISTS idx_ve_vote_account ON vote_events (vote_account, block_time DESC);
+
+-- Vote credits per validator and epoch, from vote account state
+CREATE TABLE IF NOT EXISTS validator_credits (
+    vote_account  TEXT   NOT NULL,
+    epoch         BIGINT NOT NULL,
+    node          TEXT   NOT NULL,
+    commission    SMALLINT NOT NULL,
+    credits       BIGINT NOT NULL,
+    prev_credits  BIGINT NOT NULL,
+    root_slot     BIGINT,
+    updated_slot  BIGINT NOT NULL,
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW(),
+    PRIMARY KEY (vote_account, epoch)
+);
+
+CREATE INDEX IF NOT EXISTS idx_vc_epoch ON validator_credits (epoch, vote_account);
+CREATE INDEX IF NOT EXISTS idx_vc_node  ON validator_credits (node, epoch DESC);
+
+-- Leader schedule, loaded over RPC once per epoch
+CREATE TABLE IF NOT EXISTS leader_schedule (
+    slot    BIGINT PRIMARY KEY,
+    epoch   BIGINT NOT NULL,
+    leader  TEXT   NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS idx_ls_leader ON leader_schedule (leader, epoch);
+
+-- Credits earned per epoch
+CREATE OR REPLACE VIEW validator_epoch_credits AS
+SELECT vote_account, node, epoch, commission, credits - prev_credits AS epoch_credits
+FROM validator_credits;
+
+-- Hourly vote latency per validator
+CREATE OR REPLACE VIEW validator_vote_latency AS
+SELECT vote_account,
+       time_bucket('1 hour', block_time) AS bucket,
+       COUNT(*)                          AS votes,
+       AVG(latency)::NUMERIC(10, 3)      AS avg_latency,
+       MAX(latency)                      AS max_latency
+FROM vote_events
+GROUP BY vote_account, bucket;
+
+-- Produced vs skipped leader slots. Only slots within the indexed block range
+-- count, so gaps in indexing show up as skips.
+CREATE OR REPLACE VIEW leader_slot_stats AS
+SELECT ls.epoch,
+       ls.leader,
+       COUNT(*)                  AS leader_slots,
+       COUNT(b.slot)             AS blocks_produced,
+       COUNT(*) - COUNT(b.slot)  AS skipped_slots,
+       ROUND(100.0 * (COUNT(*) - COUNT(b.slot)) / COUNT(*), 2) AS skip_rate
+FROM leader_schedule ls
+LEFT JOIN blocks b ON b.slot = ls.slot
+WHERE ls.slot BETWEEN (SELECT MIN(slot) FROM blocks) AND (SELECT MAX(slot) FROM blocks)
+GROUP BY ls.epoch, ls.leader;
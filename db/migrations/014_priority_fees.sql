@@ -0,0 +1,122 @@
+-- no-transaction
+-- Compute budget and priority fee analytics
+-- (continuous aggregates cannot be created inside a transaction block)
+
+CREATE EXTENSION IF NOT EXISTS timescaledb_toolkit;
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS compute_unit_limit BIGINT,
+    ADD COLUMN IF NOT EXISTS compute_unit_price BIGINT,   -- micro-lamports per CU
+    ADD COLUMN IF NOT EXISTS priority_fee       BIGINT,   -- lamports
+    ADD COLUMN IF NOT EXISTS base_fee           BIGINT;   -- lamports
+
+-- One sample per non-vote transaction
+CREATE TABLE IF NOT EXISTS priority_fees (
+    id                  BIGSERIAL,
+    signature           TEXT   NOT NULL,
+    slot                BIGINT NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    success             BOOLEAN NOT NULL,
+    compute_unit_limit  BIGINT NOT NULL,
+    compute_unit_price  BIGINT NOT NULL,
+    priority_fee        BIGINT NOT NULL,
+    compute_units       BIGINT NOT NULL,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('priority_fees', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_priority_fees ON priority_fees (signature, block_time);
+CREATE INDEX IF NOT EXISTS idx_pf_slot ON priority_fees (slot DESC);
+
+-- One sample per (transaction, top-level program)
+CREATE TABLE IF NOT EXISTS program_priority_fees (
+    id                  BIGSERIAL,
+    signature           TEXT   NOT NULL,
+    slot                BIGINT NOT NULL,
+    block_time          TIMESTAMPTZ NOT NULL,
+    program_id          TEXT   NOT NULL,
+    compute_unit_price  BIGINT NOT NULL,
+    priority_fee        BIGINT NOT NULL,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('program_priority_fees', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_program_priority_fees
+    ON program_priority_fees (signature, program_id, block_time);
+CREATE INDEX IF NOT EXISTS idx_ppf_program ON program_priority_fees (program_id, block_time DESC);
+
+-- Per-slot percentile sketches
+CREATE MATERIALIZED VIEW IF NOT EXISTS priority_fees_by_slot
+WITH (timescaledb.continuous, timescaledb.materialized_only = false) AS
+SELECT time_bucket('1 minute', block_time)               AS bucket,
+       slot,
+       COUNT(*)                                          AS txs,
+       percentile_agg(compute_unit_price::DOUBLE PRECISION) AS cu_price_pct,
+       percentile_agg(priority_fee::DOUBLE PRECISION)    AS priority_fee_pct
+FROM priority_fees
+GROUP BY bucket, slot
+WITH NO DATA;
+
+SELECT add_continuous_aggregate_policy('priority_fees_by_slot',
+    start_offset      => INTERVAL '1 hour',
+    end_offset        => INTERVAL '30 seconds',
+    schedule_interval => INTERVAL '30 seconds',
+    if_not_exists     => TRUE);
+
+-- Per-program, per-minute percentile sketches
+CREATE MATERIALIZED VIEW IF NOT EXISTS priority_fees_by_program
+WITH (timescaledb.continuous, timescaledb.materialized_only = false) AS
+SELECT time_bucket('1 minute', block_time)               AS bucket,
+       program_id,
+       COUNT(*)                                          AS txs,
+       percentile_agg(compute_unit_price::DOUBLE PRECISION) AS cu_price_pct,
+       percentile_agg(priority_fee::DOUBLE PRECISION)    AS priority_fee_pct
+FROM program_priority_fees
+GROUP BY bucket, program_id
+WITH NO DATA;
+
+SELECT add_continuous_aggregate_policy('priority_fees_by_program',
+    start_offset      => INTERVAL '1 hour',
+    end_offset        => INTERVAL '30 seconds',
+    schedule_interval => INTERVAL '30 seconds',
+    if_not_exists     => TRUE);
+
+-- CU price percentiles (micro-lamports) per slot
+CREATE OR REPLACE VIEW slot_priority_fees AS
+SELECT slot,
+       bucket,
+       txs,
+       approx_percentile(0.25, cu_price_pct) AS cu_price_p25,
+       approx_percentile(0.50, cu_price_pct) AS cu_price_p50,
+       approx_percentile(0.75, cu_price_pct) AS cu_price_p75,
+       approx_percentile(0.90, cu_price_pct) AS cu_price_p90,
+       approx_percentile(0.99, cu_price_pct) AS cu_price_p99,
+       approx_percentile(0.50, priority_fee_pct) AS priority_fee_p50
+FROM priority_fees_by_slot;
+
+-- Rolling estimates over the last 5 minutes, per program and overall (NULL program)
+CREATE OR REPLACE VIEW priority_fee_estimates AS
+SELECT program_id,
+       SUM(txs)                                          AS txs,
+       approx_percentile(0.25, rollup(cu_price_pct))     AS cu_price_p25,
+       approx_percentile(0.50, rollup(cu_price_pct))     AS cu_price_p50,
+       approx_percentile(0.75, rollup(cu_price_pct))     AS cu_price_p75,
+       approx_percentile(0.90, rollup(cu_price_pct))     AS cu_price_p90,
+       approx_percentile(0.99, rollup(cu_price_pct))     AS cu_price_p99,
+       approx_percentile(0.50, rollup(priority_fee_pct)) AS priority_fee_p50
+FROM priority_fees_by_program
+WHERE bucket > NOW() - INTERVAL '5 minutes'
+GROUP BY program_id
+UNION ALL
+SELECT NULL,
+       SUM(txs),
+       approx_percentile(0.25, rollup(cu_price_pct)),
+       approx_percentile(0.50, rollup(cu_price_pct)),
+       approx_percentile(0.75, rollup(cu_price_pct)),
+       approx_percentile(0.90, rollup(cu_price_pct)),
+       approx_percentile(0.99, rollup(cu_price_pct)),
+       approx_percentile(0.50, rollup(priority_fee_pct))
+FROM priority_fees_by_slot
+WHERE bucket > NOW() - INTERVAL '5 minutes';
@@ -0,0 +1,28 @@
+-- Jito tips and heuristically grouped bundles
+
+ALTER TABLE transactions
+    ADD COLUMN IF NOT EXISTS tx_index  BIGINT,   -- position within the block
+    ADD COLUMN IF NOT EXISTS jito_tip  BIGINT;   -- lamports sent to Jito tip accounts
+
+CREATE INDEX IF NOT EXISTS idx_txs_jito_tip ON transactions (slot DESC) WHERE jito_tip IS NOT NULL;
+
+CREATE TABLE IF NOT EXISTS bundles (
+    id             BIGSERIAL,
+    slot           BIGINT NOT NULL,
+    block_time     TIMESTAMPTZ NOT NULL,
+    tip_signature  TEXT   NOT NULL,
+    tip_payer      TEXT   NOT NULL,
+    tip_lamports   BIGINT NOT NULL,
+    tx_count       SMALLINT NOT NULL,
+    signatures     TEXT[] NOT NULL,
+    first_index    BIGINT NOT NULL,
+    last_index     BIGINT NOT NULL,
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('bundles', 'block_time', if_not_exists => TRUE);
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_bundles_tip ON bundles (tip_signature, block_time);
+CREATE INDEX IF NOT EXISTS idx_bundles_slot       ON bundles (slot DESC);
+CREATE INDEX IF NOT EXISTS idx_bundles_tip_payer  ON bundles (tip_payer, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_bundles_signatures ON bundles USING GIN (signatures);
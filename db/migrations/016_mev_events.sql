@@ -0,0 +1,25 @@
+-- Sandwiches and cyclic arbitrage found by the per-slot MEV stage
+
+CREATE TABLE IF NOT EXISTS mev_events (
+    id                 BIGSERIAL,
+    slot               BIGINT NOT NULL,
+    block_time         TIMESTAMPTZ NOT NULL,
+    kind               TEXT   NOT NULL CHECK (kind IN ('sandwich', 'arbitrage')),
+    searcher           TEXT   NOT NULL,
+    signatures         TEXT[] NOT NULL,
+    victim_signatures  TEXT[] NOT NULL DEFAULT '{}',
+    pools              TEXT[] NOT NULL,
+    profit_mint        TEXT   NOT NULL,
+    profit_amount      BIGINT NOT NULL,   -- native units of profit_mint; negative = loss
+    PRIMARY KEY (id, block_time)
+);
+
+SELECT create_hypertable('mev_events', 'block_time', if_not_exists => TRUE);
+
+-- The first searcher signature identifies the event
+CREATE UNIQUE INDEX IF NOT EXISTS uq_mev_events
+    ON mev_events (kind, (signatures[1]), block_time);
+CREATE INDEX IF NOT EXISTS idx_mev_searcher   ON mev_events (searcher, block_time DESC);
+CREATE INDEX IF NOT EXISTS idx_mev_slot       ON mev_events (slot DESC);
+CREATE INDEX IF NOT EXISTS idx_mev_pools      ON mev_events USING GIN (pools);
+CREATE INDEX IF NOT EXISTS idx_mev_victims    ON mev_events USING GIN (victim_signatures);
@@ -0,0 +1,13 @@
+-- Batches the writer gave up on, kept as ParsedEvent JSON for re-driving
+-- with `sol-indexer dead-letter replay`
+
+CREATE TABLE IF NOT EXISTS dead_letter_events (
+    id          BIGSERIAL PRIMARY KEY,
+    kind        TEXT        NOT NULL,   -- ParsedEvent variant, e.g. 'Transaction'
+    event       JSONB       NOT NULL,
+    error       TEXT        NOT NULL,
+    attempts    INT         NOT NULL,
+    failed_at   TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_letter_kind ON dead_letter_events (kind, failed_at DESC);
@@ -0,0 +1,9 @@
+-- Every outer and inner instruction of indexed transactions, with the JSON of
+-- the event a parser decoded from it (an array when it yielded several)
+
+ALTER TABLE instructions ADD COLUMN IF NOT EXISTS slot       BIGINT;
+ALTER TABLE instructions ADD COLUMN IF NOT EXISTS block_time TIMESTAMPTZ;
+
+CREATE UNIQUE INDEX IF NOT EXISTS uq_instructions
+    ON instructions (signature, instruction_index, COALESCE(inner_index, -1));
+CREATE INDEX IF NOT EXISTS idx_instr_program_slot ON instructions (program_id, slot DESC);
@@ -0,0 +1,7 @@
+-- Indexer progress: the newest slot every writer shard has committed
+
+CREATE TABLE IF NOT EXISTS indexer_state (
+    id              BOOLEAN PRIMARY KEY DEFAULT TRUE CHECK (id),   -- single row
+    committed_slot  BIGINT      NOT NULL,
+    updated_at      TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
@@ -0,0 +1,43 @@
+-- Latest account state, plus compression of account_states history.
+-- How much history the indexer keeps is set by ACCOUNT_HISTORY* env vars.
+
+ALTER TABLE account_states ADD COLUMN IF NOT EXISTS write_version BIGINT NOT NULL DEFAULT 0;
+
+CREATE TABLE IF NOT EXISTS accounts_latest (
+    pubkey         TEXT PRIMARY KEY,
+    slot           BIGINT  NOT NULL,
+    write_version  BIGINT  NOT NULL DEFAULT 0,
+    owner          TEXT    NOT NULL,
+    lamports       BIGINT  NOT NULL,
+    data           BYTEA,
+    executable     BOOLEAN NOT NULL DEFAULT FALSE,
+    rent_epoch     BIGINT  NOT NULL DEFAULT 0,
+    updated_at     TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_accounts_latest_owner ON accounts_latest (owner);
+
+INSERT INTO accounts_latest (pubkey, slot, owner, lamports, data, executable, rent_epoch)
+SELECT DISTINCT ON (pubkey) pubkey, slot, owner, lamports, data, executable, rent_epoch
+FROM account_states
+ORDER BY pubkey, slot DESC
+ON CONFLICT (pubkey) DO NOTHING;
+
+-- History becomes a slot-partitioned hypertable (one epoch per chunk) so old
+-- chunks can be compressed
+SELECT create_hypertable('account_states', 'slot', chunk_time_interval => 432000,
+                         if_not_exists => TRUE, migrate_data => TRUE);
+
+CREATE OR REPLACE FUNCTION account_states_now() RETURNS BIGINT
+LANGUAGE SQL STABLE AS $$ SELECT COALESCE(MAX(slot), 0) FROM accounts_latest $$;
+
+SELECT set_integer_now_func('account_states', 'account_states_now', replace_if_exists => TRUE);
+
+ALTER TABLE account_states SET (
+    timescaledb.compress,
+    timescaledb.compress_segmentby = 'pubkey',
+    timescaledb.compress_orderby   = 'slot DESC'
+);
+
+-- Compress chunks more than ~2 epochs old
+SELECT add_compression_policy('account_states', compress_after => 864000::BIGINT, if_not_exists => TRUE);
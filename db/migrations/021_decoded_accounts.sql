@@ -0,0 +1,44 @@
+-- Typed tables for account updates decoded by the indexer's account parsers
+
+CREATE TABLE IF NOT EXISTS token_balances (
+    token_account     TEXT        PRIMARY KEY,
+    program           TEXT        NOT NULL,   -- SPL Token or Token-2022
+    mint              TEXT        NOT NULL,
+    owner             TEXT        NOT NULL,
+    amount            BIGINT      NOT NULL,
+    delegate          TEXT,
+    delegated_amount  BIGINT      NOT NULL DEFAULT 0,
+    state             TEXT        NOT NULL,   -- uninitialized | initialized | frozen
+    is_native         BIGINT,                 -- rent-exempt reserve of wrapped SOL accounts
+    close_authority   TEXT,
+    last_slot         BIGINT      NOT NULL,
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_token_balances_owner ON token_balances (owner);
+CREATE INDEX IF NOT EXISTS idx_token_balances_mint  ON token_balances (mint);
+
+CREATE TABLE IF NOT EXISTS mints (
+    address           TEXT        PRIMARY KEY,
+    program           TEXT        NOT NULL,
+    decimals          SMALLINT    NOT NULL,
+    supply            BIGINT      NOT NULL,
+    mint_authority    TEXT,
+    freeze_authority  TEXT,
+    is_initialized    BOOLEAN     NOT NULL,
+    slot              BIGINT      NOT NULL,
+    updated_at        TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+-- Accounts of programs with a configured Anchor IDL (ANCHOR_IDL_DIR)
+CREATE TABLE IF NOT EXISTS anchor_accounts (
+    address       TEXT        PRIMARY KEY,
+    program       TEXT        NOT NULL,
+    account_type  TEXT        NOT NULL,
+    data          JSONB       NOT NULL,
+    slot          BIGINT      NOT NULL,
+    updated_at    TIMESTAMPTZ NOT NULL DEFAULT NOW()
+);
+
+CREATE INDEX IF NOT EXISTS idx_anchor_accounts_type ON anchor_accounts (program, account_type);
+CREATE INDEX IF NOT EXISTS idx_anchor_accounts_data ON anchor_accounts USING GIN (data jsonb_path_ops);
@@ -0,0 +1,61 @@
+-- Holder rollup per owner over the current token balances, and hourly
+-- holder snapshots for charting holder counts and concentration.
+
+-- Top holders of a mint
+CREATE INDEX IF NOT EXISTS idx_token_balances_mint_amount ON token_balances (mint, amount DESC);
+
+-- One row per (mint, owner) across all of the owner's token accounts
+CREATE OR REPLACE VIEW token_holders AS
+SELECT mint,
+       owner,
+       SUM(amount)    AS amount,
+       COUNT(*)       AS token_accounts,
+       MAX(last_slot) AS last_slot
+FROM token_balances
+GROUP BY mint, owner;
+
+CREATE TABLE IF NOT EXISTS token_holder_snapshots (
+    time          TIMESTAMPTZ      NOT NULL,
+    mint          TEXT             NOT NULL,
+    holders       INTEGER          NOT NULL,   -- owners with a non-zero balance
+    total_amount  NUMERIC          NOT NULL,
+    top10_amount  NUMERIC          NOT NULL,
+    hhi           DOUBLE PRECISION NOT NULL,   -- Herfindahl index: sum of squared holder shares
+    slot          BIGINT           NOT NULL    -- newest balance included
+);
+
+SELECT create_hypertable('token_holder_snapshots', 'time', chunk_time_interval => INTERVAL '7 days',
+                         if_not_exists => TRUE);
+
+CREATE INDEX IF NOT EXISTS idx_token_holder_snapshots_mint ON token_holder_snapshots (mint, time DESC);
+
+ALTER TABLE token_holder_snapshots SET (
+    timescaledb.compress,
+    timescaledb.compress_segmentby = 'mint',
+    timescaledb.compress_orderby   = 'time DESC'
+);
+
+SELECT add_compression_policy('token_holder_snapshots', compress_after => INTERVAL '30 days', if_not_exists => TRUE);
+
+CREATE OR REPLACE PROCEDURE snapshot_token_holders(job_id INTEGER, config JSONB)
+LANGUAGE SQL AS $$
+    INSERT INTO token_holder_snapshots (time, mint, holders, total_amount, top10_amount, hhi, slot)
+    SELECT NOW(),
+           mint,
+           COUNT(*),
+           SUM(amount),
+           COALESCE(SUM(amount) FILTER (WHERE rank <= 10), 0),
+           COALESCE(SUM((amount::FLOAT8 / NULLIF(total, 0)::FLOAT8) ^ 2), 0),
+           MAX(last_slot)
+    FROM (
+        SELECT mint, amount, last_slot,
+               ROW_NUMBER() OVER (PARTITION BY mint ORDER BY amount DESC) AS rank,
+               SUM(amount)  OVER (PARTITION BY mint)                      AS total
+        FROM token_holders
+        WHERE amount > 0
+    ) h
+    GROUP BY mint
+$$;
+
+SELECT add_job('snapshot_token_holders', INTERVAL '1 hour')
+WHERE NOT EXISTS (SELECT 1 FROM timescaledb_information.jobs WHERE proc_name = 'snapshot_token_holders');
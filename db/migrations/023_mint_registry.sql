@@ -0,0 +1,22 @@
+-- Mint registry: Token-2022 extensions, metadata name/symbol and supply
+-- tracking from MintTo / Burn between mint account updates.
+
+ALTER TABLE mints ADD COLUMN IF NOT EXISTS extensions  JSONB;    -- NULL until the mint account is seen
+ALTER TABLE mints ADD COLUMN IF NOT EXISTS name        TEXT;
+ALTER TABLE mints ADD COLUMN IF NOT EXISTS symbol      TEXT;
+-- Slot of the mint account update the supply was read from; later mints and
+-- burns are applied on top. NULL when registered from InitializeMint.
+ALTER TABLE mints ADD COLUMN IF NOT EXISTS supply_slot BIGINT;
+
+UPDATE mints SET supply_slot = slot, extensions = '{}'::JSONB WHERE supply_slot IS NULL;
+
+UPDATE mints m SET name = md.name, symbol = md.symbol
+FROM nft_metadata md
+WHERE md.mint = m.address AND m.name IS NULL;
+
+CREATE INDEX IF NOT EXISTS idx_mints_symbol ON mints (symbol);
+
+-- Plain Transfer instructions carry no decimals
+UPDATE token_transfers t SET decimals = m.decimals
+FROM mints m
+WHERE t.mint = m.address AND t.decimals = 0 AND m.decimals > 0;
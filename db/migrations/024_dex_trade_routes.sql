@@ -0,0 +1,4 @@
+-- Pool swaps executed as hops of an aggregator route. The route itself is a
+-- trade of its own (pool NULL), so volume sums take `route IS NULL` rows only.
+
+ALTER TABLE dex_trades ADD COLUMN IF NOT EXISTS route TEXT;
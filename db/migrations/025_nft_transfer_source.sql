@@ -0,0 +1,5 @@
+-- Metaplex Core transfers name only the signer, not the previous owner. The
+-- indexer resolves it from core_assets, and leaves it NULL when the asset is
+-- not indexed yet.
+
+ALTER TABLE nft_transfers ALTER COLUMN source DROP NOT NULL;
@@ -0,0 +1,56 @@
+//! Maintenance subcommands, run instead of the indexer.
+//!
+//! ```text
+//! sol-indexer migrate                      # apply pending schema migrations
+//! sol-indexer dead-letter list [limit]     # newest dead letters
+//! sol-indexer dead-letter replay [limit]   # re-drive dead letters, oldest first
+//! ```
+
+use anyhow::{bail, Context, Result};
+use sqlx::postgres::PgPoolOptions;
+use std::sync::Arc;
+
+use crate::config::Config;
+use crate::metrics::Metrics;
+use crate::writer::Writer;
+
+pub async fn migrate(cfg: &Config) -> Result<()> {
+    let pool = PgPoolOptions::new()
+        .max_connections(1)
+        .connect(&cfg.database_url)
+        .await?;
+    crate::migrate::run(&pool).await
+}
+
+pub async fn dead_letter(cfg: &Config, args: &[String]) -> Result<()> {
+    let limit: i64 = match args.get(1) {
+        Some(n) => n.parse().with_context(|| format!("invalid limit `{n}`"))?,
+        None => i64::MAX,
+    };
+    let mut writer = connect_writer(cfg).await?;
+
+    match args.first().map(String::as_str) {
+        Some("list") => {
+            for d in writer.list_dead_letters(limit.min(1_000)).await? {
+                println!("{}\t{}\t{}\t{}\t{}", d.id, d.failed_at.to_rfc3339(), d.kind, d.attempts, d.error);
+            }
+        }
+        Some("replay") => {
+            let replayed = writer.replay_dead_letters(limit).await?;
+            println!("Replayed {replayed} dead-lettered events");
+        }
+        _ => bail!("usage: sol-indexer dead-letter <list|replay> [limit]"),
+    }
+    Ok(())
+}
+
+async fn connect_writer(cfg: &Config) -> Result<Writer> {
+    let pool = PgPoolOptions::new()
+        .max_connections(2)
+        .connect(&cfg.database_url)
+        .await?;
+    let redis_client = redis::Client::open(cfg.redis_url.as_str())?;
+    let redis_conn = redis::aio::ConnectionManager::new(redis_client).await?;
+    Ok(Writer::new(pool, redis_conn, cfg.write_batch_size, Arc::new(Metrics::new()))
+        .with_account_history(cfg.account_history.clone()))
+}
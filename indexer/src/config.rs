@@ -11,14 +11,53 @@ pub struct Config {
     pub grpc_token: Option<String>,
     /// PostgreSQL connection URL (TimescaleDB compatible)
     pub database_url: String,
+    /// Optional Solana JSON-RPC URL, used to load leader schedules
+    pub rpc_url: Option<String>,
     /// Redis connection URL
     pub redis_url: String,
     /// Maximum DB pool size
     pub db_pool_size: u32,
     /// Number of events to batch before writing to DB
     pub write_batch_size: usize,
+    /// Number of parallel writer shards, each holding one DB connection at a time
+    pub writer_shards: usize,
     /// Port to serve Prometheus metrics / health endpoints
     pub metrics_port: u16,
+    /// Apply pending schema migrations at startup instead of refusing to start
+    pub auto_migrate: bool,
+    /// Which account updates are kept as history in `account_states`
+    pub account_history: AccountHistory,
+    /// Directory of Anchor IDL JSON files whose program accounts are decoded
+    pub anchor_idl_dir: Option<String>,
+}
+
+/// History retention for `account_states`; `accounts_latest` is always kept.
+#[derive(Debug, Clone)]
+pub struct AccountHistory {
+    /// Keep history at all (`ACCOUNT_HISTORY`).
+    pub enabled: bool,
+    /// Only keep history of accounts owned by these programs; empty keeps all
+    /// (`ACCOUNT_HISTORY_OWNERS`, comma-separated).
+    pub owners: Vec<String>,
+    /// Versions kept per account; `None` keeps every version
+    /// (`ACCOUNT_HISTORY_VERSIONS`).
+    pub versions: Option<u32>,
+}
+
+impl AccountHistory {
+    pub fn keeps(&self, owner: &str) -> bool {
+        self.enabled && (self.owners.is_empty() || self.owners.iter().any(|o| o == owner))
+    }
+}
+
+impl Default for AccountHistory {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            owners: Vec::new(),
+            versions: None,
+        }
+    }
 }
 
 impl Config {
@@ -27,6 +66,7 @@ impl Config {
             grpc_endpoint: env_var("GRPC_ENDPOINT")?,
             grpc_token: env::var("GRPC_TOKEN").ok(),
             database_url: env_var("DATABASE_URL")?,
+            rpc_url: env::var("RPC_URL").ok().filter(|u| !u.is_empty()),
             redis_url: env::var("REDIS_URL").unwrap_or_else(|_| "redis://127.0.0.1:6379".into()),
             db_pool_size: env::var("DB_POOL_SIZE")
                 .ok()
@@ -36,10 +76,26 @@ impl Config {
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(100),
+            writer_shards: env::var("WRITER_SHARDS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(4),
             metrics_port: env::var("METRICS_PORT")
                 .ok()
                 .and_then(|v| v.parse().ok())
                 .unwrap_or(9090),
+            auto_migrate: env::var("AUTO_MIGRATE").map_or(true, |v| v != "false" && v != "0"),
+            account_history: AccountHistory {
+                enabled: env::var("ACCOUNT_HISTORY").map_or(true, |v| v != "false" && v != "0"),
+                owners: env::var("ACCOUNT_HISTORY_OWNERS")
+                    .map(|v| v.split(',').map(|o| o.trim().to_string()).filter(|o| !o.is_empty()).collect())
+                    .unwrap_or_default(),
+                versions: env::var("ACCOUNT_HISTORY_VERSIONS")
+                    .ok()
+                    .and_then(|v| v.parse().ok())
+                    .filter(|&n| n > 0),
+            },
+            anchor_idl_dir: env::var("ANCHOR_IDL_DIR").ok().filter(|d| !d.is_empty()),
         })
     }
 }
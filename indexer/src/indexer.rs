@@ -2,20 +2,32 @@
 
 use anyhow::Result;
 use sqlx::postgres::PgPoolOptions;
+use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::mpsc;
 use tracing::{error, info, warn};
 
 use crate::config::Config;
 use crate::metrics::Metrics;
-use crate::parser::{ParsedEvent, ParserEngine};
+use crate::mev::MevStage;
+use crate::parser::anchor::AnchorAccountParser;
+use crate::parser::compute_budget::ComputeBudget;
+use crate::parser::jito::jito_tip;
+use crate::parser::stake::epoch_for_slot;
+use crate::parser::token_account::{MintParser, TokenAccountParser};
+use crate::parser::{
+    LeaderScheduleEvent, ParsedEvent, ParserEngine, COMPUTE_BUDGET_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID,
+    SPL_TOKEN_PROGRAM_ID,
+};
 use crate::receiver::{Update, YellowstoneReceiver};
-use crate::writer::Writer;
+use crate::rpc::RpcClient;
+use crate::writer::WriterPool;
 
 /// Main indexer — wires receiver → parser engine → writer.
 pub struct Indexer {
     cfg: Config,
     parser_engine: Arc<ParserEngine>,
+    mev: MevStage,
     metrics: Arc<Metrics>,
 }
 
@@ -26,14 +38,45 @@ impl Indexer {
 
         let mut parser_engine = ParserEngine::new();
         // Register built-in parsers
-        parser_engine.register(Box::new(crate::parser::spl_token::SplTokenParser::new()));
+        parser_engine.register(Box::new(crate::parser::system::SystemParser::new()));
+        parser_engine.register(Box::new(crate::parser::stake::StakeParser::new()));
+        parser_engine.register(Box::new(crate::parser::vote::VoteParser::new()));
+        parser_engine.register(Box::new(crate::parser::stake_pool::StakePoolParser::new()));
+        parser_engine.register(Box::new(crate::parser::marinade::MarinadeParser::new()));
+        parser_engine.register(Box::new(crate::parser::kamino::KaminoParser::new()));
+        parser_engine.register(Box::new(crate::parser::marginfi::MarginfiParser::new()));
+        parser_engine.register(Box::new(crate::parser::solend::SolendParser::new()));
+        for program in [SPL_TOKEN_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID] {
+            parser_engine.register(Box::new(crate::parser::spl_token::SplTokenParser::new(program)));
+        }
         parser_engine.register(Box::new(crate::parser::nft::NftParser::new()));
         parser_engine.register(Box::new(crate::parser::jupiter::JupiterParser::new()));
         parser_engine.register(Box::new(crate::parser::raydium::RaydiumParser::new()));
+        parser_engine.register(Box::new(crate::parser::bubblegum::BubblegumParser::new()));
+        parser_engine.register(Box::new(crate::parser::mpl_core::MplCoreParser::new()));
+        for parser in crate::parser::marketplace::MarketplaceParser::all() {
+            parser_engine.register(Box::new(parser));
+        }
+
+        // Register account decoders
+        for program in [SPL_TOKEN_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID] {
+            parser_engine.register_account(Box::new(TokenAccountParser::new(program)));
+            parser_engine.register_account(Box::new(MintParser::new(program)));
+        }
+        parser_engine.register_account(Box::new(crate::parser::stake::StakeAccountParser::new()));
+        parser_engine.register_account(Box::new(crate::parser::vote::VoteAccountParser::new()));
+        parser_engine.register_account(Box::new(crate::parser::raydium::AmmInfoParser::new()));
+        parser_engine.register_account(Box::new(crate::parser::nft::MetadataAccountParser::new()));
+        if let Some(dir) = &cfg.anchor_idl_dir {
+            for parser in AnchorAccountParser::load_dir(Path::new(dir))? {
+                parser_engine.register_account(Box::new(parser));
+            }
+        }
 
         Ok(Self {
             cfg,
             parser_engine: Arc::new(parser_engine),
+            mev: MevStage::new(),
             metrics,
         })
     }
@@ -45,6 +88,11 @@ impl Indexer {
             .connect(&self.cfg.database_url)
             .await?;
         info!("Database pool ready (max_connections={})", self.cfg.db_pool_size);
+        if self.cfg.auto_migrate {
+            crate::migrate::run(&db_pool).await?;
+        } else {
+            crate::migrate::check(&db_pool).await?;
+        }
 
         let redis_client = redis::Client::open(self.cfg.redis_url.as_str())?;
         let redis_conn = redis::aio::ConnectionManager::new(redis_client).await?;
@@ -53,15 +101,17 @@ impl Indexer {
         // Internal channel: receiver → writer, with backpressure
         let (tx, rx) = mpsc::channel::<ParsedEvent>(8_192);
 
-        let writer = Writer::new(
+        let writers = WriterPool::spawn(
             db_pool.clone(),
             redis_conn,
             self.cfg.write_batch_size,
+            self.cfg.writer_shards,
+            self.cfg.account_history.clone(),
             Arc::clone(&self.metrics),
         );
 
-        // Spawn writer task
-        let _write_task = tokio::spawn(writer.run(rx));
+        // Spawn writer pool task
+        let _write_task = tokio::spawn(writers.run(rx));
 
         // Connect to Yellowstone gRPC
         let mut receiver = YellowstoneReceiver::new(
@@ -72,10 +122,24 @@ impl Indexer {
         let mut stream = receiver.subscribe().await?;
         info!("Subscribed to Yellowstone gRPC stream");
 
+        let rpc = self.cfg.rpc_url.clone().map(|url| Arc::new(RpcClient::new(url)));
+        if rpc.is_none() {
+            warn!("RPC_URL not set; leader schedules will not be loaded");
+        }
+        let mut schedule_epoch = None;
+
         loop {
             match stream.recv().await {
                 Some(Ok(update)) => {
                     self.metrics.updates_received.increment(1);
+                    // Load the leader schedule the first time a block from each epoch arrives
+                    if let (Update::Block(block), Some(rpc)) = (&update, &rpc) {
+                        let epoch = epoch_for_slot(block.slot);
+                        if schedule_epoch != Some(epoch) {
+                            schedule_epoch = Some(epoch);
+                            tokio::spawn(load_leader_schedule(Arc::clone(rpc), epoch, tx.clone()));
+                        }
+                    }
                     let events = self.process_update(update);
                     for event in events {
                         if tx.send(event).await.is_err() {
@@ -98,38 +162,78 @@ impl Indexer {
         }
     }
 
-    fn process_update(&self, update: Update) -> Vec<ParsedEvent> {
+    fn process_update(&mut self, update: Update) -> Vec<ParsedEvent> {
         match update {
             Update::Transaction(tx_update) => {
                 let mut events = self.parser_engine.parse(&tx_update);
+                let budget = ComputeBudget::from_transaction(&tx_update);
+                let priority_fee = budget.priority_fee();
+                let tip = jito_tip(&tx_update);
+                let mut programs: Vec<String> = Vec::new();
+                for ix in tx_update.instructions.iter().filter(|ix| ix.inner_index.is_none()) {
+                    if ix.program_id != COMPUTE_BUDGET_PROGRAM_ID && !programs.contains(&ix.program_id) {
+                        programs.push(ix.program_id.clone());
+                    }
+                }
                 // Wrap raw transaction metadata as an event too
                 events.insert(
                     0,
                     ParsedEvent::Transaction(crate::parser::TransactionEvent {
                         signature: tx_update.signature.clone(),
                         slot: tx_update.slot,
+                        index: tx_update.index,
                         block_time: tx_update.block_time,
                         success: tx_update.success,
                         fee: tx_update.fee,
                         compute_units: tx_update.compute_units,
+                        compute_unit_limit: budget.compute_unit_limit,
+                        compute_unit_price: budget.compute_unit_price,
+                        priority_fee,
+                        base_fee: tx_update.fee.saturating_sub(priority_fee),
+                        jito_tip: tip.as_ref().map(|t| t.lamports),
+                        programs,
                         accounts: tx_update.accounts.clone(),
                         log_messages: tx_update.log_messages.clone(),
                     }),
                 );
+                let mev_events = self.mev.observe(&tx_update, tip, &events);
+                events.extend(mev_events);
                 self.metrics.transactions_processed.increment(1);
                 events
             }
             Update::Account(acct) => {
                 self.metrics.accounts_processed.increment(1);
-                vec![ParsedEvent::AccountUpdate(acct)]
+                let mut events = self.parser_engine.parse_account(&acct);
+                events.insert(0, ParsedEvent::AccountUpdate(acct));
+                events
             }
             Update::Block(block) => {
                 self.metrics.blocks_processed.increment(1);
                 self.metrics
                     .indexer_lag_slots
                     .set(block.tip_slot.saturating_sub(block.slot) as f64);
-                vec![ParsedEvent::Block(block)]
+                // The block goes last: it closes the slot for the writer watermark.
+                let mut events = self.mev.finish_slot(block.slot);
+                events.push(ParsedEvent::Block(block));
+                events
+            }
+        }
+    }
+}
+
+/// Fetch an epoch's leader schedule over RPC and hand it to the writer,
+/// retrying a few times since the schedule rarely changes mid-epoch.
+async fn load_leader_schedule(rpc: Arc<RpcClient>, epoch: u64, tx: mpsc::Sender<ParsedEvent>) {
+    for attempt in 1..=3 {
+        match rpc.leader_schedule(epoch).await {
+            Ok(leaders) if !leaders.is_empty() => {
+                info!("Loaded leader schedule for epoch {epoch} ({} slots)", leaders.len());
+                let _ = tx.send(ParsedEvent::LeaderSchedule(LeaderScheduleEvent { epoch, leaders })).await;
+                return;
             }
+            Ok(_) => warn!("Leader schedule for epoch {epoch} not available (attempt {attempt})"),
+            Err(e) => warn!("Failed to load leader schedule for epoch {epoch} (attempt {attempt}): {e}"),
         }
+        tokio::time::sleep(std::time::Duration::from_secs(10)).await;
     }
 }
@@ -8,11 +8,15 @@ use anyhow::Result;
 use tracing::info;
 use tracing_subscriber::EnvFilter;
 
+mod cli;
 mod config;
 mod indexer;
 mod metrics;
+mod migrate;
+mod mev;
 mod parser;
 mod receiver;
+mod rpc;
 mod writer;
 
 use crate::config::Config;
@@ -29,6 +33,15 @@ async fn main() -> Result<()> {
     dotenv::dotenv().ok();
 
     let cfg = Config::from_env()?;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        None | Some("run") => {}
+        Some("migrate") => return cli::migrate(&cfg).await,
+        Some("dead-letter") => return cli::dead_letter(&cfg, &args[1..]).await,
+        Some(other) => anyhow::bail!("unknown command `{other}`; expected `run`, `migrate` or `dead-letter`"),
+    }
+
     info!("Starting SolIndexer v{}", env!("CARGO_PKG_VERSION"));
     info!("Connecting to gRPC endpoint: {}", cfg.grpc_endpoint);
 
@@ -15,8 +15,10 @@ pub struct Metrics {
     pub blocks_processed: Counter,
     pub stream_errors: Counter,
     pub db_errors: Counter,
+    pub dead_letter_events: Counter,
     pub db_write_latency_ms: Histogram,
     pub indexer_lag_slots: Gauge,
+    pub committed_slot: Gauge,
 }
 
 impl Metrics {
@@ -31,8 +33,10 @@ impl Metrics {
             blocks_processed: counter!("solindexer_blocks_processed_total"),
             stream_errors: counter!("solindexer_stream_errors_total"),
             db_errors: counter!("solindexer_db_errors_total"),
+            dead_letter_events: counter!("solindexer_dead_letter_events_total"),
             db_write_latency_ms: histogram!("solindexer_db_write_latency_ms"),
             indexer_lag_slots: gauge!("solindexer_indexer_lag_slots"),
+            committed_slot: gauge!("solindexer_committed_slot"),
         }
     }
 
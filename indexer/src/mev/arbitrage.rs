@@ -0,0 +1,96 @@
+//! Cyclic arbitrage detection.
+//!
+//! A transaction whose pool swaps chain into each other (each hop spends what
+//! the previous one bought) and end in the mint the first hop spent is a
+//! cycle; it is reported when the final output exceeds the initial input.
+
+use super::{input_output, profit, TxSummary};
+use crate::parser::{MevEvent, MevKind};
+
+pub(super) fn detect(slot: u64, txs: &[TxSummary]) -> Vec<MevEvent> {
+    let mut events = Vec::new();
+
+    for tx in txs.iter().filter(|t| t.success && t.swaps.len() >= 2) {
+        let hops: Vec<_> = tx.swaps.iter().map(input_output).collect();
+        let chained = hops.windows(2).all(|w| w[0].1 .0 == w[1].0 .0);
+        let (first_in, last_out) = (hops[0].0, hops[hops.len() - 1].1);
+        if !chained || first_in.0 != last_out.0 || last_out.1 <= first_in.1 {
+            continue;
+        }
+
+        events.push(MevEvent {
+            slot,
+            block_time: tx.block_time,
+            kind: MevKind::Arbitrage,
+            searcher: tx.fee_payer.clone().unwrap_or_else(|| tx.swaps[0].user.clone()),
+            signatures: vec![tx.signature.clone()],
+            victim_signatures: vec![],
+            pools: tx.swaps.iter().filter_map(|s| s.pool.clone()).collect(),
+            profit_mint: first_in.0.to_string(),
+            profit_amount: profit(last_out.1, first_in.1),
+        });
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{SwapEvent, TradeSide};
+
+    fn swap(pool: &str, side: TradeSide, base: u64, quote: u64) -> SwapEvent {
+        SwapEvent {
+            signature: "sig".into(),
+            slot: 5,
+            block_time: 1_700_000_000,
+            program: "raydium".into(),
+            pool: Some(pool.into()),
+            route: None,
+            user: "bot".into(),
+            instruction_index: 0,
+            inner_index: None,
+            side,
+            base_mint: "wsol".into(),
+            quote_mint: "usdc".into(),
+            base_amount: base,
+            quote_amount: quote,
+            base_decimals: None,
+            quote_decimals: None,
+            price: None,
+        }
+    }
+
+    fn tx(signature: &str, swaps: Vec<SwapEvent>) -> TxSummary {
+        TxSummary {
+            signature: signature.into(),
+            index: 0,
+            block_time: 1_700_000_000,
+            success: true,
+            fee_payer: Some("bot".into()),
+            tip: None,
+            swaps,
+        }
+    }
+
+    #[test]
+    fn test_profitable_cycle_detected() {
+        let txs = vec![
+            // wsol → usdc on pool a, usdc → more wsol on pool b
+            tx("arb", vec![swap("a", TradeSide::Sell, 1_000, 150), swap("b", TradeSide::Buy, 1_012, 150)]),
+            // Same cycle at a loss
+            tx("loss", vec![swap("a", TradeSide::Sell, 1_000, 150), swap("b", TradeSide::Buy, 990, 150)]),
+            // Hops that do not chain: the second spends wsol, not the usdc bought
+            tx("unchained", vec![swap("a", TradeSide::Sell, 1_000, 150), swap("b", TradeSide::Sell, 10, 2)]),
+        ];
+
+        let events = detect(5, &txs);
+        assert_eq!(events.len(), 1);
+        let e = &events[0];
+        assert_eq!(e.kind, MevKind::Arbitrage);
+        assert_eq!(e.signatures, vec!["arb"]);
+        assert_eq!(e.pools, vec!["a", "b"]);
+        assert_eq!(e.profit_mint, "wsol");
+        assert_eq!(e.profit_amount, 12);
+    }
+}
@@ -0,0 +1,101 @@
+//! Heuristic Jito bundle reconstruction.
+//!
+//! Bundles execute atomically and land as consecutive transactions, usually
+//! signed by the searcher who pays the tip. Each tipping transaction is
+//! grouped with the adjacent successful transactions from the same payer, up
+//! to Jito's bundle size limit.
+
+use super::TxSummary;
+use crate::parser::BundleEvent;
+
+const MAX_BUNDLE_LEN: usize = 5;
+
+/// Group the slot's transactions (sorted by block index) into bundles.
+pub(super) fn detect(slot: u64, txs: &[TxSummary]) -> Vec<BundleEvent> {
+    let mut bundles = Vec::new();
+    let mut assigned = vec![false; txs.len()];
+
+    for (i, tipper) in txs.iter().enumerate() {
+        let Some(tip) = tipper.tip.as_ref().filter(|_| tipper.success && !assigned[i]) else {
+            continue;
+        };
+        let joins = |j: usize, neighbour: usize| {
+            let t = &txs[j];
+            !assigned[j]
+                && t.success
+                && t.index.abs_diff(txs[neighbour].index) == 1
+                && (t.fee_payer.as_ref() == Some(&tip.payer)
+                    || t.tip.as_ref().is_some_and(|other| other.payer == tip.payer))
+        };
+
+        let (mut start, mut end) = (i, i);
+        while start > 0 && end - start + 1 < MAX_BUNDLE_LEN && joins(start - 1, start) {
+            start -= 1;
+        }
+        while end + 1 < txs.len() && end - start + 1 < MAX_BUNDLE_LEN && joins(end + 1, end) {
+            end += 1;
+        }
+
+        let members = &txs[start..=end];
+        assigned[start..=end].iter_mut().for_each(|a| *a = true);
+        bundles.push(BundleEvent {
+            slot,
+            block_time: tipper.block_time,
+            tip_signature: tipper.signature.clone(),
+            tip_payer: tip.payer.clone(),
+            tip_lamports: members
+                .iter()
+                .filter_map(|t| t.tip.as_ref())
+                .filter(|t| t.payer == tip.payer)
+                .map(|t| t.lamports)
+                .sum(),
+            signatures: members.iter().map(|t| t.signature.clone()).collect(),
+            first_index: members[0].index,
+            last_index: members[members.len() - 1].index,
+        });
+    }
+
+    bundles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::jito::JitoTip;
+
+    fn tx(index: u64, payer: &str, tip: Option<u64>) -> TxSummary {
+        TxSummary {
+            signature: format!("sig{index}"),
+            index,
+            block_time: 1_700_000_000,
+            success: true,
+            fee_payer: Some(payer.into()),
+            tip: tip.map(|lamports| JitoTip {
+                payer: payer.into(),
+                lamports,
+            }),
+            swaps: vec![],
+        }
+    }
+
+    #[test]
+    fn test_groups_adjacent_same_payer() {
+        let txs = vec![
+            tx(0, "alice", None),
+            tx(1, "searcher", None),
+            tx(2, "searcher", None),
+            tx(3, "searcher", Some(10_000)),
+            tx(4, "bob", None),
+            tx(6, "searcher", None),
+        ];
+
+        let bundles = detect(7, &txs);
+        assert_eq!(bundles.len(), 1);
+        let b = &bundles[0];
+        assert_eq!(b.tip_signature, "sig3");
+        assert_eq!(b.tip_payer, "searcher");
+        assert_eq!(b.tip_lamports, 10_000);
+        assert_eq!(b.signatures, vec!["sig1", "sig2", "sig3"]);
+        assert_eq!((b.first_index, b.last_index), (1, 3));
+    }
+}
@@ -0,0 +1,121 @@
+//! Cross-transaction MEV analysis.
+//!
+//! Bundles and sandwiches span several transactions, so transactions and
+//! their parsed swaps are buffered per slot and analysed together once the
+//! slot's block update arrives (or once the slot falls too far behind the
+//! stream).
+
+mod arbitrage;
+mod bundles;
+mod sandwich;
+
+use std::collections::BTreeMap;
+
+use crate::parser::jito::JitoTip;
+use crate::parser::{ParsedEvent, SwapEvent, TradeSide};
+use crate::receiver::TransactionUpdate;
+
+/// Slots buffered before the oldest is analysed without its block update.
+const MAX_PENDING_SLOTS: usize = 64;
+
+/// What the detectors need to know about a transaction.
+#[derive(Debug, Clone)]
+pub struct TxSummary {
+    pub signature: String,
+    pub index: u64,
+    pub block_time: i64,
+    pub success: bool,
+    pub fee_payer: Option<String>,
+    pub tip: Option<JitoTip>,
+    /// Pool-level swaps in instruction order.
+    pub swaps: Vec<SwapEvent>,
+}
+
+pub struct MevStage {
+    slots: BTreeMap<u64, Vec<TxSummary>>,
+    /// Highest slot already analysed; later arrivals for it are ignored.
+    finished_slot: Option<u64>,
+}
+
+impl MevStage {
+    pub fn new() -> Self {
+        Self {
+            slots: BTreeMap::new(),
+            finished_slot: None,
+        }
+    }
+
+    /// Buffer a transaction and its parsed events for analysis with the rest
+    /// of its slot.
+    pub fn observe(
+        &mut self,
+        tx: &TransactionUpdate,
+        tip: Option<JitoTip>,
+        events: &[ParsedEvent],
+    ) -> Vec<ParsedEvent> {
+        if self.finished_slot.is_some_and(|s| tx.slot <= s) {
+            return Vec::new();
+        }
+        self.slots.entry(tx.slot).or_default().push(TxSummary {
+            signature: tx.signature.clone(),
+            index: tx.index,
+            block_time: tx.block_time,
+            success: tx.success,
+            fee_payer: tx.accounts.first().cloned(),
+            tip,
+            swaps: pool_swaps(events),
+        });
+
+        if self.slots.len() <= MAX_PENDING_SLOTS {
+            return Vec::new();
+        }
+        let oldest = *self.slots.keys().next().unwrap_or(&tx.slot);
+        self.finish_slot(oldest)
+    }
+
+    /// Analyse every buffered slot up to and including `slot`.
+    pub fn finish_slot(&mut self, slot: u64) -> Vec<ParsedEvent> {
+        let pending = self.slots.split_off(&(slot + 1));
+        let ready = std::mem::replace(&mut self.slots, pending);
+        self.finished_slot = self.finished_slot.max(Some(slot));
+
+        let mut events = Vec::new();
+        for (slot, mut txs) in ready {
+            txs.sort_by_key(|t| t.index);
+            events.extend(bundles::detect(slot, &txs).into_iter().map(ParsedEvent::Bundle));
+            events.extend(sandwich::detect(slot, &txs).into_iter().map(ParsedEvent::Mev));
+            events.extend(arbitrage::detect(slot, &txs).into_iter().map(ParsedEvent::Mev));
+        }
+        events
+    }
+}
+
+/// Swaps against a known pool, ordered as they executed. Aggregator-level
+/// trades carry no pool and are left out.
+fn pool_swaps(events: &[ParsedEvent]) -> Vec<SwapEvent> {
+    let mut swaps: Vec<SwapEvent> = events
+        .iter()
+        .filter_map(|e| match e {
+            ParsedEvent::Swap(s) if s.pool.is_some() => Some(s.clone()),
+            _ => None,
+        })
+        .collect();
+    swaps.sort_by_key(|s| (s.instruction_index, s.inner_index.map_or(0, |i| i + 1)));
+    swaps
+}
+
+/// `((input mint, amount), (output mint, amount))` of a swap from the
+/// trader's point of view.
+fn input_output(s: &SwapEvent) -> ((&str, u64), (&str, u64)) {
+    let base = (s.base_mint.as_str(), s.base_amount);
+    let quote = (s.quote_mint.as_str(), s.quote_amount);
+    match s.side {
+        TradeSide::Buy => (quote, base),
+        TradeSide::Sell => (base, quote),
+    }
+}
+
+/// Difference of two token amounts, clamped to the `i64` range.
+fn profit(received: u64, spent: u64) -> i64 {
+    (received as i128 - spent as i128).clamp(i64::MIN as i128, i64::MAX as i128) as i64
+}
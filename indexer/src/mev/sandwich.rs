@@ -0,0 +1,130 @@
+//! Sandwich detection.
+//!
+//! On each pool, a trader's swap followed later in the slot by their swap in
+//! the opposite direction, with other traders' same-direction swaps in
+//! between, is a sandwich: the front-run moves the price against the victims
+//! and the back-run unwinds the position. Profit is what the back-run returned
+//! of the mint the front-run spent, minus what the front-run spent.
+
+use std::collections::BTreeMap;
+
+use super::{input_output, profit, TxSummary};
+use crate::parser::{MevEvent, MevKind, SwapEvent};
+
+pub(super) fn detect(slot: u64, txs: &[TxSummary]) -> Vec<MevEvent> {
+    // pool → (tx position, swap) in execution order
+    let mut by_pool: BTreeMap<&str, Vec<(usize, &SwapEvent)>> = BTreeMap::new();
+    for (pos, tx) in txs.iter().enumerate().filter(|(_, t)| t.success) {
+        for swap in &tx.swaps {
+            if let Some(pool) = swap.pool.as_deref() {
+                by_pool.entry(pool).or_default().push((pos, swap));
+            }
+        }
+    }
+
+    let mut events = Vec::new();
+    for (pool, swaps) in by_pool {
+        let mut used = vec![false; swaps.len()];
+        for f in 0..swaps.len() {
+            if used[f] {
+                continue;
+            }
+            let (front_pos, front) = swaps[f];
+            // The first later opposite-direction swap by the same trader closes the position.
+            let closes = |&b: &usize| {
+                let (pos, s) = swaps[b];
+                !used[b] && pos != front_pos && s.user == front.user && s.side != front.side
+            };
+            let Some(b) = (f + 1..swaps.len()).find(closes) else {
+                continue;
+            };
+            let (back_pos, back) = swaps[b];
+            let victims: Vec<usize> = swaps[f + 1..b]
+                .iter()
+                .filter(|(pos, _)| ![front_pos, back_pos].contains(pos))
+                .filter(|(_, s)| s.user != front.user && s.side == front.side)
+                .map(|(pos, _)| *pos)
+                .collect();
+            if victims.is_empty() {
+                continue;
+            }
+            used[f] = true;
+            used[b] = true;
+
+            let ((spent_mint, spent), _) = input_output(front);
+            let (_, (_, received)) = input_output(back);
+            let mut victim_signatures: Vec<String> =
+                victims.iter().map(|&p| txs[p].signature.clone()).collect();
+            victim_signatures.dedup();
+            events.push(MevEvent {
+                slot,
+                block_time: txs[front_pos].block_time,
+                kind: MevKind::Sandwich,
+                searcher: front.user.clone(),
+                signatures: vec![txs[front_pos].signature.clone(), txs[back_pos].signature.clone()],
+                victim_signatures,
+                pools: vec![pool.to_string()],
+                profit_mint: spent_mint.to_string(),
+                profit_amount: profit(received, spent),
+            });
+        }
+    }
+
+    events
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::TradeSide;
+
+    fn swap_tx(index: u64, user: &str, side: TradeSide, base: u64, quote: u64) -> TxSummary {
+        TxSummary {
+            signature: format!("sig{index}"),
+            index,
+            block_time: 1_700_000_000,
+            success: true,
+            fee_payer: Some(user.into()),
+            tip: None,
+            swaps: vec![SwapEvent {
+                signature: format!("sig{index}"),
+                slot: 5,
+                block_time: 1_700_000_000,
+                program: "raydium".into(),
+                pool: Some("pool".into()),
+                route: None,
+                user: user.into(),
+                instruction_index: 0,
+                inner_index: None,
+                side,
+                base_mint: "bonk".into(),
+                quote_mint: "wsol".into(),
+                base_amount: base,
+                quote_amount: quote,
+                base_decimals: None,
+                quote_decimals: None,
+                price: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_sandwich_detected() {
+        let txs = vec![
+            swap_tx(0, "bot", TradeSide::Buy, 1_000, 100),
+            swap_tx(1, "alice", TradeSide::Buy, 500, 60),
+            swap_tx(2, "carol", TradeSide::Sell, 200, 20),
+            swap_tx(3, "bot", TradeSide::Sell, 1_000, 108),
+        ];
+
+        let events = detect(5, &txs);
+        assert_eq!(events.len(), 1);
+        let e = &events[0];
+        assert_eq!(e.kind, MevKind::Sandwich);
+        assert_eq!(e.searcher, "bot");
+        assert_eq!(e.signatures, vec!["sig0", "sig3"]);
+        assert_eq!(e.victim_signatures, vec!["sig1"]);
+        assert_eq!(e.profit_mint, "wsol");
+        assert_eq!(e.profit_amount, 8);
+    }
+}
@@ -0,0 +1,55 @@
+//! Embedded schema migrations (`db/migrations`) and the startup schema check.
+
+use anyhow::{bail, Context, Result};
+use sqlx::migrate::Migrator;
+use sqlx::{PgPool, Row};
+use tracing::info;
+
+pub static MIGRATOR: Migrator = sqlx::migrate!("../db/migrations");
+
+/// Apply pending migrations. Fails if the database has migrations this
+/// binary does not know or ones whose contents changed.
+pub async fn run(pool: &PgPool) -> Result<()> {
+    MIGRATOR.run(pool).await.context("schema migration failed")?;
+    info!("Schema up to date (version {})", latest_version());
+    Ok(())
+}
+
+/// Refuse to start unless the database is at exactly the embedded schema.
+pub async fn check(pool: &PgPool) -> Result<()> {
+    let rows = sqlx::query("SELECT version, checksum, success FROM _sqlx_migrations ORDER BY version")
+        .fetch_all(pool)
+        .await
+        .context("schema is not managed by sol-indexer; run `sol-indexer migrate`")?;
+
+    for row in &rows {
+        let version: i64 = row.get("version");
+        let checksum: Vec<u8> = row.get("checksum");
+        if !row.get::<bool, _>("success") {
+            bail!("migration {version} is partially applied; fix it before starting");
+        }
+        match MIGRATOR.iter().find(|m| m.version == version) {
+            None => bail!("database schema version {version} is newer than this binary"),
+            Some(m) if *m.checksum != *checksum => {
+                bail!("migration {version} differs from the one applied to the database")
+            }
+            Some(_) => {}
+        }
+    }
+
+    let pending: Vec<i64> = MIGRATOR
+        .iter()
+        .map(|m| m.version)
+        .filter(|v| !rows.iter().any(|r| r.get::<i64, _>("version") == *v))
+        .collect();
+    if !pending.is_empty() {
+        bail!("pending migrations {pending:?}; run `sol-indexer migrate` or set AUTO_MIGRATE=true");
+    }
+
+    info!("Schema version {} verified", latest_version());
+    Ok(())
+}
+
+fn latest_version() -> i64 {
+    MIGRATOR.iter().map(|m| m.version).max().unwrap_or(0)
+}
@@ -0,0 +1,334 @@
+//! Anchor account decoder driven by program IDLs.
+//!
+//! Every IDL yields one decoder for its program. Accounts are matched by
+//! their 8-byte discriminator and the Borsh body is decoded into JSON
+//! following the IDL's type definitions. Both the legacy (< 0.30) and the
+//! current IDL format are accepted; zero-copy (bytemuck) accounts are not.
+
+use anyhow::{anyhow, Context, Result};
+use serde_json::{json, Map, Value};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::path::Path;
+use tracing::info;
+
+use crate::parser::{AccountParser, AnchorAccountEvent, ParsedEvent};
+use crate::receiver::AccountUpdate;
+
+/// Deepest type nesting decoded; guards against recursive type definitions.
+const MAX_DEPTH: usize = 32;
+
+pub struct AnchorAccountParser {
+    program: String,
+    /// Discriminator and type name of every decodable account.
+    accounts: Vec<([u8; 8], String)>,
+    /// Type definitions (`{ "kind": "struct", .. }`) by name.
+    types: HashMap<String, Value>,
+}
+
+impl AnchorAccountParser {
+    pub fn from_idl(idl: &Value) -> Result<Self> {
+        let program = idl["address"]
+            .as_str()
+            .or_else(|| idl["metadata"]["address"].as_str())
+            .ok_or_else(|| anyhow!("IDL has no program address"))?
+            .to_string();
+
+        let mut types = HashMap::new();
+        let mut zero_copy = Vec::new();
+        for t in idl["types"].as_array().into_iter().flatten() {
+            let Some(name) = t["name"].as_str() else { continue };
+            if t["serialization"].as_str().is_some_and(|s| s != "borsh") {
+                zero_copy.push(name);
+            }
+            types.insert(name.to_string(), t["type"].clone());
+        }
+
+        let mut accounts = Vec::new();
+        for a in idl["accounts"].as_array().into_iter().flatten() {
+            let Some(name) = a["name"].as_str() else { continue };
+            if zero_copy.contains(&name) {
+                continue;
+            }
+            // Legacy IDLs define the account type inline and leave the discriminator implicit.
+            if a["type"].is_object() {
+                types.insert(name.to_string(), a["type"].clone());
+            }
+            let discriminator = match a["discriminator"].as_array() {
+                Some(bytes) => bytes
+                    .iter()
+                    .map(|b| b.as_u64().and_then(|b| u8::try_from(b).ok()))
+                    .collect::<Option<Vec<u8>>>()
+                    .and_then(|b| b.try_into().ok())
+                    .ok_or_else(|| anyhow!("account {name} has an invalid discriminator"))?,
+                None => account_discriminator(name),
+            };
+            accounts.push((discriminator, name.to_string()));
+        }
+
+        Ok(Self { program, accounts, types })
+    }
+
+    /// Load a decoder from every `*.json` IDL in `dir`.
+    pub fn load_dir(dir: &Path) -> Result<Vec<Self>> {
+        let mut parsers = Vec::new();
+        let entries = std::fs::read_dir(dir).with_context(|| format!("reading IDL dir {}", dir.display()))?;
+        for entry in entries {
+            let path = entry?.path();
+            if path.extension().is_none_or(|e| e != "json") {
+                continue;
+            }
+            let raw = std::fs::read(&path).with_context(|| format!("reading IDL {}", path.display()))?;
+            let idl: Value = serde_json::from_slice(&raw).with_context(|| format!("parsing IDL {}", path.display()))?;
+            let parser = Self::from_idl(&idl).with_context(|| format!("loading IDL {}", path.display()))?;
+            info!("Loaded {} Anchor account types for {}", parser.accounts.len(), parser.program);
+            parsers.push(parser);
+        }
+        Ok(parsers)
+    }
+
+    fn account_type(&self, data: &[u8]) -> Option<&str> {
+        let discriminator = data.get(..8)?;
+        self.accounts
+            .iter()
+            .find(|(d, _)| d == discriminator)
+            .map(|(_, name)| name.as_str())
+    }
+
+    fn decode_defined(&self, name: &str, buf: &mut &[u8], depth: usize) -> Option<Value> {
+        let def = self.types.get(name)?;
+        let fields = |fields: &Value, buf: &mut &[u8]| self.decode_fields(fields, buf, depth + 1);
+        match def["kind"].as_str()? {
+            "struct" => fields(&def["fields"], buf),
+            "enum" => {
+                let variant = def["variants"].get(take(buf, 1)?[0] as usize)?;
+                let name = variant["name"].as_str()?;
+                if variant["fields"].as_array().is_none_or(|f| f.is_empty()) {
+                    Some(json!(name))
+                } else {
+                    Some(json!({ name: fields(&variant["fields"], buf)? }))
+                }
+            }
+            "type" => self.decode(&def["alias"], buf, depth + 1),
+            _ => None,
+        }
+    }
+
+    /// Named fields decode to an object, tuple fields to an array.
+    fn decode_fields(&self, fields: &Value, buf: &mut &[u8], depth: usize) -> Option<Value> {
+        let Some(fields) = fields.as_array() else {
+            return Some(json!({}));
+        };
+        if fields.iter().all(|f| f["name"].is_string()) {
+            let mut out = Map::new();
+            for f in fields {
+                out.insert(f["name"].as_str()?.to_string(), self.decode(&f["type"], buf, depth)?);
+            }
+            Some(Value::Object(out))
+        } else {
+            fields.iter().map(|ty| self.decode(ty, buf, depth)).collect()
+        }
+    }
+
+    fn decode(&self, ty: &Value, buf: &mut &[u8], depth: usize) -> Option<Value> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        if let Some(primitive) = ty.as_str() {
+            return decode_primitive(primitive, buf);
+        }
+        if let Some(inner) = ty.get("option") {
+            return match take(buf, 1)?[0] {
+                0 => Some(Value::Null),
+                _ => self.decode(inner, buf, depth + 1),
+            };
+        }
+        if let Some(inner) = ty.get("coption") {
+            // The value is laid out even when the tag says it is absent.
+            let present = u32::from_le_bytes(take(buf, 4)?.try_into().ok()?) != 0;
+            let value = self.decode(inner, buf, depth + 1)?;
+            return Some(if present { value } else { Value::Null });
+        }
+        if let Some(inner) = ty.get("vec") {
+            let len = u32::from_le_bytes(take(buf, 4)?.try_into().ok()?);
+            return (0..len).map(|_| self.decode(inner, buf, depth + 1)).collect();
+        }
+        if let Some(array) = ty.get("array") {
+            let len = array.get(1)?.as_u64()?;
+            return (0..len).map(|_| self.decode(&array[0], buf, depth + 1)).collect();
+        }
+        if let Some(defined) = ty.get("defined") {
+            let name = defined.as_str().or_else(|| defined["name"].as_str())?;
+            return self.decode_defined(name, buf, depth + 1);
+        }
+        None
+    }
+}
+
+/// `sha256("account:<Name>")[..8]`
+fn account_discriminator(name: &str) -> [u8; 8] {
+    let hash = Sha256::digest(format!("account:{name}"));
+    hash[..8].try_into().unwrap_or_default()
+}
+
+fn take<'a>(buf: &mut &'a [u8], n: usize) -> Option<&'a [u8]> {
+    if buf.len() < n {
+        return None;
+    }
+    let (head, rest) = buf.split_at(n);
+    *buf = rest;
+    Some(head)
+}
+
+/// 128-bit integers become strings; JSON numbers cannot hold them.
+fn decode_primitive(name: &str, buf: &mut &[u8]) -> Option<Value> {
+    macro_rules! le {
+        ($t:ty) => {
+            <$t>::from_le_bytes(take(buf, std::mem::size_of::<$t>())?.try_into().ok()?)
+        };
+    }
+    Some(match name {
+        "bool" => json!(take(buf, 1)?[0] != 0),
+        "u8" => json!(le!(u8)),
+        "i8" => json!(le!(i8)),
+        "u16" => json!(le!(u16)),
+        "i16" => json!(le!(i16)),
+        "u32" => json!(le!(u32)),
+        "i32" => json!(le!(i32)),
+        "u64" => json!(le!(u64)),
+        "i64" => json!(le!(i64)),
+        "f32" => json!(le!(f32)),
+        "f64" => json!(le!(f64)),
+        "u128" => json!(le!(u128).to_string()),
+        "i128" => json!(le!(i128).to_string()),
+        "publicKey" | "pubkey" => json!(bs58::encode(take(buf, 32)?).into_string()),
+        "string" => {
+            let len = le!(u32) as usize;
+            json!(String::from_utf8_lossy(take(buf, len)?))
+        }
+        "bytes" => {
+            let len = le!(u32) as usize;
+            json!(hex::encode(take(buf, len)?))
+        }
+        _ => return None,
+    })
+}
+
+impl AccountParser for AnchorAccountParser {
+    fn owner(&self) -> &str {
+        &self.program
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        self.account_type(data).is_some()
+    }
+
+    /// Accounts that fail to decode (e.g. after a layout change the IDL does
+    /// not know about) produce no event.
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        let Some(account_type) = self.account_type(&account.data) else {
+            return Vec::new();
+        };
+        let body = &mut &account.data[8..];
+        self.decode_defined(account_type, body, 0)
+            .map(|data| {
+                ParsedEvent::AnchorAccount(AnchorAccountEvent {
+                    address: account.pubkey.clone(),
+                    slot: account.slot,
+                    program: self.program.clone(),
+                    account_type: account_type.to_string(),
+                    data,
+                })
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PROGRAM: &str = "Pool111111111111111111111111111111111111111";
+
+    #[test]
+    fn test_decode_legacy_idl_account() {
+        let idl = json!({
+            "metadata": { "address": PROGRAM },
+            "accounts": [{
+                "name": "Pool",
+                "type": { "kind": "struct", "fields": [
+                    { "name": "authority", "type": "publicKey" },
+                    { "name": "fees", "type": { "array": ["u16", 2] } },
+                    { "name": "label", "type": { "option": "string" } },
+                    { "name": "status", "type": { "defined": "Status" } },
+                    { "name": "reserves", "type": { "vec": "u128" } }
+                ]}
+            }],
+            "types": [{
+                "name": "Status",
+                "type": { "kind": "enum", "variants": [
+                    { "name": "Active" },
+                    { "name": "Paused", "fields": [{ "name": "until", "type": "i64" }] }
+                ]}
+            }]
+        });
+        let parser = AnchorAccountParser::from_idl(&idl).unwrap();
+
+        let mut data = account_discriminator("Pool").to_vec();
+        data.extend_from_slice(&[7u8; 32]);
+        data.extend_from_slice(&30u16.to_le_bytes());
+        data.extend_from_slice(&5u16.to_le_bytes());
+        data.extend_from_slice(&[1, 3, 0, 0, 0]);
+        data.extend_from_slice(b"SOL");
+        data.push(1);
+        data.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&(u64::MAX as u128 + 1).to_le_bytes());
+        data.extend_from_slice(&[0u8; 16]); // unused account space
+
+        let events = parser.parse(&AccountUpdate {
+            pubkey: "pool".into(),
+            slot: 1,
+            owner: PROGRAM.into(),
+            lamports: 1,
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: 0,
+        });
+        let [ParsedEvent::AnchorAccount(a)] = events.as_slice() else {
+            panic!("expected an Anchor account, got {events:?}");
+        };
+        assert_eq!(a.account_type, "Pool");
+        assert_eq!(
+            a.data,
+            json!({
+                "authority": bs58::encode([7u8; 32]).into_string(),
+                "fees": [30, 5],
+                "label": "SOL",
+                "status": { "Paused": { "until": 1_700_000_000i64 } },
+                "reserves": ["18446744073709551616"]
+            })
+        );
+    }
+
+    #[test]
+    fn test_current_idl_discriminators_and_zero_copy() {
+        let idl = json!({
+            "address": PROGRAM,
+            "accounts": [
+                { "name": "Config", "discriminator": [1, 2, 3, 4, 5, 6, 7, 8] },
+                { "name": "Book", "discriminator": [8, 7, 6, 5, 4, 3, 2, 1] }
+            ],
+            "types": [
+                { "name": "Config", "type": { "kind": "struct", "fields": [{ "name": "admin", "type": "pubkey" }] } },
+                { "name": "Book", "serialization": "bytemuck", "type": { "kind": "struct", "fields": [] } }
+            ]
+        });
+        let parser = AnchorAccountParser::from_idl(&idl).unwrap();
+        assert_eq!(parser.owner(), PROGRAM);
+        assert!(parser.matches(&[1, 2, 3, 4, 5, 6, 7, 8, 0]));
+        assert!(!parser.matches(&[8, 7, 6, 5, 4, 3, 2, 1, 0]));
+    }
+}
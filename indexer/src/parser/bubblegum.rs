@@ -0,0 +1,239 @@
+//! Compressed NFT parser (Metaplex Bubblegum).
+//!
+//! Bubblegum does not store leaves on chain; the new leaf and the Merkle path
+//! it produced are only visible through the `spl-noop` CPIs issued by
+//! Bubblegum (leaf schema) and by spl-account-compression (change log).
+
+use std::io;
+
+use crate::parser::nft::{de, pubkey, trim};
+use crate::parser::{
+    CnftAction, CnftEvent, CnftMetadata, LeafSchema, ParsedEvent, Parser, PathNode,
+    BUBBLEGUM_PROGRAM_ID, SPL_NOOP_PROGRAM_ID,
+};
+use crate::receiver::TransactionUpdate;
+
+pub struct BubblegumParser;
+
+impl BubblegumParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Anchor instruction discriminators (first 8 bytes of sha256("global:<name>"))
+const IX_MINT_V1: [u8; 8] = [0x91, 0x62, 0xc0, 0x76, 0xb8, 0x93, 0x76, 0x68];
+const IX_MINT_TO_COLLECTION_V1: [u8; 8] = [0x99, 0x12, 0xb2, 0x2f, 0xc5, 0x9e, 0x56, 0x0f];
+pub(crate) const IX_TRANSFER: [u8; 8] = [0xa3, 0x34, 0xc8, 0xe7, 0x8c, 0x03, 0x45, 0xba];
+const IX_BURN: [u8; 8] = [0x74, 0x6e, 0x1d, 0x38, 0x6b, 0xdb, 0x2a, 0x5d];
+const IX_REDEEM: [u8; 8] = [0xb8, 0x0c, 0x56, 0x95, 0x46, 0xc4, 0x61, 0xe1];
+const IX_DELEGATE: [u8; 8] = [0x5a, 0x93, 0x4b, 0xb2, 0x55, 0x58, 0x04, 0x89];
+
+// `AccountCompressionEvent` variants logged through spl-noop
+const EVENT_CHANGE_LOG: u8 = 0;
+pub(crate) const EVENT_APPLICATION_DATA: u8 = 1;
+// `BubblegumEventType::LeafSchemaEvent`
+const BUBBLEGUM_LEAF_SCHEMA_EVENT: u8 = 1;
+
+struct ChangeLog {
+    tree: String,
+    path: Vec<PathNode>,
+    seq: u64,
+    index: u32,
+}
+
+/// `AccountCompressionEvent::ChangeLog(ChangeLogEvent::V1 { id, path, seq, index })`
+fn decode_change_log(data: &[u8]) -> io::Result<ChangeLog> {
+    let buf = &mut &data[2..];
+    let tree: [u8; 32] = de(buf)?;
+    let path: Vec<([u8; 32], u32)> = de(buf)?;
+    let seq: u64 = de(buf)?;
+    let index: u32 = de(buf)?;
+    Ok(ChangeLog {
+        tree: pubkey(tree),
+        path: path
+            .into_iter()
+            .map(|(hash, index)| PathNode { index, hash })
+            .collect(),
+        seq,
+        index,
+    })
+}
+
+/// `AccountCompressionEvent::ApplicationData(V1 { application_data })` carrying a
+/// Bubblegum `LeafSchemaEvent { version, schema: LeafSchema::V1 { .. } }`.
+pub(crate) fn decode_leaf_schema(data: &[u8]) -> io::Result<Option<LeafSchema>> {
+    let buf = &mut &data[2..];
+    let app_data: Vec<u8> = de(buf)?;
+    let buf = &mut app_data.as_slice();
+    let (event_type, _version, _schema_version): (u8, u8, u8) = de(buf)?;
+    if event_type != BUBBLEGUM_LEAF_SCHEMA_EVENT {
+        return Ok(None);
+    }
+    let (asset_id, owner, delegate): ([u8; 32], [u8; 32], [u8; 32]) = de(buf)?;
+    Ok(Some(LeafSchema {
+        asset_id: pubkey(asset_id),
+        owner: pubkey(owner),
+        delegate: pubkey(delegate),
+        nonce: de(buf)?,
+        data_hash: de(buf)?,
+        creator_hash: de(buf)?,
+    }))
+}
+
+/// Leading fields of `MetadataArgs`, up to and including `collection`.
+fn decode_metadata_args(data: &[u8]) -> io::Result<CnftMetadata> {
+    let buf = &mut &data[8..];
+    let name = trim(de(buf)?);
+    let symbol = trim(de(buf)?);
+    let uri = trim(de(buf)?);
+    let _: (u16, bool, bool, Option<u8>, Option<u8>) = de(buf)?;
+    let collection: Option<(bool, [u8; 32])> = de(buf)?;
+    Ok(CnftMetadata {
+        name,
+        symbol,
+        uri,
+        collection: collection.map(|(_, key)| pubkey(key)),
+        collection_verified: collection.is_some_and(|(verified, _)| verified),
+    })
+}
+
+impl Parser for BubblegumParser {
+    fn program_id(&self) -> &str {
+        BUBBLEGUM_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // A failed transaction leaves the tree and its leaves unchanged.
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            if ix.program_id != BUBBLEGUM_PROGRAM_ID || ix.data.len() < 8 {
+                continue;
+            }
+            let action = match ix.data[..8].try_into().unwrap_or_default() {
+                IX_MINT_V1 | IX_MINT_TO_COLLECTION_V1 => CnftAction::Mint,
+                IX_TRANSFER => CnftAction::Transfer,
+                IX_BURN => CnftAction::Burn,
+                IX_REDEEM => CnftAction::Redeem,
+                IX_DELEGATE => CnftAction::Delegate,
+                _ => continue,
+            };
+
+            // The noop logs for this instruction follow it, up to the next
+            // Bubblegum instruction of the same outer instruction.
+            let mut change_log = None;
+            let mut leaf = None;
+            for inner in tx.instructions[pos + 1..]
+                .iter()
+                .take_while(|i| i.instruction_index == ix.instruction_index && i.inner_index.is_some())
+                .take_while(|i| i.program_id != BUBBLEGUM_PROGRAM_ID)
+                .filter(|i| i.program_id == SPL_NOOP_PROGRAM_ID && i.data.len() > 2)
+            {
+                match inner.data[0] {
+                    EVENT_CHANGE_LOG if change_log.is_none() => {
+                        change_log = decode_change_log(&inner.data).ok();
+                    }
+                    EVENT_APPLICATION_DATA if leaf.is_none() => {
+                        leaf = decode_leaf_schema(&inner.data).ok().flatten();
+                    }
+                    _ => {}
+                }
+            }
+            let Some(change_log) = change_log else {
+                continue;
+            };
+
+            let metadata = match action {
+                CnftAction::Mint => decode_metadata_args(&ix.data).ok().map(|mut m| {
+                    // mint_to_collection_v1 verifies the collection mint at accounts[8].
+                    if ix.data[..8] == IX_MINT_TO_COLLECTION_V1 {
+                        m.collection = ix.accounts.get(8).cloned().or(m.collection);
+                        m.collection_verified = true;
+                    }
+                    m
+                }),
+                _ => None,
+            };
+
+            events.push(ParsedEvent::Cnft(CnftEvent {
+                signature: tx.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.block_time,
+                action,
+                tree: change_log.tree,
+                leaf_index: change_log.index,
+                seq: change_log.seq,
+                leaf,
+                path: change_log.path,
+                metadata,
+            }));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::RawInstruction;
+
+    fn ix(program_id: &str, inner_index: Option<u32>, data: Vec<u8>, accounts: &[&str]) -> RawInstruction {
+        RawInstruction {
+            program_id: program_id.into(),
+            instruction_index: 0,
+            inner_index,
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    #[test]
+    fn test_mint_to_collection_decoded() {
+        let (tree, collection, owner) = ([1u8; 32], [2u8; 32], [3u8; 32]);
+
+        // MetadataArgs up to the (unverified) collection, then a Bubblegum-only tail
+        let mut mint = IX_MINT_TO_COLLECTION_V1.to_vec();
+        let args = ("Tiny #7".to_string(), "TINY".to_string(), "https://x/7.json".to_string());
+        mint.extend(borsh::to_vec(&args).unwrap());
+        mint.extend(borsh::to_vec(&(500u16, false, true, None::<u8>, Some(0u8))).unwrap());
+        mint.extend(borsh::to_vec(&Some((false, collection))).unwrap());
+        mint.extend([0u8; 8]);
+
+        let mut change_log = vec![EVENT_CHANGE_LOG, 0];
+        change_log.extend(borsh::to_vec(&(tree, vec![([9u8; 32], 16u32), ([8u8; 32], 1)], 42u64, 0u32)).unwrap());
+
+        let mut schema = vec![BUBBLEGUM_LEAF_SCHEMA_EVENT, 1, 0];
+        schema.extend(borsh::to_vec(&([4u8; 32], owner, owner, 0u64, [5u8; 32], [6u8; 32])).unwrap());
+        let mut leaf = vec![EVENT_APPLICATION_DATA, 0];
+        leaf.extend(borsh::to_vec(&schema).unwrap());
+
+        let accounts = ["config", "owner", "delegate", "tree", "payer", "tree_auth", "coll_auth", "rec", "coll_mint"];
+        let mut tx = TransactionUpdate::test(vec![
+            ix(BUBBLEGUM_PROGRAM_ID, None, mint, &accounts),
+            ix(SPL_NOOP_PROGRAM_ID, Some(0), leaf, &[]),
+            ix(SPL_NOOP_PROGRAM_ID, Some(1), change_log, &[]),
+        ]);
+
+        let events = BubblegumParser::new().parse(&tx);
+        let [ParsedEvent::Cnft(e)] = events.as_slice() else {
+            panic!("expected one cNFT event, got {events:?}");
+        };
+        assert!(matches!(e.action, CnftAction::Mint));
+        assert_eq!((e.tree.clone(), e.leaf_index, e.seq), (pubkey(tree), 0, 42));
+        assert_eq!(e.path.iter().map(|n| n.index).collect::<Vec<_>>(), [16, 1]);
+        assert_eq!(e.leaf.as_ref().map(|l| l.owner.clone()), Some(pubkey(owner)));
+        let metadata = e.metadata.as_ref().unwrap();
+        assert_eq!((metadata.name.as_str(), metadata.uri.as_str()), ("Tiny #7", "https://x/7.json"));
+        // mint_to_collection_v1 verifies the collection passed in its accounts
+        assert_eq!(metadata.collection.as_deref(), Some("coll_mint"));
+        assert!(metadata.collection_verified);
+
+        tx.success = false;
+        assert!(BubblegumParser::new().parse(&tx).is_empty());
+    }
+}
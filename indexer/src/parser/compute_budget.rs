@@ -0,0 +1,109 @@
+//! ComputeBudget program decoding and priority fee calculation.
+//!
+//! Compute budget instructions configure the whole transaction rather than
+//! producing events of their own, so they are read when the transaction
+//! event is built instead of through a registered parser.
+
+use crate::parser::COMPUTE_BUDGET_PROGRAM_ID;
+use crate::receiver::TransactionUpdate;
+
+// ComputeBudget instruction discriminants
+const IX_SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const IX_SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+/// Compute units granted to each non-ComputeBudget instruction when the
+/// transaction does not set a limit.
+const DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT: u32 = 200_000;
+const MAX_COMPUTE_UNIT_LIMIT: u32 = 1_400_000;
+const MICRO_LAMPORTS_PER_LAMPORT: u128 = 1_000_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputeBudget {
+    /// Requested compute units (the runtime default if no limit was set).
+    pub compute_unit_limit: u32,
+    /// Price per compute unit in micro-lamports.
+    pub compute_unit_price: u64,
+}
+
+impl ComputeBudget {
+    /// Decode the budget requested by a transaction's top-level instructions.
+    pub fn from_transaction(tx: &TransactionUpdate) -> Self {
+        let mut limit = None;
+        let mut price = 0;
+        let mut other_instructions = 0u32;
+
+        for ix in tx.instructions.iter().filter(|ix| ix.inner_index.is_none()) {
+            if ix.program_id != COMPUTE_BUDGET_PROGRAM_ID {
+                other_instructions += 1;
+                continue;
+            }
+            match ix.data.split_first() {
+                Some((&IX_SET_COMPUTE_UNIT_LIMIT, args)) => {
+                    limit = args.get(..4).and_then(|b| b.try_into().ok()).map(u32::from_le_bytes);
+                }
+                Some((&IX_SET_COMPUTE_UNIT_PRICE, args)) => {
+                    price = args
+                        .get(..8)
+                        .and_then(|b| b.try_into().ok())
+                        .map(u64::from_le_bytes)
+                        .unwrap_or_default();
+                }
+                _ => {}
+            }
+        }
+
+        let limit = limit
+            .unwrap_or(other_instructions.saturating_mul(DEFAULT_INSTRUCTION_COMPUTE_UNIT_LIMIT))
+            .min(MAX_COMPUTE_UNIT_LIMIT);
+        Self {
+            compute_unit_limit: limit,
+            compute_unit_price: price,
+        }
+    }
+
+    /// Priority fee in lamports: price × requested units, rounded up.
+    pub fn priority_fee(&self) -> u64 {
+        let micro_lamports = self.compute_unit_price as u128 * self.compute_unit_limit as u128;
+        micro_lamports.div_ceil(MICRO_LAMPORTS_PER_LAMPORT).min(u64::MAX as u128) as u64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::RawInstruction;
+
+    #[test]
+    fn test_priority_fee() {
+        let ix = |program_id: &str, data: Vec<u8>| RawInstruction {
+            program_id: program_id.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: vec![],
+        };
+        let mut limit = vec![IX_SET_COMPUTE_UNIT_LIMIT];
+        limit.extend_from_slice(&300_000u32.to_le_bytes());
+        let mut price = vec![IX_SET_COMPUTE_UNIT_PRICE];
+        price.extend_from_slice(&50_001u64.to_le_bytes());
+        let mut tx = TransactionUpdate::test(vec![
+            ix(COMPUTE_BUDGET_PROGRAM_ID, limit),
+            ix(COMPUTE_BUDGET_PROGRAM_ID, price),
+            ix("JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4", vec![]),
+        ]);
+        tx.fee = 20_001;
+        tx.compute_units = 120_000;
+
+        let budget = ComputeBudget::from_transaction(&tx);
+        assert_eq!(budget.compute_unit_limit, 300_000);
+        assert_eq!(budget.compute_unit_price, 50_001);
+        // 50_001 * 300_000 = 15_000_300_000 micro-lamports
+        assert_eq!(budget.priority_fee(), 15_001);
+
+        // Without a limit, each other instruction gets the default.
+        tx.instructions.remove(0);
+        let budget = ComputeBudget::from_transaction(&tx);
+        assert_eq!(budget.compute_unit_limit, 200_000);
+        assert_eq!(budget.priority_fee(), 10_001);
+    }
+}
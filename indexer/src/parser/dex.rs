@@ -0,0 +1,190 @@
+//! Shared DEX helpers — base/quote orientation, price computation and
+//! resolution of swap legs from inner token transfers.
+
+use crate::parser::{
+    SwapEvent, TradeSide, JUPITER_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID, SPL_TOKEN_PROGRAM_ID,
+    USDC_MINT, USDT_MINT, WSOL_MINT,
+};
+use crate::receiver::{RawInstruction, TransactionUpdate};
+
+/// Mints preferred as the quote side of a pair, most preferred first.
+const QUOTE_PRIORITY: [&str; 3] = [USDC_MINT, USDT_MINT, WSOL_MINT];
+
+/// Aggregators whose routes are recorded as trades of their own.
+const AGGREGATORS: [&str; 1] = [JUPITER_PROGRAM_ID];
+
+/// One side of a swap as seen by the user.
+#[derive(Debug, Clone)]
+pub struct Leg {
+    pub mint: String,
+    pub amount: u64,
+    pub decimals: Option<u8>,
+}
+
+/// Returns true if `a` is the base mint of the `(a, b)` pair.
+///
+/// The quote is whichever mint ranks higher in `QUOTE_PRIORITY`; pairs with
+/// no ranked mint fall back to lexicographic order so orientation is stable.
+pub fn orient(a: &str, b: &str) -> bool {
+    let rank = |m: &str| QUOTE_PRIORITY.iter().position(|q| *q == m);
+    match (rank(a), rank(b)) {
+        (Some(ra), Some(rb)) => ra > rb,
+        (Some(_), None) => false,
+        (None, Some(_)) => true,
+        (None, None) => a <= b,
+    }
+}
+
+/// Decimal-adjusted quote-per-base price.
+pub fn price(base_amount: u64, quote_amount: u64, base_decimals: u8, quote_decimals: u8) -> Option<f64> {
+    if base_amount == 0 {
+        return None;
+    }
+    let base = base_amount as f64 / 10f64.powi(base_decimals as i32);
+    let quote = quote_amount as f64 / 10f64.powi(quote_decimals as i32);
+    Some(quote / base)
+}
+
+/// Aggregator that invoked `ix` as a hop of its route, if any.
+fn route(tx: &TransactionUpdate, ix: &RawInstruction, program: &str) -> Option<String> {
+    ix.inner_index?;
+    let outer = tx
+        .instructions
+        .iter()
+        .find(|i| i.instruction_index == ix.instruction_index && i.inner_index.is_none())?;
+    (outer.program_id != program && AGGREGATORS.contains(&outer.program_id.as_str()))
+        .then(|| outer.program_id.clone())
+}
+
+/// Build a normalised `SwapEvent` from the user's input and output legs.
+pub fn build_swap(
+    tx: &TransactionUpdate,
+    ix: &RawInstruction,
+    program: &str,
+    pool: Option<String>,
+    user: String,
+    input: Leg,
+    output: Leg,
+) -> SwapEvent {
+    let (side, base, quote) = if orient(&output.mint, &input.mint) {
+        (TradeSide::Buy, output, input)
+    } else {
+        (TradeSide::Sell, input, output)
+    };
+    let price = match (base.decimals, quote.decimals) {
+        (Some(bd), Some(qd)) => price(base.amount, quote.amount, bd, qd),
+        _ => None,
+    };
+
+    SwapEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        program: program.into(),
+        route: pool.as_ref().and_then(|_| route(tx, ix, program)),
+        pool,
+        user,
+        instruction_index: ix.instruction_index,
+        inner_index: ix.inner_index,
+        side,
+        base_mint: base.mint,
+        quote_mint: quote.mint,
+        base_amount: base.amount,
+        quote_amount: quote.amount,
+        base_decimals: base.decimals,
+        quote_decimals: quote.decimals,
+        price,
+    }
+}
+
+/// An SPL token transfer invoked by another instruction.
+#[derive(Debug, Clone)]
+pub struct InnerTransfer {
+    pub source: String,
+    pub destination: String,
+    pub amount: u64,
+}
+
+/// Collect the SPL token transfers invoked after the instruction at `pos`
+/// within the same outer instruction.
+pub fn inner_transfers(tx: &TransactionUpdate, pos: usize) -> Vec<InnerTransfer> {
+    let Some(ix) = tx.instructions.get(pos) else {
+        return Vec::new();
+    };
+    tx.instructions[pos + 1..]
+        .iter()
+        .take_while(|i| i.instruction_index == ix.instruction_index && i.inner_index.is_some())
+        .filter(|i| i.program_id == SPL_TOKEN_PROGRAM_ID || i.program_id == SPL_TOKEN_2022_PROGRAM_ID)
+        .filter_map(|i| {
+            let amount = u64::from_le_bytes(i.data.get(1..9)?.try_into().ok()?);
+            match i.data[0] {
+                // transfer: [source, destination, authority]
+                3 => Some(InnerTransfer {
+                    source: i.accounts.first()?.clone(),
+                    destination: i.accounts.get(1)?.clone(),
+                    amount,
+                }),
+                // transferChecked: [source, mint, destination, authority]
+                12 => Some(InnerTransfer {
+                    source: i.accounts.first()?.clone(),
+                    destination: i.accounts.get(2)?.clone(),
+                    amount,
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+/// Resolve a leg from the user's token account and the amount moved.
+pub fn leg(tx: &TransactionUpdate, token_account_key: &str, amount: u64) -> Option<Leg> {
+    let balance = tx.token_balance(token_account_key)?;
+    Some(Leg {
+        mint: balance.mint.clone(),
+        amount,
+        decimals: Some(balance.decimals),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::RAYDIUM_AMM_PROGRAM_ID;
+
+    #[test]
+    fn test_quote_priority() {
+        assert!(orient("BONK", USDC_MINT));
+        assert!(!orient(USDC_MINT, "BONK"));
+        assert!(orient(WSOL_MINT, USDC_MINT));
+        assert!(orient("AAA", "BBB"));
+        assert!(!orient("BBB", "AAA"));
+    }
+
+    #[test]
+    fn test_price_is_decimal_adjusted() {
+        // 2 SOL (9 decimals) for 300 USDC (6 decimals) → 150 USDC/SOL
+        let p = price(2_000_000_000, 300_000_000, 9, 6).unwrap();
+        assert!((p - 150.0).abs() < 1e-9);
+        assert!(price(0, 1, 9, 6).is_none());
+    }
+
+    #[test]
+    fn test_aggregator_hops_are_tagged() {
+        let ix = |program_id: &str, inner_index| RawInstruction {
+            program_id: program_id.into(),
+            instruction_index: 0,
+            inner_index,
+            data: vec![],
+            accounts: vec![],
+        };
+        let tx = TransactionUpdate::test(vec![ix(JUPITER_PROGRAM_ID, None), ix(RAYDIUM_AMM_PROGRAM_ID, Some(0))]);
+        let leg = |mint: &str| Leg { mint: mint.into(), amount: 1, decimals: None };
+        let swap = |pos: usize, program, pool: Option<&str>| {
+            let (input, output) = (leg(USDC_MINT), leg("BONK"));
+            build_swap(&tx, &tx.instructions[pos], program, pool.map(Into::into), "user".into(), input, output)
+        };
+
+        assert_eq!(swap(1, RAYDIUM_AMM_PROGRAM_ID, Some("pool")).route.as_deref(), Some(JUPITER_PROGRAM_ID));
+        assert_eq!(swap(0, JUPITER_PROGRAM_ID, None).route, None);
+    }
+}
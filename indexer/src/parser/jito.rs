@@ -0,0 +1,95 @@
+//! Jito tip detection.
+//!
+//! Tips are plain System transfers to one of the Jito tip accounts, so they
+//! are read when the transaction event is built rather than by a parser.
+
+use crate::parser::{system, JITO_TIP_ACCOUNTS, SYSTEM_PROGRAM_ID};
+use crate::receiver::TransactionUpdate;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JitoTip {
+    /// Source of the (first) tip transfer.
+    pub payer: String,
+    pub lamports: u64,
+}
+
+/// Total lamports a transaction transferred to Jito tip accounts, including
+/// transfers made through CPI. A failed transaction transferred nothing.
+pub fn jito_tip(tx: &TransactionUpdate) -> Option<JitoTip> {
+    if !tx.success {
+        return None;
+    }
+    let mut tip: Option<JitoTip> = None;
+    for ix in tx.instructions.iter().filter(|ix| ix.program_id == SYSTEM_PROGRAM_ID) {
+        let (Some(tag), Some(lamports)) = (ix.data.get(..4), ix.data.get(4..12)) else {
+            continue;
+        };
+        if u32::from_le_bytes(tag.try_into().unwrap_or_default()) != system::IX_TRANSFER {
+            continue;
+        }
+        let (Some(source), Some(destination)) = (ix.accounts.first(), ix.accounts.get(1)) else {
+            continue;
+        };
+        if !JITO_TIP_ACCOUNTS.contains(&destination.as_str()) {
+            continue;
+        }
+        let lamports = u64::from_le_bytes(lamports.try_into().unwrap_or_default());
+        match &mut tip {
+            Some(t) => t.lamports = t.lamports.saturating_add(lamports),
+            None => {
+                tip = Some(JitoTip {
+                    payer: source.clone(),
+                    lamports,
+                })
+            }
+        }
+    }
+    tip
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::RawInstruction;
+
+    fn transfer(inner_index: Option<u32>, from: &str, to: &str, lamports: u64) -> RawInstruction {
+        let mut data = system::IX_TRANSFER.to_le_bytes().to_vec();
+        data.extend_from_slice(&lamports.to_le_bytes());
+        RawInstruction {
+            program_id: SYSTEM_PROGRAM_ID.into(),
+            instruction_index: inner_index.map_or(0, |_| 1),
+            inner_index,
+            data,
+            accounts: vec![from.into(), to.into()],
+        }
+    }
+
+    #[test]
+    fn test_tips_summed_across_cpi() {
+        let mut tx = TransactionUpdate::test(vec![
+            transfer(None, "searcher", JITO_TIP_ACCOUNTS[0], 10_000),
+            transfer(None, "searcher", "bob", 1_000_000),
+            // Tip paid by a program on the searcher's behalf
+            transfer(Some(0), "vault", JITO_TIP_ACCOUNTS[3], 5_000),
+        ]);
+        tx.accounts = vec!["searcher".into()];
+
+        let tip = jito_tip(&tx);
+        assert_eq!(
+            tip,
+            Some(JitoTip {
+                payer: "searcher".into(),
+                lamports: 15_000
+            })
+        );
+        tx.success = false;
+        assert_eq!(jito_tip(&tx), None);
+
+        let untipped = TransactionUpdate {
+            instructions: vec![transfer(None, "searcher", "bob", 1_000_000)],
+            success: true,
+            ..tx
+        };
+        assert_eq!(jito_tip(&untipped), None);
+    }
+}
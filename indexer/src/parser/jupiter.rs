@@ -1,7 +1,12 @@
 //! Jupiter swap parser.
+//!
+//! Jupiter v6 emits an Anchor `SwapEvent` via self-CPI for every hop of a
+//! route. The hops of one route are collapsed into a single aggregator-level
+//! trade from the first hop's input to the last hop's output.
 
-use crate::parser::{ParsedEvent, Parser, SwapEvent, JUPITER_PROGRAM_ID};
-use crate::receiver::TransactionUpdate;
+use crate::parser::dex::{self, Leg};
+use crate::parser::{ParsedEvent, Parser, JUPITER_PROGRAM_ID};
+use crate::receiver::{RawInstruction, TransactionUpdate};
 
 pub struct JupiterParser;
 
@@ -11,8 +16,35 @@ impl JupiterParser {
     }
 }
 
-// Jupiter v6 route instruction discriminator (first 8 bytes of sha256("global:route"))
-const ROUTE_DISCRIMINATOR: [u8; 8] = [0xe5, 0x17, 0xcb, 0x97, 0x7a, 0xe3, 0xad, 0x2a];
+// Anchor self-CPI event instruction tag (sha256("anchor:event")[..8], little-endian)
+const EVENT_IX_TAG: [u8; 8] = [0xe4, 0x45, 0xa5, 0x2e, 0x51, 0xcb, 0x9a, 0x1d];
+// First 8 bytes of sha256("event:SwapEvent")
+const SWAP_EVENT_DISCRIMINATOR: [u8; 8] = [0x40, 0xc6, 0xcd, 0xe8, 0x26, 0x08, 0x71, 0xe2];
+// tag(8) + discriminator(8) + amm(32) + input_mint(32) + input_amount(8) + output_mint(32) + output_amount(8)
+const SWAP_EVENT_LEN: usize = 128;
+
+/// One hop of a route, as logged by Jupiter.
+struct Hop {
+    input_mint: String,
+    input_amount: u64,
+    output_mint: String,
+    output_amount: u64,
+}
+
+fn decode_hop(ix: &RawInstruction) -> Option<Hop> {
+    let d = &ix.data;
+    if d.len() < SWAP_EVENT_LEN || d[..8] != EVENT_IX_TAG || d[8..16] != SWAP_EVENT_DISCRIMINATOR {
+        return None;
+    }
+    let key = |off: usize| bs58::encode(&d[off..off + 32]).into_string();
+    let amount = |off: usize| u64::from_le_bytes(d[off..off + 8].try_into().unwrap_or_default());
+    Some(Hop {
+        input_mint: key(48),
+        input_amount: amount(80),
+        output_mint: key(88),
+        output_amount: amount(120),
+    })
+}
 
 impl Parser for JupiterParser {
     fn program_id(&self) -> &str {
@@ -20,33 +52,98 @@ impl Parser for JupiterParser {
     }
 
     fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // Swap events are logged before a later hop can revert the route.
+        if !tx.success {
+            return Vec::new();
+        }
         let mut events = Vec::new();
 
-        for ix in &tx.instructions {
-            if ix.program_id != JUPITER_PROGRAM_ID {
-                continue;
-            }
-            if ix.data.len() < 8 {
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            // Route instructions are the non-event Jupiter invocations.
+            if ix.program_id != JUPITER_PROGRAM_ID || ix.data.len() < 8 || ix.data[..8] == EVENT_IX_TAG {
                 continue;
             }
-            if ix.data[..8] != ROUTE_DISCRIMINATOR {
+            let hops: Vec<Hop> = tx.instructions[pos + 1..]
+                .iter()
+                .take_while(|i| i.instruction_index == ix.instruction_index && i.inner_index.is_some())
+                .filter(|i| i.program_id == JUPITER_PROGRAM_ID)
+                .filter_map(decode_hop)
+                .collect();
+            let (Some(first), Some(last)) = (hops.first(), hops.last()) else {
                 continue;
-            }
-            // Best-effort: accounts[0] = token_program, accounts[1] = user authority,
-            // accounts[2] = user source token account, accounts[3] = user dest token account.
-            // Full ABI decode would use Anchor IDL; this is a simplified version.
-            events.push(ParsedEvent::Swap(SwapEvent {
-                signature: tx.signature.clone(),
-                block_time: tx.block_time,
-                program: JUPITER_PROGRAM_ID.into(),
-                input_mint: ix.accounts.get(2).cloned().unwrap_or_default(),
-                output_mint: ix.accounts.get(3).cloned().unwrap_or_default(),
-                input_amount: 0,  // would decode from ix.data
-                output_amount: 0, // would decode from ix.data
-                user: ix.accounts.get(1).cloned().unwrap_or_default(),
-            }));
+            };
+
+            // Best-effort: accounts[1] is the user authority for the route variants.
+            let user = ix.accounts.get(1).cloned().unwrap_or_default();
+            let decimals = |mint: &str| {
+                tx.post_token_balances
+                    .iter()
+                    .find(|b| b.mint == mint)
+                    .map(|b| b.decimals)
+            };
+            let input = Leg {
+                mint: first.input_mint.clone(),
+                amount: first.input_amount,
+                decimals: decimals(&first.input_mint),
+            };
+            let output = Leg {
+                mint: last.output_mint.clone(),
+                amount: last.output_amount,
+                decimals: decimals(&last.output_mint),
+            };
+            events.push(ParsedEvent::Swap(dex::build_swap(
+                tx,
+                ix,
+                JUPITER_PROGRAM_ID,
+                None,
+                user,
+                input,
+                output,
+            )));
         }
 
         events
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_route_collapsed_unless_failed() {
+        let ix = |inner_index, data| RawInstruction {
+            program_id: JUPITER_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index,
+            data,
+            accounts: vec!["program".into(), "user".into()],
+        };
+        let hop = |input: u8, input_amount: u64, output: u8, output_amount: u64| {
+            let mut data = [EVENT_IX_TAG, SWAP_EVENT_DISCRIMINATOR].concat();
+            data.extend([9u8; 32]); // amm
+            data.extend([input; 32]);
+            data.extend(input_amount.to_le_bytes());
+            data.extend([output; 32]);
+            data.extend(output_amount.to_le_bytes());
+            ix(Some(0), data)
+        };
+        let mut tx = TransactionUpdate::test(vec![
+            ix(None, vec![0xe5; 8]),
+            hop(1, 1_000, 2, 500),
+            hop(2, 500, 3, 42),
+        ]);
+
+        let events = JupiterParser::new().parse(&tx);
+        let [ParsedEvent::Swap(swap)] = events.as_slice() else {
+            panic!("expected one swap, got {events:?}");
+        };
+        assert_eq!(swap.user, "user");
+        assert_eq!(swap.pool, None);
+        let mints = [&swap.base_mint, &swap.quote_mint].map(|m| bs58::decode(m).into_vec().unwrap()[0]);
+        assert!(mints.contains(&1) && mints.contains(&3));
+
+        tx.success = false;
+        assert!(JupiterParser::new().parse(&tx).is_empty());
+    }
+}
@@ -0,0 +1,168 @@
+//! Kamino Lend parser.
+//!
+//! The `_v2` instructions (with farms) take the same leading accounts as v1,
+//! so both decode through the same layouts. Amounts are the underlying
+//! liquidity moved to or from the user, falling back to the argument.
+
+use crate::parser::lending::{self, amount_arg, mint_of, received_by, sent_from};
+use crate::parser::{LendingAction, ParsedEvent, Parser, KAMINO_LEND_PROGRAM_ID};
+use crate::receiver::TransactionUpdate;
+
+pub struct KaminoParser;
+
+impl KaminoParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Anchor instruction discriminators (first 8 bytes of sha256("global:<name>"))
+const IX_DEPOSIT: [u8; 8] = [0x81, 0xc7, 0x04, 0x02, 0xde, 0x27, 0x1a, 0x2e];
+const IX_DEPOSIT_V2: [u8; 8] = [0xd8, 0xe0, 0xbf, 0x1b, 0xcc, 0x97, 0x66, 0xaf];
+const IX_WITHDRAW: [u8; 8] = [0x4b, 0x5d, 0x5d, 0xdc, 0x22, 0x96, 0xda, 0xc4];
+const IX_WITHDRAW_V2: [u8; 8] = [0xeb, 0x34, 0x77, 0x98, 0x95, 0xc5, 0x14, 0x07];
+const IX_BORROW: [u8; 8] = [0x79, 0x7f, 0x12, 0xcc, 0x49, 0xf5, 0xe1, 0x41];
+const IX_BORROW_V2: [u8; 8] = [0xa1, 0x80, 0x8f, 0xf5, 0xab, 0xc7, 0xc2, 0x06];
+const IX_REPAY: [u8; 8] = [0x91, 0xb2, 0x0d, 0xe1, 0x4c, 0xf0, 0x93, 0x48];
+const IX_REPAY_V2: [u8; 8] = [0x74, 0xae, 0xd5, 0x4c, 0xb4, 0x35, 0xd2, 0x90];
+const IX_LIQUIDATE: [u8; 8] = [0xb1, 0x47, 0x9a, 0xbc, 0xe2, 0x85, 0x4a, 0x37];
+const IX_LIQUIDATE_V2: [u8; 8] = [0xa2, 0xa1, 0x23, 0x8f, 0x1e, 0xbb, 0xb9, 0x67];
+
+impl Parser for KaminoParser {
+    fn program_id(&self) -> &str {
+        KAMINO_LEND_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            if ix.program_id != KAMINO_LEND_PROGRAM_ID || ix.data.len() < 8 {
+                continue;
+            }
+            let acct = |i: usize| ix.accounts.get(i).cloned();
+            let arg = amount_arg(&ix.data, 8);
+            // (action, reserve index, mint index, user token account index)
+            let (action, reserve, mint, user_account) = match ix.data[..8].try_into().unwrap_or_default() {
+                // [owner, obligation, market, market_authority, reserve, liquidity_mint, liquidity_supply,
+                //  collateral_mint, destination_collateral, user_source_liquidity, ..]
+                IX_DEPOSIT | IX_DEPOSIT_V2 => (LendingAction::Deposit, 4, 5, 9),
+                // [owner, obligation, market, market_authority, reserve, liquidity_mint, source_collateral,
+                //  collateral_mint, liquidity_supply, user_destination_liquidity, ..]
+                IX_WITHDRAW | IX_WITHDRAW_V2 => (LendingAction::Withdraw, 4, 5, 9),
+                // [owner, obligation, market, market_authority, reserve, liquidity_mint, source_liquidity,
+                //  fee_receiver, user_destination_liquidity, ..]
+                IX_BORROW | IX_BORROW_V2 => (LendingAction::Borrow, 4, 5, 8),
+                // [owner, obligation, market, reserve, liquidity_mint, destination_liquidity,
+                //  user_source_liquidity, ..]
+                IX_REPAY | IX_REPAY_V2 => (LendingAction::Repay, 3, 4, 6),
+                // [liquidator, obligation, market, market_authority, repay_reserve, repay_mint,
+                //  repay_supply, withdraw_reserve, withdraw_mint, withdraw_collateral_mint,
+                //  withdraw_collateral_supply, withdraw_liquidity_supply, withdraw_fee_receiver,
+                //  user_source_liquidity, user_destination_collateral, user_destination_liquidity, ..]
+                IX_LIQUIDATE | IX_LIQUIDATE_V2 => (LendingAction::Liquidate, 4, 5, 13),
+                _ => continue,
+            };
+            let (Some(reserve), Some(obligation), Some(user_account)) = (acct(reserve), acct(1), acct(user_account))
+            else {
+                continue;
+            };
+
+            let mut e = lending::event(tx, ix, KAMINO_LEND_PROGRAM_ID, action, reserve, obligation.clone());
+            e.mint = acct(mint).or_else(|| mint_of(tx, &user_account));
+            match action {
+                LendingAction::Deposit | LendingAction::Repay => {
+                    e.owner = acct(0);
+                    e.amount = sent_from(tx, pos, &user_account).or(arg);
+                }
+                LendingAction::Borrow => {
+                    e.owner = acct(0);
+                    e.amount = received_by(tx, pos, &user_account).or(arg);
+                }
+                // The argument is a collateral (cToken) amount, not liquidity.
+                LendingAction::Withdraw => {
+                    e.owner = acct(0);
+                    e.amount = received_by(tx, pos, &user_account);
+                }
+                LendingAction::Liquidate => {
+                    e.amount = sent_from(tx, pos, &user_account).or(arg);
+                    e.liquidator = acct(0);
+                    e.liquidatee = Some(obligation);
+                    e.collateral_reserve = acct(7);
+                    e.collateral_mint = acct(8);
+                    e.collateral_amount = acct(15).and_then(|a| received_by(tx, pos, &a));
+                }
+            }
+            events.push(ParsedEvent::Lending(e));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SPL_TOKEN_PROGRAM_ID;
+    use crate::receiver::{RawInstruction, TokenBalance};
+
+    #[test]
+    fn test_liquidation_parsed() {
+        let mut data = IX_LIQUIDATE_V2.to_vec();
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        let transfer = |source: &str, destination: &str, amount: u64| {
+            let mut data = vec![3u8];
+            data.extend_from_slice(&amount.to_le_bytes());
+            RawInstruction {
+                program_id: SPL_TOKEN_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: Some(0),
+                data,
+                accounts: vec![source.into(), destination.into(), "auth".into()],
+            }
+        };
+        let accounts = [
+            "liquidator", "obligation", "market", "market_auth", "usdc_reserve", "usdc", "usdc_supply",
+            "sol_reserve", "wsol", "c_mint", "c_supply", "sol_supply", "fee", "usdc_src", "c_dst", "sol_dst",
+        ];
+        let mut tx = TransactionUpdate::test(vec![
+            RawInstruction {
+                program_id: KAMINO_LEND_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: None,
+                data,
+                accounts: accounts.iter().map(|a| a.to_string()).collect(),
+            },
+            transfer("usdc_src", "usdc_supply", 150_000_000),
+            transfer("c_supply", "c_dst", 900_000_000),
+            transfer("sol_supply", "sol_dst", 1_050_000_000),
+        ]);
+        tx.post_token_balances = vec![TokenBalance {
+            account: "usdc_src".into(),
+            mint: "usdc".into(),
+            owner: "liquidator".into(),
+            amount: 0,
+            decimals: 6,
+        }];
+
+        let events = KaminoParser::new().parse(&tx);
+        let [ParsedEvent::Lending(e)] = events.as_slice() else {
+            panic!("expected one lending event, got {events:?}");
+        };
+        assert_eq!(e.action, LendingAction::Liquidate);
+        assert_eq!((e.reserve.as_str(), e.mint.as_deref()), ("usdc_reserve", Some("usdc")));
+        assert_eq!(e.amount, Some(150_000_000));
+        assert_eq!(e.liquidator.as_deref(), Some("liquidator"));
+        assert_eq!(e.liquidatee.as_deref(), Some("obligation"));
+        assert_eq!(e.collateral_reserve.as_deref(), Some("sol_reserve"));
+        assert_eq!(e.collateral_mint.as_deref(), Some("wsol"));
+        // Liquidity redeemed to the liquidator, not the cTokens seized
+        assert_eq!(e.collateral_amount, Some(1_050_000_000));
+
+        tx.success = false;
+        assert!(KaminoParser::new().parse(&tx).is_empty());
+    }
+}
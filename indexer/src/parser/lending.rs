@@ -0,0 +1,73 @@
+//! Shared helpers for the lending protocol parsers — normalised events and
+//! amounts resolved from the token transfers each instruction invokes.
+//!
+//! The parsers skip failed transactions: a failed liquidation attempt would
+//! otherwise look like a liquidation, with the instruction argument standing
+//! in for the missing transfers.
+
+use crate::parser::dex;
+use crate::parser::{LendingAction, LendingEvent};
+use crate::receiver::{RawInstruction, TransactionUpdate};
+
+/// Empty event for `action` on `reserve` / `obligation`; the caller fills in
+/// the accounts and amounts it knows.
+pub fn event(
+    tx: &TransactionUpdate,
+    ix: &RawInstruction,
+    program: &str,
+    action: LendingAction,
+    reserve: String,
+    obligation: String,
+) -> LendingEvent {
+    LendingEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        instruction_index: ix.instruction_index,
+        inner_index: ix.inner_index,
+        program: program.into(),
+        action,
+        reserve,
+        obligation,
+        owner: None,
+        mint: None,
+        amount: None,
+        liquidator: None,
+        liquidatee: None,
+        collateral_reserve: None,
+        collateral_mint: None,
+        collateral_amount: None,
+    }
+}
+
+/// Tokens sent from `account` by the instruction at `pos`.
+pub fn sent_from(tx: &TransactionUpdate, pos: usize, account: &str) -> Option<u64> {
+    let sent: u64 = dex::inner_transfers(tx, pos)
+        .iter()
+        .filter(|t| t.source == account)
+        .map(|t| t.amount)
+        .sum();
+    (sent > 0).then_some(sent)
+}
+
+/// Tokens received by `account` from the instruction at `pos`.
+pub fn received_by(tx: &TransactionUpdate, pos: usize, account: &str) -> Option<u64> {
+    let received: u64 = dex::inner_transfers(tx, pos)
+        .iter()
+        .filter(|t| t.destination == account)
+        .map(|t| t.amount)
+        .sum();
+    (received > 0).then_some(received)
+}
+
+/// Mint of a token account touched by the transaction.
+pub fn mint_of(tx: &TransactionUpdate, account: &str) -> Option<String> {
+    tx.token_balance(account).map(|b| b.mint.clone())
+}
+
+/// Little-endian `u64` amount argument at `off`. `u64::MAX` means "all" to
+/// these programs and carries no amount.
+pub fn amount_arg(data: &[u8], off: usize) -> Option<u64> {
+    let amount = u64::from_le_bytes(data.get(off..off + 8)?.try_into().ok()?);
+    (amount != u64::MAX).then_some(amount)
+}
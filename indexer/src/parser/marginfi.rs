@@ -0,0 +1,138 @@
+//! MarginFi v2 parser.
+//!
+//! Bank mints are not instruction accounts, so they are resolved from the
+//! token accounts involved. Liquidations settle between MarginFi accounts
+//! without moving tokens to the liquidator, so only the seized collateral
+//! amount (the instruction argument) is known.
+
+use crate::parser::lending::{self, amount_arg, mint_of, received_by, sent_from};
+use crate::parser::{LendingAction, ParsedEvent, Parser, MARGINFI_PROGRAM_ID};
+use crate::receiver::TransactionUpdate;
+
+pub struct MarginfiParser;
+
+impl MarginfiParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Anchor instruction discriminators (first 8 bytes of sha256("global:<name>"))
+const IX_DEPOSIT: [u8; 8] = [0xab, 0x5e, 0xeb, 0x67, 0x52, 0x40, 0xd4, 0x8c];
+const IX_WITHDRAW: [u8; 8] = [0x24, 0x48, 0x4a, 0x13, 0xd2, 0xd2, 0xc0, 0xc0];
+const IX_BORROW: [u8; 8] = [0x04, 0x7e, 0x74, 0x35, 0x30, 0x05, 0xd4, 0x1f];
+const IX_REPAY: [u8; 8] = [0x4f, 0xd1, 0xac, 0xb1, 0xde, 0x33, 0xad, 0x97];
+const IX_LIQUIDATE: [u8; 8] = [0xd6, 0xa9, 0x97, 0xd5, 0xfb, 0xa7, 0x56, 0xdb];
+
+impl Parser for MarginfiParser {
+    fn program_id(&self) -> &str {
+        MARGINFI_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            if ix.program_id != MARGINFI_PROGRAM_ID || ix.data.len() < 8 {
+                continue;
+            }
+            let acct = |i: usize| ix.accounts.get(i).cloned();
+            let arg = amount_arg(&ix.data, 8);
+            let discriminator: [u8; 8] = ix.data[..8].try_into().unwrap_or_default();
+
+            if discriminator == IX_LIQUIDATE {
+                // accounts: [group, asset_bank, liab_bank, liquidator_account, signer,
+                //            liquidatee_account, vault_authority, liab_vault, insurance_vault, ..]
+                let (Some(liab_bank), Some(liquidatee)) = (acct(2), acct(5)) else {
+                    continue;
+                };
+                let mut e = lending::event(
+                    tx,
+                    ix,
+                    MARGINFI_PROGRAM_ID,
+                    LendingAction::Liquidate,
+                    liab_bank,
+                    liquidatee.clone(),
+                );
+                e.mint = acct(7).and_then(|vault| mint_of(tx, &vault));
+                e.liquidator = acct(3);
+                e.liquidatee = Some(liquidatee);
+                e.collateral_reserve = acct(1);
+                e.collateral_amount = arg;
+                events.push(ParsedEvent::Lending(e));
+                continue;
+            }
+
+            // accounts: [group, marginfi_account, signer, bank, user_token_account, ..]
+            let action = match discriminator {
+                IX_DEPOSIT => LendingAction::Deposit,
+                IX_WITHDRAW => LendingAction::Withdraw,
+                IX_BORROW => LendingAction::Borrow,
+                IX_REPAY => LendingAction::Repay,
+                _ => continue,
+            };
+            let (Some(account), Some(bank), Some(user_account)) = (acct(1), acct(3), acct(4)) else {
+                continue;
+            };
+            let mut e = lending::event(tx, ix, MARGINFI_PROGRAM_ID, action, bank, account);
+            e.owner = acct(2);
+            e.mint = mint_of(tx, &user_account);
+            e.amount = match action {
+                LendingAction::Deposit | LendingAction::Repay => sent_from(tx, pos, &user_account),
+                _ => received_by(tx, pos, &user_account),
+            }
+            .or(arg);
+            events.push(ParsedEvent::Lending(e));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::{RawInstruction, TokenBalance};
+
+    #[test]
+    fn test_liquidation_parsed() {
+        let mut data = IX_LIQUIDATE.to_vec();
+        data.extend_from_slice(&2_500_000u64.to_le_bytes());
+        let accounts = [
+            "group", "sol_bank", "usdc_bank", "liquidator_account", "signer", "liquidatee_account",
+            "vault_auth", "usdc_vault", "insurance_vault",
+        ];
+        let mut tx = TransactionUpdate::test(vec![RawInstruction {
+            program_id: MARGINFI_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        }]);
+        tx.post_token_balances = vec![TokenBalance {
+            account: "usdc_vault".into(),
+            mint: "usdc".into(),
+            owner: "vault_auth".into(),
+            amount: 0,
+            decimals: 6,
+        }];
+
+        let events = MarginfiParser::new().parse(&tx);
+        let [ParsedEvent::Lending(e)] = events.as_slice() else {
+            panic!("expected one lending event, got {events:?}");
+        };
+        assert_eq!(e.action, LendingAction::Liquidate);
+        assert_eq!((e.reserve.as_str(), e.mint.as_deref()), ("usdc_bank", Some("usdc")));
+        assert_eq!(e.liquidator.as_deref(), Some("liquidator_account"));
+        assert_eq!(e.liquidatee.as_deref(), Some("liquidatee_account"));
+        assert_eq!(e.obligation, "liquidatee_account");
+        assert_eq!(e.collateral_reserve.as_deref(), Some("sol_bank"));
+        assert_eq!(e.collateral_amount, Some(2_500_000));
+
+        tx.success = false;
+        assert!(MarginfiParser::new().parse(&tx).is_empty());
+    }
+}
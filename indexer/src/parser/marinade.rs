@@ -0,0 +1,152 @@
+//! Marinade liquid staking parser.
+//!
+//! Marinade is an Anchor program: SOL and stake accounts are deposited for
+//! mSOL, which is redeemed instantly through the liquidity pool
+//! (`liquid_unstake`), through a delayed ticket (`order_unstake` + `claim`),
+//! or as a stake account.
+
+use crate::parser::stake_pool::{lamports_paid_to, lamports_split};
+use crate::parser::{ParsedEvent, Parser, StakePoolAction, StakePoolEvent, MARINADE_PROGRAM_ID};
+use crate::receiver::TransactionUpdate;
+
+pub struct MarinadeParser;
+
+impl MarinadeParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Anchor instruction discriminators (first 8 bytes of sha256("global:<name>"))
+const IX_DEPOSIT: [u8; 8] = [0xf2, 0x23, 0xc6, 0x89, 0x52, 0xe1, 0xf2, 0xb6];
+const IX_DEPOSIT_STAKE_ACCOUNT: [u8; 8] = [0x6e, 0x82, 0x73, 0x29, 0xa4, 0x66, 0x02, 0x3b];
+const IX_LIQUID_UNSTAKE: [u8; 8] = [0x1e, 0x1e, 0x77, 0xf0, 0xbf, 0xe3, 0x0c, 0x10];
+const IX_ORDER_UNSTAKE: [u8; 8] = [0x61, 0xa7, 0x90, 0x6b, 0x75, 0xbe, 0x80, 0x24];
+const IX_CLAIM: [u8; 8] = [0x3e, 0xc6, 0xd6, 0xc1, 0xd5, 0x9f, 0x6c, 0xd2];
+const IX_WITHDRAW_STAKE_ACCOUNT: [u8; 8] = [0xd3, 0x55, 0xb8, 0x41, 0xb7, 0xb1, 0xe9, 0xd9];
+
+fn u64_at(data: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(off..off + 8)?.try_into().ok()?))
+}
+
+impl Parser for MarinadeParser {
+    fn program_id(&self) -> &str {
+        MARINADE_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // Failed transactions mint and burn no mSOL.
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            if ix.program_id != MARINADE_PROGRAM_ID || ix.data.len() < 8 {
+                continue;
+            }
+            let acct = |i: usize| ix.accounts.get(i).cloned();
+            let increase = |i: usize| acct(i).and_then(|a| tx.token_balance_increase(&a));
+            // (action, user, sol_amount, lst_mint, lst_amount)
+            let decoded = match ix.data[..8].try_into().unwrap_or_default() {
+                IX_DEPOSIT => {
+                    // deposit(lamports): accounts [state, msol_mint, liq_pool_sol_leg, liq_pool_msol_leg,
+                    //   liq_pool_msol_leg_authority, reserve, transfer_from, mint_to, ..]
+                    (StakePoolAction::DepositSol, acct(6), u64_at(&ix.data, 8), acct(1), increase(7))
+                }
+                IX_DEPOSIT_STAKE_ACCOUNT => {
+                    // deposit_stake_account(validator_index): accounts [state, validator_list,
+                    //   stake_list, stake_account, stake_authority, duplication_flag, rent_payer,
+                    //   msol_mint, mint_to, ..]
+                    (StakePoolAction::DepositStake, acct(4), None, acct(7), increase(8))
+                }
+                IX_LIQUID_UNSTAKE => {
+                    // liquid_unstake(msol_amount): accounts [state, msol_mint, liq_pool_sol_leg,
+                    //   liq_pool_msol_leg, treasury_msol, get_msol_from, get_msol_from_authority,
+                    //   transfer_sol_to, ..]
+                    let sol = acct(7).and_then(|to| lamports_paid_to(tx, pos, &to));
+                    (StakePoolAction::LiquidUnstake, acct(6), sol, acct(1), u64_at(&ix.data, 8))
+                }
+                IX_ORDER_UNSTAKE => {
+                    // order_unstake(msol_amount): accounts [state, msol_mint, burn_msol_from,
+                    //   burn_msol_authority, new_ticket_account, ..]
+                    (StakePoolAction::OrderUnstake, acct(3), None, acct(1), u64_at(&ix.data, 8))
+                }
+                IX_CLAIM => {
+                    // claim: accounts [state, reserve, ticket_account, transfer_sol_to, ..]
+                    let sol = acct(3).and_then(|to| lamports_paid_to(tx, pos, &to));
+                    (StakePoolAction::Claim, acct(3), sol, None, None)
+                }
+                IX_WITHDRAW_STAKE_ACCOUNT => {
+                    // withdraw_stake_account(stake_index, validator_index, msol_amount, beneficiary):
+                    //   accounts [state, msol_mint, burn_msol_from, burn_msol_authority, ..]
+                    let sol = lamports_split(tx, pos);
+                    (StakePoolAction::WithdrawStake, acct(3), sol, acct(1), u64_at(&ix.data, 16))
+                }
+                _ => continue,
+            };
+            let (action, user, sol_amount, lst_mint, lst_amount) = decoded;
+            let (Some(pool), Some(user)) = (acct(0), user) else {
+                continue;
+            };
+
+            events.push(ParsedEvent::StakePool(StakePoolEvent {
+                signature: tx.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.block_time,
+                instruction_index: ix.instruction_index,
+                inner_index: ix.inner_index,
+                program: MARINADE_PROGRAM_ID.into(),
+                pool,
+                action,
+                user,
+                sol_amount,
+                lst_mint,
+                lst_amount,
+            }));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::{RawInstruction, TokenBalance};
+
+    #[test]
+    fn test_deposit_stake_account_parsed() {
+        let mut data = IX_DEPOSIT_STAKE_ACCOUNT.to_vec();
+        data.extend_from_slice(&12u32.to_le_bytes()); // validator_index
+        let accounts = [
+            "state", "validators", "stakes", "stake", "alice", "dupflag", "payer", "msol", "aliceata",
+        ];
+        let mut tx = TransactionUpdate::test(vec![RawInstruction {
+            program_id: MARINADE_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        }]);
+        tx.post_token_balances = vec![TokenBalance {
+            account: "aliceata".into(),
+            mint: "msol".into(),
+            owner: "alice".into(),
+            amount: 4_200_000_000,
+            decimals: 9,
+        }];
+
+        let events = MarinadeParser::new().parse(&tx);
+        let [ParsedEvent::StakePool(e)] = events.as_slice() else {
+            panic!("expected one stake pool event, got {events:?}");
+        };
+        assert_eq!(e.action, StakePoolAction::DepositStake);
+        assert_eq!((e.pool.as_str(), e.user.as_str()), ("state", "alice"));
+        assert_eq!(e.lst_mint.as_deref(), Some("msol"));
+        assert_eq!(e.lst_amount, Some(4_200_000_000));
+
+        tx.success = false;
+        assert!(MarinadeParser::new().parse(&tx).is_empty());
+    }
+}
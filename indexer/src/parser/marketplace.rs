@@ -0,0 +1,244 @@
+//! NFT marketplace sale parser (Tensor TSwap/TComp/TAMM, Magic Eden M2/M3).
+//!
+//! Sale instructions differ per program and version, so sales are recovered
+//! from their effects: an NFT changes owner inside a marketplace instruction
+//! while lamports move through System transfers. The marketplace fee is what
+//! the program's fee account receives, the seller is the previous owner (or,
+//! for escrowed listings, the largest remaining recipient) and everything else
+//! paid out is royalty.
+
+use std::collections::HashMap;
+
+use crate::parser::{bubblegum, dex, spl_token, system};
+use crate::parser::{
+    NftSaleEvent, ParsedEvent, Parser, BUBBLEGUM_PROGRAM_ID, MAGIC_EDEN_M2_PROGRAM_ID,
+    MAGIC_EDEN_M3_PROGRAM_ID, SPL_NOOP_PROGRAM_ID, SYSTEM_PROGRAM_ID, TENSOR_AMM_PROGRAM_ID,
+    TENSOR_CNFT_PROGRAM_ID, TENSOR_SWAP_PROGRAM_ID,
+};
+use crate::receiver::TransactionUpdate;
+
+/// Position of the fee recipient in each marketplace's sale instructions.
+const MARKETPLACES: [(&str, usize); 5] = [
+    (TENSOR_SWAP_PROGRAM_ID, 1),    // fee_vault
+    (TENSOR_CNFT_PROGRAM_ID, 0),    // tcomp
+    (TENSOR_AMM_PROGRAM_ID, 3),     // fee_vault
+    (MAGIC_EDEN_M2_PROGRAM_ID, 10), // auction_house_treasury
+    (MAGIC_EDEN_M3_PROGRAM_ID, 3),  // referral
+];
+
+pub struct MarketplaceParser {
+    program_id: &'static str,
+    fee_account: usize,
+}
+
+impl MarketplaceParser {
+    /// One parser per supported marketplace program.
+    pub fn all() -> Vec<Self> {
+        MARKETPLACES
+            .iter()
+            .map(|&(program_id, fee_account)| Self {
+                program_id,
+                fee_account,
+            })
+            .collect()
+    }
+}
+
+/// An NFT (token mint or compressed asset id) changing owner.
+struct Movement {
+    mint: String,
+    from: String,
+    to: String,
+}
+
+/// Find the NFT moved by the CPIs following the instruction at `pos`, either
+/// an amount-1 SPL token transfer or a Bubblegum transfer.
+fn nft_movement(tx: &TransactionUpdate, pos: usize) -> Option<Movement> {
+    for t in dex::inner_transfers(tx, pos) {
+        let Some(source) = tx.token_balance(&t.source) else {
+            continue;
+        };
+        if !spl_token::is_nft_movement(tx, &source.mint, t.amount, source.decimals) {
+            continue;
+        }
+        return Some(Movement {
+            mint: source.mint.clone(),
+            from: source.owner.clone(),
+            to: tx
+                .token_balance(&t.destination)
+                .map(|b| b.owner.clone())
+                .unwrap_or(t.destination),
+        });
+    }
+
+    // Compressed NFTs: transfer accounts are [tree_authority, leaf_owner,
+    // leaf_delegate, new_leaf_owner, ..] and the asset id is in the leaf event.
+    let inner = tx.inner_instructions(pos);
+    let transfer = inner.iter().position(|i| {
+        i.program_id == BUBBLEGUM_PROGRAM_ID && i.data.get(..8) == Some(&bubblegum::IX_TRANSFER[..])
+    })?;
+    let leaf = inner[transfer + 1..]
+        .iter()
+        .filter(|i| i.program_id == SPL_NOOP_PROGRAM_ID && i.data.len() > 2)
+        .filter(|i| i.data[0] == bubblegum::EVENT_APPLICATION_DATA)
+        .find_map(|i| bubblegum::decode_leaf_schema(&i.data).ok().flatten())?;
+    let accounts = &inner[transfer].accounts;
+    Some(Movement {
+        mint: leaf.asset_id,
+        from: accounts.get(1)?.clone(),
+        to: accounts.get(3)?.clone(),
+    })
+}
+
+/// Lamports received per account through System transfers following `pos`.
+/// Payments to token accounts (rent for new ATAs) and self-transfers are skipped.
+fn lamports_received(tx: &TransactionUpdate, pos: usize) -> HashMap<String, u64> {
+    let mut received = HashMap::new();
+    for ix in tx.inner_instructions(pos) {
+        if ix.program_id != SYSTEM_PROGRAM_ID || ix.data.len() < 12 || ix.accounts.len() < 2 {
+            continue;
+        }
+        if u32::from_le_bytes(ix.data[..4].try_into().unwrap_or_default()) != system::IX_TRANSFER {
+            continue;
+        }
+        let (from, to) = (&ix.accounts[0], &ix.accounts[1]);
+        if from == to || tx.token_balance(to).is_some() {
+            continue;
+        }
+        let lamports = u64::from_le_bytes(ix.data[4..12].try_into().unwrap_or_default());
+        *received.entry(to.clone()).or_insert(0) += lamports;
+    }
+    received
+}
+
+impl Parser for MarketplaceParser {
+    fn program_id(&self) -> &str {
+        self.program_id
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // A failed buy moves neither the NFT nor the lamports.
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+        let mut last_index = None;
+
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            // One sale per outer instruction, decoded from the first
+            // invocation of the marketplace within it.
+            if ix.program_id != self.program_id || last_index == Some(ix.instruction_index) {
+                continue;
+            }
+            let Some(movement) = nft_movement(tx, pos) else {
+                continue;
+            };
+            last_index = Some(ix.instruction_index);
+
+            let mut received = lamports_received(tx, pos);
+            received.remove(&movement.to);
+            let marketplace_fee = ix
+                .accounts
+                .get(self.fee_account)
+                .and_then(|fee_account| received.remove(fee_account))
+                .unwrap_or(0);
+            let seller = if received.contains_key(&movement.from) {
+                Some(movement.from.clone())
+            } else {
+                received
+                    .iter()
+                    .max_by(|a, b| a.1.cmp(b.1).then_with(|| b.0.cmp(a.0)))
+                    .map(|(account, _)| account.clone())
+            };
+            let Some(seller) = seller else {
+                continue; // no lamports changed hands: a listing or delisting
+            };
+            let proceeds = received.remove(&seller).unwrap_or(0);
+            let royalty: u64 = received.values().sum();
+
+            events.push(ParsedEvent::NftSale(NftSaleEvent {
+                signature: tx.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.block_time,
+                marketplace: self.program_id.into(),
+                instruction_index: ix.instruction_index,
+                mint: movement.mint,
+                buyer: movement.to,
+                seller,
+                price: proceeds + marketplace_fee + royalty,
+                marketplace_fee,
+                royalty,
+            }));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SPL_TOKEN_PROGRAM_ID;
+    use crate::receiver::{RawInstruction, TokenBalance};
+
+    fn inner(program_id: &str, data: Vec<u8>, accounts: &[&str]) -> RawInstruction {
+        RawInstruction {
+            program_id: program_id.into(),
+            instruction_index: 0,
+            inner_index: Some(0),
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        }
+    }
+
+    fn lamports(from: &str, to: &str, amount: u64) -> RawInstruction {
+        let mut data = system::IX_TRANSFER.to_le_bytes().to_vec();
+        data.extend_from_slice(&amount.to_le_bytes());
+        inner(SYSTEM_PROGRAM_ID, data, &[from, to])
+    }
+
+    #[test]
+    fn test_escrowed_listing_sale() {
+        let mut nft = vec![3u8];
+        nft.extend_from_slice(&1u64.to_le_bytes());
+        let balance = |account: &str, owner: &str, amount| TokenBalance {
+            account: account.into(),
+            mint: "nftmint".into(),
+            owner: owner.into(),
+            amount,
+            decimals: 0,
+        };
+        let mut tx = TransactionUpdate::test(vec![
+            RawInstruction {
+                program_id: TENSOR_SWAP_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: None,
+                data: vec![0; 16],
+                accounts: vec!["tswap".into(), "feevault".into()],
+            },
+            lamports("buyer", "lister", 10_000_000_000),
+            lamports("buyer", "feevault", 150_000_000),
+            lamports("buyer", "creator", 500_000_000),
+            inner(SPL_TOKEN_PROGRAM_ID, nft, &["escrow", "buyerata", "listing"]),
+        ]);
+        tx.pre_token_balances = vec![balance("escrow", "listing", 1)];
+        tx.post_token_balances = vec![balance("escrow", "listing", 0), balance("buyerata", "buyer", 1)];
+
+        let parser = MarketplaceParser::all().remove(0);
+        let events = parser.parse(&tx);
+        assert_eq!(events.len(), 1);
+        if let ParsedEvent::NftSale(s) = &events[0] {
+            assert_eq!(s.mint, "nftmint");
+            assert_eq!(s.buyer, "buyer");
+            assert_eq!(s.seller, "lister");
+            assert_eq!(s.marketplace_fee, 150_000_000);
+            assert_eq!(s.royalty, 500_000_000);
+            assert_eq!(s.price, 10_650_000_000);
+        } else {
+            panic!("Expected NftSale");
+        }
+
+        let failed = TransactionUpdate { success: false, ..tx };
+        assert!(parser.parse(&failed).is_empty());
+    }
+}
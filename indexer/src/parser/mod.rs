@@ -1,29 +1,110 @@
 //! Parser engine — pluggable instruction-level parsing.
 
+pub mod anchor;
+pub mod bubblegum;
+pub mod compute_budget;
+pub mod dex;
+pub mod jito;
 pub mod jupiter;
+pub mod kamino;
+pub mod lending;
+pub mod marginfi;
+pub mod marinade;
+pub mod marketplace;
+pub mod mpl_core;
 pub mod nft;
 pub mod raydium;
+pub mod solend;
 pub mod spl_token;
+pub mod stake;
+pub mod stake_pool;
+pub mod system;
+pub mod token_account;
+pub mod vote;
 
 use crate::receiver::{AccountUpdate, BlockUpdate, TransactionUpdate};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
 // Well-known program IDs
+pub const SYSTEM_PROGRAM_ID: &str = "11111111111111111111111111111111";
+pub const STAKE_PROGRAM_ID: &str = "Stake11111111111111111111111111111111111111";
+pub const VOTE_PROGRAM_ID: &str = "Vote111111111111111111111111111111111111111";
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+pub const SPL_STAKE_POOL_PROGRAM_ID: &str = "SPoo1Ku8WFXoNDMHPsrGSTSG1Y47rzgn41SLUNakuHy";
+pub const MARINADE_PROGRAM_ID: &str = "MarBmsSgKXdrN1egZf5sqe1TMai9K1rChYNDJgjq7aD";
+pub const KAMINO_LEND_PROGRAM_ID: &str = "KLend2g3cP87fffoy8q1mQqGKjrxjC8boSyAYavgmjD";
+pub const MARGINFI_PROGRAM_ID: &str = "MFv2hWf31Z9kbCa1snEPYctwafyvdvfHn6VQpcdfb";
+pub const SOLEND_PROGRAM_ID: &str = "So1endDq2YkqhipRh3WViPa8hdiSpxWy6z3Z6tMCpAo";
 pub const SPL_TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
 pub const SPL_TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
 pub const METAPLEX_PROGRAM_ID: &str = "metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s";
 pub const JUPITER_PROGRAM_ID: &str = "JUP6LkbZbjS1jKKwapdHNy74zcZ3tLUZoi5QNyVTaV4";
 pub const RAYDIUM_AMM_PROGRAM_ID: &str = "675kPX9MHTjS2zt1qfr1NYHuzeLXfQM9H24wFSUt1Mp8";
+pub const MPL_CORE_PROGRAM_ID: &str = "CoREENxT6tW1HoK8ypY1SxRMZTcVPm7R94rH4PZNhX7d";
+pub const TENSOR_SWAP_PROGRAM_ID: &str = "TSWAPaqyCSx2KABk68Shruf4rp7CxcNi8hAsbdwmHbN";
+pub const TENSOR_CNFT_PROGRAM_ID: &str = "TCMPhJdwDryooaGtiocG1u3xcYbRpiJzb283XfCZsDp";
+pub const TENSOR_AMM_PROGRAM_ID: &str = "TAMM6ub33ij1mbetoMyVBLeKY5iP41i4UPUJQGkhfsg";
+pub const MAGIC_EDEN_M2_PROGRAM_ID: &str = "M2mx93ekt1fmXSVkTrUL9xVFHkmME8HTUi5Cyc5aF7K";
+pub const MAGIC_EDEN_M3_PROGRAM_ID: &str = "mmm3XBJg5gk8XJxEKBvdgptZz6SgK4tXvn36sodowMc";
+pub const BUBBLEGUM_PROGRAM_ID: &str = "BGUMApB5aqAsgmHTkT8GLZ9fy3mHmETvF7ZtJCiC84N";
+pub const SPL_NOOP_PROGRAM_ID: &str = "noopb9bkMVfRPU8AsbpTUg8AQkHtKwMYZiFUjNRtMmV";
+
+// Well-known mints
+pub const WSOL_MINT: &str = "So11111111111111111111111111111111111111112";
+pub const USDC_MINT: &str = "EPjFWdd5AufqSSqeM2qN1xzybapC8G4wEGGkZwyTDt1v";
+pub const USDT_MINT: &str = "Es9vMFrzaCERmJfrF4H2FYD4KCoNkY11McCe8BtDUmBs";
+
+/// Jito tip payment accounts.
+pub const JITO_TIP_ACCOUNTS: [&str; 8] = [
+    "96gYZGLnJYVFmbjzopPSU6QiEV5fGqZNyN9nmNhvrZU5",
+    "HFqU5x63VTqvQss8hp11i4wVV8bD44PvwucfZ2bU7gRe",
+    "Cw8CFyM9FkoMi7K7Crf6HNQqf4uEMzpKw6QNghXLvLkY",
+    "ADaUMid9yfUytqMBgopwjb2DTLSokTSzL1zt6iGPaS49",
+    "DfXygSm4jCyNCybVYYK6DwvWqjKee8pbDmJGcLWNDXjh",
+    "ADuUkR4vqLUMWXxW9gh6D6L8pMSawimctcNZ5pGwDcEt",
+    "DttWaMuVvTiduZRnguLF7jNxTgiMBZ1hyAumKUiL2KRL",
+    "3AVi9Tg9Uo68tJfuvoKvqKNWKkC5wPdSSdeBnizKZ6jT",
+];
+
+/// NFT marketplace programs whose instructions NFT transfers are linked to.
+pub const MARKETPLACE_PROGRAM_IDS: [&str; 5] = [
+    TENSOR_SWAP_PROGRAM_ID,
+    TENSOR_CNFT_PROGRAM_ID,
+    TENSOR_AMM_PROGRAM_ID,
+    MAGIC_EDEN_M2_PROGRAM_ID,
+    MAGIC_EDEN_M3_PROGRAM_ID,
+];
 
 /// A parsed, normalised event ready for the writer.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ParsedEvent {
     Transaction(TransactionEvent),
+    Instruction(InstructionEvent),
     TokenTransfer(TokenTransferEvent),
+    SolTransfer(SolTransferEvent),
+    SystemAccount(SystemAccountEvent),
+    Stake(StakeEvent),
+    StakeAccount(StakeAccountEvent),
+    StakePool(StakePoolEvent),
+    Vote(VoteEvent),
+    VoteAccount(VoteAccountEvent),
+    LeaderSchedule(LeaderScheduleEvent),
+    Bundle(BundleEvent),
+    Mev(MevEvent),
+    Lending(LendingEvent),
     NftMint(NftMintEvent),
     NftTransfer(NftTransferEvent),
+    NftMetadata(NftMetadataEvent),
+    NftSale(NftSaleEvent),
+    Cnft(CnftEvent),
+    CoreAsset(CoreAssetEvent),
     Swap(SwapEvent),
+    Pool(PoolEvent),
+    TokenAccount(TokenAccountEvent),
+    Mint(MintEvent),
+    TokenSupply(TokenSupplyEvent),
+    AnchorAccount(AnchorAccountEvent),
     AccountUpdate(AccountUpdate),
     Block(BlockUpdate),
 }
@@ -32,18 +113,47 @@ pub enum ParsedEvent {
 pub struct TransactionEvent {
     pub signature: String,
     pub slot: u64,
+    /// Position within the block.
+    pub index: u64,
     pub block_time: i64,
     pub success: bool,
+    /// Total fee paid: `base_fee + priority_fee`.
     pub fee: u64,
     pub compute_units: u64,
+    /// Requested compute units and their price in micro-lamports.
+    pub compute_unit_limit: u32,
+    pub compute_unit_price: u64,
+    pub priority_fee: u64,
+    pub base_fee: u64,
+    /// Lamports tipped to Jito tip accounts.
+    pub jito_tip: Option<u64>,
+    /// Distinct top-level programs, excluding ComputeBudget.
+    pub programs: Vec<String>,
     pub accounts: Vec<String>,
     pub log_messages: Vec<String>,
 }
 
+/// A raw instruction of an indexed transaction. `parsed` holds the event a
+/// parser decoded from it, or an array when it yielded several.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstructionEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub program_id: String,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub data: Vec<u8>,
+    pub accounts: Vec<String>,
+    pub parsed: Option<serde_json::Value>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TokenTransferEvent {
     pub signature: String,
     pub block_time: i64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
     pub mint: String,
     pub source: String,
     pub destination: String,
@@ -51,11 +161,325 @@ pub struct TokenTransferEvent {
     pub decimals: u8,
 }
 
+/// Lamports moved by a System program instruction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SolTransferEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub source: String,
+    pub destination: String,
+    pub lamports: u64,
+}
+
+/// Account lifecycle or durable nonce instruction of the System program.
+/// Only the fields carried by the instruction are set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SystemAccountEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub action: SystemAction,
+    pub account: String,
+    /// Program the account is assigned to.
+    pub owner: Option<String>,
+    pub space: Option<u64>,
+    pub lamports: Option<u64>,
+    /// Base and seed of an address derived with `create_with_seed`.
+    pub base: Option<String>,
+    pub seed: Option<String>,
+    /// Nonce authority.
+    pub authority: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SystemAction {
+    CreateAccount,
+    Assign,
+    Allocate,
+    InitializeNonce,
+    AdvanceNonce,
+    WithdrawNonce,
+    AuthorizeNonce,
+    UpgradeNonce,
+}
+
+impl SystemAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SystemAction::CreateAccount => "create_account",
+            SystemAction::Assign => "assign",
+            SystemAction::Allocate => "allocate",
+            SystemAction::InitializeNonce => "initialize_nonce",
+            SystemAction::AdvanceNonce => "advance_nonce",
+            SystemAction::WithdrawNonce => "withdraw_nonce",
+            SystemAction::AuthorizeNonce => "authorize_nonce",
+            SystemAction::UpgradeNonce => "upgrade_nonce",
+        }
+    }
+}
+
+/// A Stake program instruction, stamped with the epoch it executed in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakeEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub epoch: u64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub action: StakeAction,
+    pub stake_account: String,
+    pub vote_account: Option<String>,
+    /// Staker / withdrawer set by the instruction, or the signing authority.
+    pub staker: Option<String>,
+    pub withdrawer: Option<String>,
+    /// Split destination, merge source or withdraw recipient.
+    pub related_account: Option<String>,
+    pub lamports: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StakeAction {
+    Initialize,
+    AuthorizeStaker,
+    AuthorizeWithdrawer,
+    Delegate,
+    Deactivate,
+    Withdraw,
+    Split,
+    Merge,
+}
+
+impl StakeAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StakeAction::Initialize => "initialize",
+            StakeAction::AuthorizeStaker => "authorize_staker",
+            StakeAction::AuthorizeWithdrawer => "authorize_withdrawer",
+            StakeAction::Delegate => "delegate",
+            StakeAction::Deactivate => "deactivate",
+            StakeAction::Withdraw => "withdraw",
+            StakeAction::Split => "split",
+            StakeAction::Merge => "merge",
+        }
+    }
+}
+
+/// Decoded state of a stake account. Authorities are `None` for
+/// uninitialized or closed accounts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakeAccountEvent {
+    pub address: String,
+    pub slot: u64,
+    pub lamports: u64,
+    pub rent_exempt_reserve: Option<u64>,
+    pub staker: Option<String>,
+    pub withdrawer: Option<String>,
+    pub vote_account: Option<String>,
+    pub delegated_stake: Option<u64>,
+    pub activation_epoch: Option<u64>,
+    pub deactivation_epoch: Option<u64>,
+}
+
+/// A deposit into or withdrawal from a liquid staking pool. `lst_amount` is
+/// minted for deposits and burned for withdrawals.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StakePoolEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub program: String,
+    pub pool: String,
+    pub action: StakePoolAction,
+    /// Wallet that deposits or receives the SOL.
+    pub user: String,
+    pub sol_amount: Option<u64>,
+    pub lst_mint: Option<String>,
+    pub lst_amount: Option<u64>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StakePoolAction {
+    DepositSol,
+    DepositStake,
+    WithdrawSol,
+    WithdrawStake,
+    /// Instant unstake through a liquidity pool.
+    LiquidUnstake,
+    /// Delayed unstake: LST burned now, SOL claimed later.
+    OrderUnstake,
+    Claim,
+}
+
+impl StakePoolAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            StakePoolAction::DepositSol => "deposit_sol",
+            StakePoolAction::DepositStake => "deposit_stake",
+            StakePoolAction::WithdrawSol => "withdraw_sol",
+            StakePoolAction::WithdrawStake => "withdraw_stake",
+            StakePoolAction::LiquidUnstake => "liquid_unstake",
+            StakePoolAction::OrderUnstake => "order_unstake",
+            StakePoolAction::Claim => "claim",
+        }
+    }
+}
+
+/// A vote landed by a validator. `latency` is the number of slots between the
+/// newest slot voted on and the slot the vote landed in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub vote_account: String,
+    pub authority: Option<String>,
+    pub voted_slot: u64,
+    pub root_slot: Option<u64>,
+    pub latency: u64,
+}
+
+/// Decoded state of a vote account: identity, commission and the credits
+/// earned in the most recent epoch it voted in.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VoteAccountEvent {
+    pub address: String,
+    pub slot: u64,
+    pub node: String,
+    pub commission: u8,
+    pub root_slot: Option<u64>,
+    pub epoch_credits: Option<EpochCredits>,
+}
+
+/// Vote credits for one epoch; credits are cumulative across epochs.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EpochCredits {
+    pub epoch: u64,
+    pub credits: u64,
+    pub prev_credits: u64,
+}
+
+/// Leader schedule for an epoch, as `(slot, validator identity)` pairs.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeaderScheduleEvent {
+    pub epoch: u64,
+    pub leaders: Vec<(u64, String)>,
+}
+
+/// Adjacent transactions from one fee payer grouped around a Jito tip; a
+/// heuristic reconstruction of a Jito bundle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BundleEvent {
+    pub slot: u64,
+    pub block_time: i64,
+    /// Transaction that paid the tip.
+    pub tip_signature: String,
+    pub tip_payer: String,
+    pub tip_lamports: u64,
+    /// Member signatures in block order.
+    pub signatures: Vec<String>,
+    pub first_index: u64,
+    pub last_index: u64,
+}
+
+/// A sandwich or cyclic arbitrage found by the MEV stage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MevEvent {
+    pub slot: u64,
+    pub block_time: i64,
+    pub kind: MevKind,
+    pub searcher: String,
+    /// Searcher transactions: front-run and back-run, or the arbitrage.
+    pub signatures: Vec<String>,
+    pub victim_signatures: Vec<String>,
+    /// Pools traded, in order.
+    pub pools: Vec<String>,
+    /// Estimated profit, in native units of `profit_mint`; negative if the
+    /// searcher lost on the trade.
+    pub profit_mint: String,
+    pub profit_amount: i64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MevKind {
+    Sandwich,
+    Arbitrage,
+}
+
+impl MevKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            MevKind::Sandwich => "sandwich",
+            MevKind::Arbitrage => "arbitrage",
+        }
+    }
+}
+
+/// A normalised money-market action. Amounts are in native units of the mint.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LendingEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub program: String,
+    pub action: LendingAction,
+    /// Kamino/Solend reserve or MarginFi bank; the repaid debt side for liquidations.
+    pub reserve: String,
+    /// Kamino/Solend obligation or MarginFi account acted on.
+    pub obligation: String,
+    /// Wallet that signed a deposit, withdrawal, borrow or repay.
+    pub owner: Option<String>,
+    pub mint: Option<String>,
+    pub amount: Option<u64>,
+    /// Liquidations only: the liquidator (wallet, or MarginFi account) and the
+    /// liquidated position, and the collateral seized from it.
+    pub liquidator: Option<String>,
+    pub liquidatee: Option<String>,
+    pub collateral_reserve: Option<String>,
+    pub collateral_mint: Option<String>,
+    pub collateral_amount: Option<u64>,
+}
+
+/// Serialised in snake_case so webhook filters can match on `action`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LendingAction {
+    Deposit,
+    Withdraw,
+    Borrow,
+    Repay,
+    Liquidate,
+}
+
+impl LendingAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            LendingAction::Deposit => "deposit",
+            LendingAction::Withdraw => "withdraw",
+            LendingAction::Borrow => "borrow",
+            LendingAction::Repay => "repay",
+            LendingAction::Liquidate => "liquidate",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NftMintEvent {
     pub signature: String,
+    pub slot: u64,
     pub block_time: i64,
     pub mint: String,
+    /// Wallet the token was minted to.
     pub owner: String,
     pub metadata_uri: Option<String>,
     pub collection: Option<String>,
@@ -64,22 +488,378 @@ pub struct NftMintEvent {
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NftTransferEvent {
     pub signature: String,
+    pub slot: u64,
     pub block_time: i64,
     pub mint: String,
-    pub from: String,
+    /// Wallet owners, not token accounts. `None` for Core transfers, whose
+    /// previous owner the writer resolves from the indexed asset.
+    pub from: Option<String>,
     pub to: String,
+    /// Marketplace program and outer instruction index the transfer belongs to.
+    pub marketplace: Option<String>,
+    pub marketplace_ix: Option<u32>,
+}
+
+/// An NFT sold through a marketplace instruction. Amounts are in lamports.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NftSaleEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    /// Marketplace program and the outer instruction index of the sale.
+    pub marketplace: String,
+    pub instruction_index: u32,
+    /// Token mint, or the asset id of a compressed NFT.
+    pub mint: String,
+    pub buyer: String,
+    pub seller: String,
+    /// Total paid: seller proceeds + marketplace fee + royalty.
+    pub price: u64,
+    pub marketplace_fee: u64,
+    pub royalty: u64,
+}
+
+/// A change to a Metaplex metadata account, keyed by the metadata address.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NftMetadataEvent {
+    /// Empty, like `block_time`, when decoded from the account itself.
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub metadata: String,
+    pub update: MetadataUpdate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum MetadataUpdate {
+    /// Metadata created or its data replaced.
+    Upsert(MetadataFields),
+    /// Collection membership verified or unverified.
+    Collection { collection: String, verified: bool },
+    Burned,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MetadataFields {
+    /// Not present on `UpdateMetadataAccountV2`.
+    pub mint: Option<String>,
+    pub update_authority: Option<String>,
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub seller_fee_basis_points: u16,
+    pub creators: Vec<Creator>,
+    pub collection: Option<String>,
+    pub collection_verified: bool,
+    pub token_standard: Option<u8>,
+    pub is_mutable: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Creator {
+    pub address: String,
+    pub verified: bool,
+    pub share: u8,
+}
+
+/// A compressed NFT leaf change (Bubblegum) with the Merkle tree change log it produced.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CnftEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub action: CnftAction,
+    pub tree: String,
+    pub leaf_index: u32,
+    /// Tree sequence number after this change.
+    pub seq: u64,
+    /// New leaf contents; `None` when the leaf was emptied (burn / redeem).
+    pub leaf: Option<LeafSchema>,
+    /// Nodes from the leaf up to the root, as written by this change.
+    pub path: Vec<PathNode>,
+    /// Metadata from `mint_v1` / `mint_to_collection_v1`.
+    pub metadata: Option<CnftMetadata>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CnftAction {
+    Mint,
+    Transfer,
+    Burn,
+    Redeem,
+    Delegate,
+}
+
+impl CnftAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            CnftAction::Mint => "mint",
+            CnftAction::Transfer => "transfer",
+            CnftAction::Burn => "burn",
+            CnftAction::Redeem => "redeem",
+            CnftAction::Delegate => "delegate",
+        }
+    }
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LeafSchema {
+    pub asset_id: String,
+    pub owner: String,
+    pub delegate: String,
+    pub nonce: u64,
+    pub data_hash: [u8; 32],
+    pub creator_hash: [u8; 32],
+}
+
+/// A Merkle tree node; the root has index 1 and leaf `i` has index `2^depth + i`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PathNode {
+    pub index: u32,
+    pub hash: [u8; 32],
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CnftMetadata {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub collection: Option<String>,
+    pub collection_verified: bool,
+}
+
+/// A change to a Metaplex Core asset or collection account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CoreAssetEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    /// Asset or collection account.
+    pub address: String,
+    pub update: CoreUpdate,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum CoreUpdate {
+    AssetCreated {
+        owner: String,
+        update_authority: String,
+        collection: Option<String>,
+        name: String,
+        uri: String,
+        plugins: Vec<CorePlugin>,
+    },
+    CollectionCreated {
+        update_authority: String,
+        name: String,
+        uri: String,
+        plugins: Vec<CorePlugin>,
+    },
+    Transferred {
+        new_owner: String,
+    },
+    Updated {
+        name: Option<String>,
+        uri: Option<String>,
+        update_authority: Option<String>,
+    },
+    Burned,
+    /// Plugin added, or replaced by `UpdatePlugin`.
+    PluginSet(CorePlugin),
+    PluginRemoved {
+        plugin_type: String,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CorePlugin {
+    pub plugin_type: String,
+    /// `None`, `Owner`, `UpdateAuthority` or an address.
+    pub authority: Option<String>,
+    pub data: serde_json::Value,
+}
+
+/// A DEX trade normalised to base/quote orientation (see `dex::orient`).
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SwapEvent {
     pub signature: String,
+    pub slot: u64,
     pub block_time: i64,
     pub program: String,
-    pub input_mint: String,
-    pub output_mint: String,
-    pub input_amount: u64,
-    pub output_amount: u64,
+    /// Pool / AMM account; `None` for aggregator-level trades.
+    pub pool: Option<String>,
+    /// Aggregator whose route this pool swap is a hop of. The route is also
+    /// recorded as an aggregator-level trade, so volume counts only one level.
+    #[serde(default)]
+    pub route: Option<String>,
     pub user: String,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub side: TradeSide,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub base_amount: u64,
+    pub quote_amount: u64,
+    pub base_decimals: Option<u8>,
+    pub quote_decimals: Option<u8>,
+    /// Quote units per base unit, decimal-adjusted. `None` if decimals are unknown.
+    pub price: Option<f64>,
+}
+
+/// Trade direction from the user's point of view: `Buy` acquires the base mint.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TradeSide {
+    Buy,
+    Sell,
+}
+
+impl TradeSide {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TradeSide::Buy => "buy",
+            TradeSide::Sell => "sell",
+        }
+    }
+}
+
+/// Pool registry entry, from a pool-creation instruction or a pool account update.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PoolEvent {
+    pub program: String,
+    pub address: String,
+    pub slot: u64,
+    pub base_mint: String,
+    pub quote_mint: String,
+    pub base_vault: Option<String>,
+    pub quote_vault: Option<String>,
+    pub base_decimals: Option<u8>,
+    pub quote_decimals: Option<u8>,
+    pub fee_bps: Option<u32>,
+    /// True when emitted by the pool-creation instruction.
+    pub created: bool,
+}
+
+/// Decoded SPL Token / Token-2022 token account.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenAccountEvent {
+    pub address: String,
+    pub slot: u64,
+    /// Owning token program.
+    pub program: String,
+    pub mint: String,
+    pub owner: String,
+    pub amount: u64,
+    pub delegate: Option<String>,
+    pub delegated_amount: u64,
+    pub state: TokenAccountState,
+    /// Rent-exempt reserve of a wrapped SOL account.
+    pub is_native: Option<u64>,
+    pub close_authority: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TokenAccountState {
+    Uninitialized,
+    Initialized,
+    Frozen,
+}
+
+impl TokenAccountState {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TokenAccountState::Uninitialized => "uninitialized",
+            TokenAccountState::Initialized => "initialized",
+            TokenAccountState::Frozen => "frozen",
+        }
+    }
+}
+
+/// An SPL Token / Token-2022 mint, decoded from the mint account or from
+/// `InitializeMint`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MintEvent {
+    pub address: String,
+    pub slot: u64,
+    /// Owning token program.
+    pub program: String,
+    pub decimals: u8,
+    pub supply: u64,
+    pub mint_authority: Option<String>,
+    pub freeze_authority: Option<String>,
+    pub is_initialized: bool,
+    /// Token-2022 extensions by name, e.g. `{"TransferFeeConfig": {..}}`.
+    /// `None` for `InitializeMint`, which only registers a mint not yet known.
+    #[serde(default)]
+    pub extensions: Option<serde_json::Value>,
+    /// From the Token-2022 `TokenMetadata` extension.
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub symbol: Option<String>,
+}
+
+/// Tokens minted (`MintTo`) or burned (`Burn`), changing a mint's supply.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenSupplyEvent {
+    pub signature: String,
+    pub slot: u64,
+    pub block_time: i64,
+    pub instruction_index: u32,
+    pub inner_index: Option<u32>,
+    pub mint: String,
+    /// Token account minted to or burned from.
+    pub account: String,
+    pub change: SupplyChange,
+    pub amount: u64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SupplyChange {
+    Mint,
+    Burn,
+}
+
+impl TokenSupplyEvent {
+    /// Signed change in supply.
+    pub fn delta(&self) -> i64 {
+        match self.change {
+            SupplyChange::Mint => self.amount as i64,
+            SupplyChange::Burn => -(self.amount as i64),
+        }
+    }
+}
+
+/// An Anchor program account decoded through the program's IDL.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorAccountEvent {
+    pub address: String,
+    pub slot: u64,
+    pub program: String,
+    /// IDL account type, e.g. `Obligation`.
+    pub account_type: String,
+    pub data: serde_json::Value,
+}
+
+impl ParsedEvent {
+    /// Instruction the event was decoded from, for events that record it.
+    /// An inner index of `None` means an outer instruction or that the event
+    /// only records the outer index.
+    pub fn instruction_position(&self) -> Option<(u32, Option<u32>)> {
+        match self {
+            ParsedEvent::TokenTransfer(e) => Some((e.instruction_index, e.inner_index)),
+            ParsedEvent::TokenSupply(e) => Some((e.instruction_index, e.inner_index)),
+            ParsedEvent::SolTransfer(e) => Some((e.instruction_index, e.inner_index)),
+            ParsedEvent::SystemAccount(e) => Some((e.instruction_index, e.inner_index)),
+            ParsedEvent::Stake(e) => Some((e.instruction_index, e.inner_index)),
+            ParsedEvent::Swap(e) => Some((e.instruction_index, e.inner_index)),
+            ParsedEvent::StakePool(e) => Some((e.instruction_index, None)),
+            ParsedEvent::Lending(e) => Some((e.instruction_index, None)),
+            ParsedEvent::NftSale(e) => Some((e.instruction_index, None)),
+            _ => None,
+        }
+    }
 }
 
 /// Pluggable parser interface.
@@ -88,15 +868,26 @@ pub trait Parser: Send + Sync {
     fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent>;
 }
 
+/// Pluggable account decoder, the account-side counterpart of `Parser`.
+/// Decoders are keyed by owner program; `matches` picks the account type,
+/// usually by discriminator or exact data length.
+pub trait AccountParser: Send + Sync {
+    fn owner(&self) -> &str;
+    fn matches(&self, data: &[u8]) -> bool;
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent>;
+}
+
 /// Holds all registered parsers and routes transactions to the correct one.
 pub struct ParserEngine {
     parsers: HashMap<String, Box<dyn Parser>>,
+    accounts: HashMap<String, Vec<Box<dyn AccountParser>>>,
 }
 
 impl ParserEngine {
     pub fn new() -> Self {
         Self {
             parsers: HashMap::new(),
+            accounts: HashMap::new(),
         }
     }
 
@@ -104,9 +895,16 @@ impl ParserEngine {
         self.parsers.insert(parser.program_id().to_string(), parser);
     }
 
-    /// Parse all instructions in a transaction and collect events.
+    /// Add an account decoder. An owner's decoders are tried in registration order.
+    pub fn register_account(&mut self, parser: Box<dyn AccountParser>) {
+        self.accounts.entry(parser.owner().to_string()).or_default().push(parser);
+    }
+
+    /// Parse all instructions in a transaction and collect events, followed
+    /// by an `Instruction` event for every raw instruction, outer and inner.
     pub fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
         let mut events = Vec::new();
+        let mut parsed: Vec<Vec<serde_json::Value>> = vec![Vec::new(); tx.instructions.len()];
         let programs_in_tx: std::collections::HashSet<&str> = tx
             .instructions
             .iter()
@@ -115,9 +913,65 @@ impl ParserEngine {
 
         for program_id in programs_in_tx {
             if let Some(parser) = self.parsers.get(program_id) {
-                events.extend(parser.parse(tx));
+                for event in parser.parse(tx) {
+                    if let Some(pos) = locate_instruction(tx, program_id, &event) {
+                        parsed[pos].push(serde_json::to_value(&event).unwrap_or_default());
+                    }
+                    events.push(event);
+                }
             }
         }
+
+        for (ix, mut values) in tx.instructions.iter().zip(parsed) {
+            let parsed = match values.len() {
+                0 => None,
+                1 => values.pop(),
+                _ => Some(serde_json::Value::Array(values)),
+            };
+            events.push(ParsedEvent::Instruction(InstructionEvent {
+                signature: tx.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.block_time,
+                program_id: ix.program_id.clone(),
+                instruction_index: ix.instruction_index,
+                inner_index: ix.inner_index,
+                data: ix.data.clone(),
+                accounts: ix.accounts.clone(),
+                parsed,
+            }));
+        }
         events
     }
+
+    /// Decode an account update with the first of its owner's decoders that
+    /// matches the data.
+    pub fn parse_account(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        self.accounts
+            .get(account.owner.as_str())
+            .and_then(|decoders| decoders.iter().find(|d| d.matches(&account.data)))
+            .map(|decoder| decoder.parse(account))
+            .unwrap_or_default()
+    }
+}
+
+/// Index into `tx.instructions` of the instruction an event came from. Events
+/// without a recorded position are attributed only when the producing
+/// program has a single instruction in the transaction.
+fn locate_instruction(tx: &TransactionUpdate, program_id: &str, event: &ParsedEvent) -> Option<usize> {
+    let ixs = &tx.instructions;
+    match event.instruction_position() {
+        Some((index, Some(inner))) => ixs
+            .iter()
+            .position(|i| i.instruction_index == index && i.inner_index == Some(inner)),
+        Some((index, None)) => ixs
+            .iter()
+            .position(|i| i.instruction_index == index && i.program_id == program_id),
+        None => {
+            let mut own = ixs.iter().enumerate().filter(|(_, i)| i.program_id == program_id);
+            match (own.next(), own.next()) {
+                (Some((pos, _)), None) => Some(pos),
+                _ => None,
+            }
+        }
+    }
 }
@@ -0,0 +1,421 @@
+//! Metaplex Core asset parser.
+//!
+//! Core stores an asset in a single account with plugins attached to it.
+//! Creates and transfers are also emitted as `NftMint` / `NftTransfer` so
+//! Core assets show up in the same ownership tables as Token Metadata NFTs.
+
+use serde_json::{json, Value};
+use std::io;
+
+use crate::parser::nft::{de, marketplace_for, pubkey};
+use crate::parser::{
+    CoreAssetEvent, CorePlugin, CoreUpdate, NftMintEvent, NftTransferEvent, ParsedEvent, Parser,
+    MPL_CORE_PROGRAM_ID,
+};
+use crate::receiver::{RawInstruction, TransactionUpdate};
+
+pub struct MplCoreParser;
+
+impl MplCoreParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// MplAssetInstruction discriminants
+const IX_CREATE_V1: u8 = 0;
+const IX_CREATE_COLLECTION_V1: u8 = 1;
+const IX_ADD_PLUGIN_V1: u8 = 2;
+const IX_ADD_COLLECTION_PLUGIN_V1: u8 = 3;
+const IX_REMOVE_PLUGIN_V1: u8 = 4;
+const IX_REMOVE_COLLECTION_PLUGIN_V1: u8 = 5;
+const IX_UPDATE_PLUGIN_V1: u8 = 6;
+const IX_UPDATE_COLLECTION_PLUGIN_V1: u8 = 7;
+const IX_BURN_V1: u8 = 12;
+const IX_BURN_COLLECTION_V1: u8 = 13;
+const IX_TRANSFER_V1: u8 = 14;
+const IX_UPDATE_V1: u8 = 15;
+const IX_UPDATE_COLLECTION_V1: u8 = 16;
+const IX_CREATE_V2: u8 = 20;
+const IX_CREATE_COLLECTION_V2: u8 = 21;
+
+/// `PluginType` names, indexed by discriminant.
+const PLUGIN_TYPES: [&str; 15] = [
+    "Royalties",
+    "FreezeDelegate",
+    "BurnDelegate",
+    "TransferDelegate",
+    "UpdateDelegate",
+    "PermanentFreezeDelegate",
+    "Attributes",
+    "PermanentTransferDelegate",
+    "PermanentBurnDelegate",
+    "Edition",
+    "MasterEdition",
+    "AddBlocker",
+    "ImmutableMetadata",
+    "VerifiedCreators",
+    "Autograph",
+];
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+fn plugin_type_name(discriminant: u8) -> io::Result<String> {
+    PLUGIN_TYPES
+        .get(discriminant as usize)
+        .map(|s| s.to_string())
+        .ok_or_else(|| invalid("unknown plugin type"))
+}
+
+fn read_pubkeys(buf: &mut &[u8]) -> io::Result<Vec<String>> {
+    let keys: Vec<[u8; 32]> = de(buf)?;
+    Ok(keys.into_iter().map(pubkey).collect())
+}
+
+/// Decode a `Plugin` enum into its type name and a JSON body.
+fn read_plugin(buf: &mut &[u8]) -> io::Result<(String, Value)> {
+    let discriminant: u8 = de(buf)?;
+    let data = match discriminant {
+        // Royalties { basis_points, creators, rule_set }
+        0 => {
+            let basis_points: u16 = de(buf)?;
+            let creators: Vec<([u8; 32], u8)> = de(buf)?;
+            let rule_set = match de::<u8>(buf)? {
+                0 => json!(null),
+                1 => json!({ "programAllowList": read_pubkeys(buf)? }),
+                2 => json!({ "programDenyList": read_pubkeys(buf)? }),
+                _ => return Err(invalid("unknown rule set")),
+            };
+            json!({
+                "basisPoints": basis_points,
+                "creators": creators
+                    .into_iter()
+                    .map(|(address, percentage)| json!({ "address": pubkey(address), "percentage": percentage }))
+                    .collect::<Vec<_>>(),
+                "ruleSet": rule_set,
+            })
+        }
+        // FreezeDelegate / PermanentFreezeDelegate { frozen }
+        1 | 5 => json!({ "frozen": de::<bool>(buf)? }),
+        // UpdateDelegate { additional_delegates }
+        4 => json!({ "additionalDelegates": read_pubkeys(buf)? }),
+        // Attributes { attribute_list }
+        6 => {
+            let attributes: Vec<(String, String)> = de(buf)?;
+            json!({
+                "attributeList": attributes
+                    .into_iter()
+                    .map(|(key, value)| json!({ "key": key, "value": value }))
+                    .collect::<Vec<_>>(),
+            })
+        }
+        // Edition { number }
+        9 => json!({ "number": de::<u32>(buf)? }),
+        // MasterEdition { max_supply, name, uri }
+        10 => {
+            let (max_supply, name, uri): (Option<u32>, Option<String>, Option<String>) = de(buf)?;
+            json!({ "maxSupply": max_supply, "name": name, "uri": uri })
+        }
+        // VerifiedCreators { signatures: [{ address, verified }] }
+        13 => {
+            let signatures: Vec<([u8; 32], bool)> = de(buf)?;
+            json!({
+                "signatures": signatures
+                    .into_iter()
+                    .map(|(address, verified)| json!({ "address": pubkey(address), "verified": verified }))
+                    .collect::<Vec<_>>(),
+            })
+        }
+        // Autograph { signatures: [{ address, message }] }
+        14 => {
+            let signatures: Vec<([u8; 32], String)> = de(buf)?;
+            json!({
+                "signatures": signatures
+                    .into_iter()
+                    .map(|(address, message)| json!({ "address": pubkey(address), "message": message }))
+                    .collect::<Vec<_>>(),
+            })
+        }
+        // BurnDelegate, TransferDelegate, PermanentTransferDelegate,
+        // PermanentBurnDelegate, AddBlocker, ImmutableMetadata: no fields
+        2 | 3 | 7 | 8 | 11 | 12 => json!({}),
+        _ => return Err(invalid("unknown plugin")),
+    };
+    Ok((plugin_type_name(discriminant)?, data))
+}
+
+/// Decode an `Authority` enum.
+fn read_authority(buf: &mut &[u8]) -> io::Result<String> {
+    Ok(match de::<u8>(buf)? {
+        0 => "None".into(),
+        1 => "Owner".into(),
+        2 => "UpdateAuthority".into(),
+        3 => pubkey(de(buf)?),
+        _ => return Err(invalid("unknown authority")),
+    })
+}
+
+fn read_optional_authority(buf: &mut &[u8]) -> io::Result<Option<String>> {
+    match de::<u8>(buf)? {
+        0 => Ok(None),
+        _ => read_authority(buf).map(Some),
+    }
+}
+
+/// `{ plugin: Plugin, authority: Option<Authority> }`
+fn read_plugin_with_authority(buf: &mut &[u8]) -> io::Result<CorePlugin> {
+    let (plugin_type, data) = read_plugin(buf)?;
+    Ok(CorePlugin {
+        plugin_type,
+        authority: read_optional_authority(buf)?,
+        data,
+    })
+}
+
+fn read_plugin_list(buf: &mut &[u8]) -> io::Result<Vec<CorePlugin>> {
+    if de::<u8>(buf)? == 0 {
+        return Ok(Vec::new());
+    }
+    let len: u32 = de(buf)?;
+    (0..len).map(|_| read_plugin_with_authority(buf)).collect()
+}
+
+/// `CreateV1Args` / `CreateV2Args`: `{ data_state, name, uri, plugins, .. }`
+fn decode_create(data: &[u8]) -> io::Result<(String, String, Vec<CorePlugin>)> {
+    let buf = &mut &data[1..];
+    let _data_state: u8 = de(buf)?;
+    let name: String = de(buf)?;
+    let uri: String = de(buf)?;
+    Ok((name, uri, read_plugin_list(buf)?))
+}
+
+/// `CreateCollectionV1Args` / `CreateCollectionV2Args`: `{ name, uri, plugins, .. }`
+fn decode_create_collection(data: &[u8]) -> io::Result<(String, String, Vec<CorePlugin>)> {
+    let buf = &mut &data[1..];
+    let name: String = de(buf)?;
+    let uri: String = de(buf)?;
+    Ok((name, uri, read_plugin_list(buf)?))
+}
+
+/// `UpdateV1Args { new_name, new_uri, new_update_authority }`
+fn decode_update(data: &[u8], collection: bool) -> io::Result<CoreUpdate> {
+    let buf = &mut &data[1..];
+    let name: Option<String> = de(buf)?;
+    let uri: Option<String> = de(buf)?;
+    let update_authority = if collection || buf.is_empty() || de::<u8>(buf)? == 0 {
+        None
+    } else {
+        // UpdateAuthority::{None, Address(Pubkey), Collection(Pubkey)}
+        match de::<u8>(buf)? {
+            0 => None,
+            _ => Some(pubkey(de(buf)?)),
+        }
+    };
+    Ok(CoreUpdate::Updated {
+        name,
+        uri,
+        update_authority,
+    })
+}
+
+impl MplCoreParser {
+    fn event(tx: &TransactionUpdate, address: &str, update: CoreUpdate) -> ParsedEvent {
+        ParsedEvent::CoreAsset(CoreAssetEvent {
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            address: address.to_string(),
+            update,
+        })
+    }
+
+    /// Decode one instruction; returns `None` for unhandled or malformed ones.
+    fn parse_ix(tx: &TransactionUpdate, ix: &RawInstruction) -> Option<Vec<ParsedEvent>> {
+        // Absent optional accounts are passed as the program id.
+        let acct = |i: usize| {
+            ix.accounts
+                .get(i)
+                .filter(|a| a.as_str() != MPL_CORE_PROGRAM_ID)
+                .cloned()
+        };
+
+        let events = match *ix.data.first()? {
+            IX_CREATE_V1 | IX_CREATE_V2 => {
+                // accounts: [asset, collection, authority, payer, owner, update_authority, ..]
+                let (name, uri, plugins) = decode_create(&ix.data).ok()?;
+                let asset = acct(0)?;
+                let payer = acct(3)?;
+                let collection = acct(1);
+                let owner = acct(4).unwrap_or_else(|| payer.clone());
+                let update_authority = collection.clone().or(acct(5)).unwrap_or(payer);
+                vec![
+                    ParsedEvent::NftMint(NftMintEvent {
+                        signature: tx.signature.clone(),
+                        slot: tx.slot,
+                        block_time: tx.block_time,
+                        mint: asset.clone(),
+                        owner: owner.clone(),
+                        metadata_uri: Some(uri.clone()),
+                        collection: collection.clone(),
+                    }),
+                    Self::event(
+                        tx,
+                        &asset,
+                        CoreUpdate::AssetCreated {
+                            owner,
+                            update_authority,
+                            collection,
+                            name,
+                            uri,
+                            plugins,
+                        },
+                    ),
+                ]
+            }
+            IX_CREATE_COLLECTION_V1 | IX_CREATE_COLLECTION_V2 => {
+                // accounts: [collection, update_authority, payer, ..]
+                let (name, uri, plugins) = decode_create_collection(&ix.data).ok()?;
+                let update_authority = acct(1).or(acct(2))?;
+                vec![Self::event(
+                    tx,
+                    &acct(0)?,
+                    CoreUpdate::CollectionCreated {
+                        update_authority,
+                        name,
+                        uri,
+                        plugins,
+                    },
+                )]
+            }
+            IX_TRANSFER_V1 => {
+                // accounts: [asset, collection, payer, authority, new_owner, ..]
+                let asset = acct(0)?;
+                let new_owner = acct(4)?;
+                let (marketplace, marketplace_ix) = marketplace_for(tx, ix.instruction_index);
+                vec![
+                    ParsedEvent::NftTransfer(NftTransferEvent {
+                        signature: tx.signature.clone(),
+                        slot: tx.slot,
+                        block_time: tx.block_time,
+                        mint: asset.clone(),
+                        from: None,
+                        to: new_owner.clone(),
+                        marketplace,
+                        marketplace_ix,
+                    }),
+                    Self::event(tx, &asset, CoreUpdate::Transferred { new_owner }),
+                ]
+            }
+            IX_BURN_V1 | IX_BURN_COLLECTION_V1 => {
+                vec![Self::event(tx, &acct(0)?, CoreUpdate::Burned)]
+            }
+            IX_UPDATE_V1 | IX_UPDATE_COLLECTION_V1 => {
+                let update = decode_update(&ix.data, ix.data[0] == IX_UPDATE_COLLECTION_V1).ok()?;
+                vec![Self::event(tx, &acct(0)?, update)]
+            }
+            IX_ADD_PLUGIN_V1 | IX_ADD_COLLECTION_PLUGIN_V1 => {
+                // { plugin, init_authority }
+                let plugin = read_plugin_with_authority(&mut &ix.data[1..]).ok()?;
+                vec![Self::event(tx, &acct(0)?, CoreUpdate::PluginSet(plugin))]
+            }
+            IX_UPDATE_PLUGIN_V1 | IX_UPDATE_COLLECTION_PLUGIN_V1 => {
+                // { plugin } — authority is unchanged
+                let (plugin_type, data) = read_plugin(&mut &ix.data[1..]).ok()?;
+                let plugin = CorePlugin {
+                    plugin_type,
+                    authority: None,
+                    data,
+                };
+                vec![Self::event(tx, &acct(0)?, CoreUpdate::PluginSet(plugin))]
+            }
+            IX_REMOVE_PLUGIN_V1 | IX_REMOVE_COLLECTION_PLUGIN_V1 => {
+                let plugin_type = plugin_type_name(*ix.data.get(1)?).ok()?;
+                vec![Self::event(tx, &acct(0)?, CoreUpdate::PluginRemoved { plugin_type })]
+            }
+            _ => return None,
+        };
+        Some(events)
+    }
+}
+
+impl Parser for MplCoreParser {
+    fn program_id(&self) -> &str {
+        MPL_CORE_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // Failed transactions leave assets and plugins unchanged.
+        if !tx.success {
+            return Vec::new();
+        }
+        tx.instructions
+            .iter()
+            .filter(|ix| ix.program_id == MPL_CORE_PROGRAM_ID)
+            .filter_map(|ix| Self::parse_ix(tx, ix))
+            .flatten()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tx(data: Vec<u8>, accounts: &[&str]) -> TransactionUpdate {
+        TransactionUpdate::test(vec![RawInstruction {
+            program_id: MPL_CORE_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        }])
+    }
+
+    #[test]
+    fn test_create_with_royalties_decoded() {
+        let creator = [7u8; 32];
+        let mut data = vec![IX_CREATE_V1, 0]; // data_state: AccountState
+        data.extend(borsh::to_vec(&("Core #1".to_string(), "https://x/1.json".to_string())).unwrap());
+        data.extend([1, 1, 0, 0, 0]); // plugins: Some, one entry
+        data.push(0); // Plugin::Royalties
+        data.extend(borsh::to_vec(&(500u16, vec![(creator, 100u8)], 0u8)).unwrap());
+        data.extend([1, 2]); // authority: Some(UpdateAuthority)
+
+        // No collection: the absent account is passed as the program id
+        let tx = make_tx(data, &["asset", MPL_CORE_PROGRAM_ID, "auth", "payer", "owner", "upd_auth"]);
+        let events = MplCoreParser::new().parse(&tx);
+        let [ParsedEvent::NftMint(mint), ParsedEvent::CoreAsset(asset)] = events.as_slice() else {
+            panic!("expected a mint and a Core asset, got {events:?}");
+        };
+        assert_eq!((mint.mint.as_str(), mint.owner.as_str()), ("asset", "owner"));
+        assert_eq!(mint.collection, None);
+        let CoreUpdate::AssetCreated { update_authority, name, plugins, .. } = &asset.update else {
+            panic!("expected AssetCreated, got {:?}", asset.update);
+        };
+        assert_eq!((update_authority.as_str(), name.as_str()), ("upd_auth", "Core #1"));
+        assert_eq!(plugins.len(), 1);
+        assert_eq!(plugins[0].plugin_type, "Royalties");
+        assert_eq!(plugins[0].authority.as_deref(), Some("UpdateAuthority"));
+        assert_eq!(plugins[0].data["basisPoints"], 500);
+        assert_eq!(plugins[0].data["creators"][0]["address"], pubkey(creator));
+    }
+
+    #[test]
+    fn test_transfer_decoded_and_failed_skipped() {
+        let mut tx = make_tx(
+            vec![IX_TRANSFER_V1, 0], // compression_proof: None
+            &["asset", "coll", "payer", "seller", "buyer"],
+        );
+        let events = MplCoreParser::new().parse(&tx);
+        let [ParsedEvent::NftTransfer(t), ParsedEvent::CoreAsset(asset)] = events.as_slice() else {
+            panic!("expected a transfer and a Core asset, got {events:?}");
+        };
+        assert_eq!((t.from.as_deref(), t.to.as_str()), (None, "buyer"));
+        assert!(matches!(&asset.update, CoreUpdate::Transferred { new_owner } if new_owner == "buyer"));
+
+        tx.success = false;
+        assert!(MplCoreParser::new().parse(&tx).is_empty());
+    }
+}
@@ -1,9 +1,18 @@
-//! NFT mint and transfer parser (Metaplex Token Metadata).
+//! NFT mint, transfer and metadata parser (Metaplex Token Metadata).
+//!
+//! Decodes the Borsh-encoded arguments of the legacy metadata instructions
+//! and of the token-metadata "v1" API (`Create`/`Mint`/`Transfer`/`Burn`),
+//! and metadata accounts themselves.
 
+use borsh::BorshDeserialize;
+use std::io;
+
+use crate::parser::spl_token;
 use crate::parser::{
-    NftMintEvent, NftTransferEvent, ParsedEvent, Parser, METAPLEX_PROGRAM_ID,
+    AccountParser, Creator, MetadataFields, MetadataUpdate, NftMetadataEvent, NftMintEvent, NftTransferEvent,
+    ParsedEvent, Parser, MARKETPLACE_PROGRAM_IDS, METAPLEX_PROGRAM_ID,
 };
-use crate::receiver::TransactionUpdate;
+use crate::receiver::{AccountUpdate, RawInstruction, TransactionUpdate};
 
 pub struct NftParser;
 
@@ -13,8 +22,225 @@ impl NftParser {
     }
 }
 
-const IX_CREATE_METADATA: u8 = 0; // CreateMetadataAccount
+/// Decodes `MetadataV1` accounts.
+pub struct MetadataAccountParser;
+
+impl MetadataAccountParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Token Metadata instruction discriminants
 const IX_MINT_NEW_EDITION: u8 = 11;
+const IX_UPDATE_METADATA_V2: u8 = 15;
+const IX_VERIFY_COLLECTION: u8 = 18;
+const IX_UNVERIFY_COLLECTION: u8 = 22;
+const IX_SET_AND_VERIFY_COLLECTION: u8 = 25;
+const IX_BURN_NFT: u8 = 29;
+const IX_VERIFY_SIZED_COLLECTION_ITEM: u8 = 30;
+const IX_UNVERIFY_SIZED_COLLECTION_ITEM: u8 = 31;
+const IX_SET_AND_VERIFY_SIZED_COLLECTION_ITEM: u8 = 32;
+const IX_CREATE_METADATA_V3: u8 = 33;
+const IX_BURN: u8 = 41;
+const IX_CREATE: u8 = 42;
+const IX_MINT: u8 = 43;
+const IX_VERIFY: u8 = 52;
+const IX_UNVERIFY: u8 = 53;
+
+// `VerificationArgs` variant for collection verification
+const VERIFICATION_COLLECTION_V1: u8 = 1;
+
+// Token Metadata account `Key` of a metadata account
+const KEY_METADATA_V1: u8 = 4;
+
+pub(crate) fn de<T: BorshDeserialize>(buf: &mut &[u8]) -> io::Result<T> {
+    T::deserialize(buf)
+}
+
+pub(crate) fn pubkey(bytes: [u8; 32]) -> String {
+    bs58::encode(bytes).into_string()
+}
+
+pub(crate) fn trim(s: String) -> String {
+    s.trim_end_matches('\0').to_string()
+}
+
+/// Token Metadata `Transfer` (v1) instruction discriminant.
+pub(crate) const IX_TRANSFER: u8 = 49;
+
+/// Marketplace instruction an NFT movement belongs to: the marketplace
+/// instruction within the same outer instruction, else the first marketplace
+/// instruction in the transaction. Returns `(program_id, instruction_index)`.
+pub(crate) fn marketplace_for(tx: &TransactionUpdate, instruction_index: u32) -> (Option<String>, Option<u32>) {
+    let is_marketplace = |ix: &&RawInstruction| MARKETPLACE_PROGRAM_IDS.contains(&ix.program_id.as_str());
+    let found = tx
+        .instructions
+        .iter()
+        .filter(is_marketplace)
+        .find(|ix| ix.instruction_index == instruction_index)
+        .or_else(|| tx.instructions.iter().find(is_marketplace));
+    match found {
+        Some(ix) => (Some(ix.program_id.clone()), Some(ix.instruction_index)),
+        None => (None, None),
+    }
+}
+
+/// Decode the fields shared by `DataV2` and the prefix of `AssetData`:
+/// name, symbol, uri, seller_fee_basis_points, creators.
+fn read_common(buf: &mut &[u8]) -> io::Result<MetadataFields> {
+    let name = trim(de(buf)?);
+    let symbol = trim(de(buf)?);
+    let uri = trim(de(buf)?);
+    let seller_fee_basis_points: u16 = de(buf)?;
+    let creators: Option<Vec<([u8; 32], bool, u8)>> = de(buf)?;
+    Ok(MetadataFields {
+        mint: None,
+        update_authority: None,
+        name,
+        symbol,
+        uri,
+        seller_fee_basis_points,
+        creators: creators
+            .unwrap_or_default()
+            .into_iter()
+            .map(|(address, verified, share)| Creator {
+                address: pubkey(address),
+                verified,
+                share,
+            })
+            .collect(),
+        collection: None,
+        collection_verified: false,
+        token_standard: None,
+        is_mutable: None,
+    })
+}
+
+fn read_collection(buf: &mut &[u8], fields: &mut MetadataFields) -> io::Result<()> {
+    let collection: Option<(bool, [u8; 32])> = de(buf)?;
+    if let Some((verified, key)) = collection {
+        fields.collection = Some(pubkey(key));
+        fields.collection_verified = verified;
+    }
+    Ok(())
+}
+
+/// `DataV2 { name, symbol, uri, seller_fee_basis_points, creators, collection, uses }`
+fn read_data_v2(buf: &mut &[u8]) -> io::Result<MetadataFields> {
+    let mut fields = read_common(buf)?;
+    read_collection(buf, &mut fields)?;
+    let _uses: Option<(u8, u64, u64)> = de(buf)?;
+    Ok(fields)
+}
+
+/// `CreateMetadataAccountArgsV3 { data: DataV2, is_mutable, collection_details }`
+fn decode_create_metadata_v3(data: &[u8]) -> io::Result<MetadataFields> {
+    let buf = &mut &data[1..];
+    let mut fields = read_data_v2(buf)?;
+    fields.is_mutable = Some(de(buf)?);
+    Ok(fields)
+}
+
+/// `UpdateMetadataAccountArgsV2 { data: Option<DataV2>, update_authority, .. }`
+fn decode_update_metadata_v2(data: &[u8]) -> io::Result<Option<MetadataFields>> {
+    let buf = &mut &data[1..];
+    let has_data: u8 = de(buf)?;
+    if has_data == 0 {
+        return Ok(None);
+    }
+    let mut fields = read_data_v2(buf)?;
+    let update_authority: Option<[u8; 32]> = de(buf)?;
+    fields.update_authority = update_authority.map(pubkey);
+    let _primary_sale_happened: Option<bool> = de(buf)?;
+    fields.is_mutable = de(buf)?;
+    Ok(Some(fields))
+}
+
+/// `CreateArgs::V1 { asset_data: AssetData, .. }`
+fn decode_create_v1(data: &[u8]) -> io::Result<MetadataFields> {
+    let buf = &mut data.get(2..).ok_or(io::ErrorKind::UnexpectedEof)?;
+    let mut fields = read_common(buf)?;
+    let _primary_sale_happened: bool = de(buf)?;
+    fields.is_mutable = Some(de(buf)?);
+    fields.token_standard = Some(de(buf)?);
+    read_collection(buf, &mut fields)?;
+    Ok(fields)
+}
+
+/// `Metadata { key, update_authority, mint, data: Data, primary_sale_happened,
+/// is_mutable, edition_nonce, token_standard, collection, .. }`. Accounts
+/// created before a field existed are zero-padded, which reads as `None`.
+fn decode_metadata_account(data: &[u8]) -> io::Result<MetadataFields> {
+    let buf = &mut &data[1..];
+    let update_authority: [u8; 32] = de(buf)?;
+    let mint: [u8; 32] = de(buf)?;
+    let mut fields = read_common(buf)?;
+    fields.mint = Some(pubkey(mint));
+    fields.update_authority = Some(pubkey(update_authority));
+    let _primary_sale_happened: bool = de(buf)?;
+    fields.is_mutable = Some(de(buf)?);
+    let _edition_nonce: Option<u8> = de(buf)?;
+    fields.token_standard = de(buf)?;
+    read_collection(buf, &mut fields)?;
+    Ok(fields)
+}
+
+impl AccountParser for MetadataAccountParser {
+    fn owner(&self) -> &str {
+        METAPLEX_PROGRAM_ID
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        data.first() == Some(&KEY_METADATA_V1)
+    }
+
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        decode_metadata_account(&account.data)
+            .map(|fields| {
+                ParsedEvent::NftMetadata(NftMetadataEvent {
+                    signature: String::new(),
+                    slot: account.slot,
+                    block_time: 0,
+                    metadata: account.pubkey.clone(),
+                    update: MetadataUpdate::Upsert(fields),
+                })
+            })
+            .into_iter()
+            .collect()
+    }
+}
+
+impl NftParser {
+    fn metadata_event(
+        tx: &TransactionUpdate,
+        ix: &RawInstruction,
+        metadata_idx: usize,
+        update: MetadataUpdate,
+    ) -> Option<ParsedEvent> {
+        Some(ParsedEvent::NftMetadata(NftMetadataEvent {
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            metadata: ix.accounts.get(metadata_idx)?.clone(),
+            update,
+        }))
+    }
+
+    /// An `NftMint` if this transaction mints one whole token of the 0-decimal,
+    /// single-supply `mint`; metadata is also created for fungible mints.
+    fn mint_event(tx: &TransactionUpdate, mint: &str, fields: Option<&MetadataFields>) -> Option<ParsedEvent> {
+        Some(ParsedEvent::NftMint(NftMintEvent {
+            signature: tx.signature.clone(),
+            slot: tx.slot,
+            block_time: tx.block_time,
+            mint: mint.to_string(),
+            owner: spl_token::nft_minted_to(tx, mint)?,
+            metadata_uri: fields.map(|f| f.uri.clone()),
+            collection: fields.and_then(|f| f.collection.clone()),
+        }))
+    }
+}
 
 impl Parser for NftParser {
     fn program_id(&self) -> &str {
@@ -22,7 +248,14 @@ impl Parser for NftParser {
     }
 
     fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // Failed transactions leave metadata and ownership unchanged.
+        if !tx.success {
+            return Vec::new();
+        }
         let mut events = Vec::new();
+        // Metadata created by `Create` (v1), so a later `Mint` in the same
+        // transaction can carry its uri and collection.
+        let mut created: Vec<MetadataFields> = Vec::new();
 
         for ix in &tx.instructions {
             if ix.program_id != METAPLEX_PROGRAM_ID {
@@ -31,33 +264,126 @@ impl Parser for NftParser {
             if ix.data.is_empty() {
                 continue;
             }
+            let acct = |i: usize| ix.accounts.get(i).map(String::as_str).unwrap_or_default();
+
             match ix.data[0] {
-                IX_CREATE_METADATA => {
-                    if ix.accounts.len() < 4 {
+                IX_CREATE_METADATA_V3 => {
+                    // accounts: [metadata, mint, mint_authority, payer, update_authority, ..]
+                    let Ok(mut fields) = decode_create_metadata_v3(&ix.data) else {
+                        continue;
+                    };
+                    if ix.accounts.len() < 5 {
                         continue;
                     }
-                    events.push(ParsedEvent::NftMint(NftMintEvent {
-                        signature: tx.signature.clone(),
-                        block_time: tx.block_time,
-                        mint: ix.accounts.get(1).cloned().unwrap_or_default(),
-                        owner: ix.accounts.get(3).cloned().unwrap_or_default(),
-                        metadata_uri: None,
-                        collection: None,
-                    }));
+                    fields.mint = Some(acct(1).to_string());
+                    fields.update_authority = Some(acct(4).to_string());
+                    events.extend(Self::mint_event(tx, acct(1), Some(&fields)));
+                    events.extend(Self::metadata_event(tx, ix, 0, MetadataUpdate::Upsert(fields)));
+                }
+                IX_CREATE => {
+                    // accounts: [metadata, master_edition, mint, authority, payer, update_authority, ..]
+                    let Ok(mut fields) = decode_create_v1(&ix.data) else {
+                        continue;
+                    };
+                    if ix.accounts.len() < 6 {
+                        continue;
+                    }
+                    fields.mint = Some(acct(2).to_string());
+                    fields.update_authority = Some(acct(5).to_string());
+                    created.push(fields.clone());
+                    events.extend(Self::metadata_event(tx, ix, 0, MetadataUpdate::Upsert(fields)));
+                }
+                IX_MINT => {
+                    // accounts: [token, token_owner, metadata, master_edition, token_record, mint, ..]
+                    if ix.accounts.len() < 6 {
+                        continue;
+                    }
+                    let fields = created.iter().find(|f| f.mint.as_deref() == Some(acct(5)));
+                    events.extend(Self::mint_event(tx, acct(5), fields));
                 }
                 IX_MINT_NEW_EDITION => {
-                    // edition mint — treat as NFT transfer to new owner
-                    if ix.accounts.len() < 2 {
+                    // accounts: [new_metadata, new_edition, master_edition, new_mint, edition_mark,
+                    //            new_mint_authority, ..]
+                    if ix.accounts.len() < 6 {
                         continue;
                     }
+                    events.extend(Self::mint_event(tx, acct(3), None));
+                }
+                IX_UPDATE_METADATA_V2 => {
+                    // accounts: [metadata, update_authority]
+                    if let Ok(Some(fields)) = decode_update_metadata_v2(&ix.data) {
+                        events.extend(Self::metadata_event(tx, ix, 0, MetadataUpdate::Upsert(fields)));
+                    }
+                }
+                IX_VERIFY_COLLECTION
+                | IX_SET_AND_VERIFY_COLLECTION
+                | IX_VERIFY_SIZED_COLLECTION_ITEM
+                | IX_SET_AND_VERIFY_SIZED_COLLECTION_ITEM
+                | IX_UNVERIFY_COLLECTION
+                | IX_UNVERIFY_SIZED_COLLECTION_ITEM => {
+                    // accounts: [metadata, collection_authority, payer, collection_mint, ..]
+                    // SetAndVerify* insert update_authority before collection_mint;
+                    // UnverifyCollection has no payer.
+                    let collection_idx = match ix.data[0] {
+                        IX_SET_AND_VERIFY_COLLECTION | IX_SET_AND_VERIFY_SIZED_COLLECTION_ITEM => 4,
+                        IX_UNVERIFY_COLLECTION => 2,
+                        _ => 3,
+                    };
+                    let verified = !matches!(
+                        ix.data[0],
+                        IX_UNVERIFY_COLLECTION | IX_UNVERIFY_SIZED_COLLECTION_ITEM
+                    );
+                    let Some(collection) = ix.accounts.get(collection_idx).cloned() else {
+                        continue;
+                    };
+                    events.extend(Self::metadata_event(
+                        tx,
+                        ix,
+                        0,
+                        MetadataUpdate::Collection { collection, verified },
+                    ));
+                }
+                IX_VERIFY | IX_UNVERIFY => {
+                    // accounts: [authority, delegate_record, metadata, collection_mint, ..]
+                    if ix.data.get(1) != Some(&VERIFICATION_COLLECTION_V1) {
+                        continue;
+                    }
+                    let Some(collection) = ix.accounts.get(3).cloned() else {
+                        continue;
+                    };
+                    let verified = ix.data[0] == IX_VERIFY;
+                    events.extend(Self::metadata_event(
+                        tx,
+                        ix,
+                        2,
+                        MetadataUpdate::Collection { collection, verified },
+                    ));
+                }
+                IX_TRANSFER => {
+                    // accounts: [token, token_owner, destination_token, destination_owner, mint, ..]
+                    if ix.accounts.len() < 5 {
+                        continue;
+                    }
+                    let (marketplace, marketplace_ix) = marketplace_for(tx, ix.instruction_index);
                     events.push(ParsedEvent::NftTransfer(NftTransferEvent {
                         signature: tx.signature.clone(),
+                        slot: tx.slot,
                         block_time: tx.block_time,
-                        mint: ix.accounts.get(1).cloned().unwrap_or_default(),
-                        from: String::new(),
-                        to: ix.accounts.first().cloned().unwrap_or_default(),
+                        mint: acct(4).to_string(),
+                        from: Some(acct(1).to_string()),
+                        to: acct(3).to_string(),
+                        marketplace,
+                        marketplace_ix,
                     }));
                 }
+                IX_BURN_NFT => {
+                    // accounts: [metadata, owner, mint, ..]
+                    events.extend(Self::metadata_event(tx, ix, 0, MetadataUpdate::Burned));
+                }
+                IX_BURN => {
+                    // accounts: [authority, collection_metadata, metadata, ..]
+                    events.extend(Self::metadata_event(tx, ix, 2, MetadataUpdate::Burned));
+                }
                 _ => {}
             }
         }
@@ -65,3 +391,90 @@ impl Parser for NftParser {
         events
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SPL_TOKEN_PROGRAM_ID;
+    use crate::receiver::TokenBalance;
+
+    fn borsh_str(out: &mut Vec<u8>, s: &str) {
+        out.extend_from_slice(&(s.len() as u32).to_le_bytes());
+        out.extend_from_slice(s.as_bytes());
+    }
+
+    #[test]
+    fn test_create_metadata_v3_decoded() {
+        let collection = [7u8; 32];
+        let mut data = vec![IX_CREATE_METADATA_V3];
+        borsh_str(&mut data, "Mad Lad #1\0\0");
+        borsh_str(&mut data, "MAD");
+        borsh_str(&mut data, "https://arweave.net/x");
+        data.extend_from_slice(&500u16.to_le_bytes());
+        data.push(1); // creators: Some
+        data.extend_from_slice(&1u32.to_le_bytes());
+        data.extend_from_slice(&[9u8; 32]);
+        data.extend_from_slice(&[1, 100]); // verified, share
+        data.push(1); // collection: Some
+        data.push(0); // unverified
+        data.extend_from_slice(&collection);
+        data.push(0); // uses: None
+        data.push(1); // is_mutable
+        data.push(0); // collection_details: None
+
+        let create = RawInstruction {
+            program_id: METAPLEX_PROGRAM_ID.into(),
+            instruction_index: 1,
+            inner_index: None,
+            data,
+            accounts: ["md", "mint", "mint_auth", "payer", "upd_auth"].map(String::from).to_vec(),
+        };
+        let mut mint_to = vec![7];
+        mint_to.extend_from_slice(&1u64.to_le_bytes());
+
+        // Without a MintTo the metadata may belong to a fungible mint.
+        let mut tx = TransactionUpdate::test(vec![create.clone()]);
+        let events = NftParser::new().parse(&tx);
+        assert!(matches!(events.as_slice(), [ParsedEvent::NftMetadata(_)]));
+
+        tx.instructions.insert(
+            0,
+            RawInstruction {
+                program_id: SPL_TOKEN_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: None,
+                data: mint_to,
+                accounts: ["mint", "alice_ata", "mint_auth"].map(String::from).to_vec(),
+            },
+        );
+        tx.post_token_balances = vec![TokenBalance {
+            account: "alice_ata".into(),
+            mint: "mint".into(),
+            owner: "alice".into(),
+            amount: 1,
+            decimals: 0,
+        }];
+
+        let events = NftParser::new().parse(&tx);
+        assert_eq!(events.len(), 2);
+        let ParsedEvent::NftMint(mint) = &events[0] else {
+            panic!("Expected NftMint");
+        };
+        assert_eq!(mint.owner, "alice");
+        assert_eq!(mint.metadata_uri.as_deref(), Some("https://arweave.net/x"));
+        assert_eq!(mint.collection, Some(pubkey(collection)));
+        let ParsedEvent::NftMetadata(NftMetadataEvent { update: MetadataUpdate::Upsert(f), .. }) = &events[1] else {
+            panic!("Expected NftMetadata upsert");
+        };
+        assert_eq!(f.name, "Mad Lad #1");
+        assert_eq!(f.seller_fee_basis_points, 500);
+        assert_eq!(f.creators.len(), 1);
+        assert_eq!(f.creators[0].share, 100);
+        assert_eq!(f.is_mutable, Some(true));
+
+        let failed = TransactionUpdate { success: false, ..tx };
+        assert!(NftParser::new().parse(&failed).is_empty());
+
+        assert!(decode_create_v1(&[IX_CREATE]).is_err());
+    }
+}
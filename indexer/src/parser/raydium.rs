@@ -1,7 +1,11 @@
 //! Raydium AMM parser.
+//!
+//! Swaps are resolved from the token transfers the AMM invokes, and pools are
+//! registered from `Initialize2` and from `AmmInfo` account updates.
 
-use crate::parser::{ParsedEvent, Parser, SwapEvent, RAYDIUM_AMM_PROGRAM_ID};
-use crate::receiver::TransactionUpdate;
+use crate::parser::dex::{self, Leg};
+use crate::parser::{AccountParser, ParsedEvent, Parser, PoolEvent, RAYDIUM_AMM_PROGRAM_ID};
+use crate::receiver::{AccountUpdate, TransactionUpdate};
 
 pub struct RaydiumParser;
 
@@ -11,19 +15,45 @@ impl RaydiumParser {
     }
 }
 
-// Raydium AMM swap instruction discriminant
+/// Decodes `AmmInfo` pool state accounts.
+pub struct AmmInfoParser;
+
+impl AmmInfoParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Raydium AMM instruction discriminants
+const IX_INITIALIZE2: u8 = 1;
 const IX_SWAP_BASE_IN: u8 = 9;
 const IX_SWAP_BASE_OUT: u8 = 11;
 
+// AmmInfo account layout (752 bytes)
+const AMM_INFO_LEN: usize = 752;
+const AMM_COIN_DECIMALS_OFFSET: usize = 32;
+const AMM_PC_DECIMALS_OFFSET: usize = 40;
+const AMM_SWAP_FEE_NUMERATOR_OFFSET: usize = 176;
+const AMM_SWAP_FEE_DENOMINATOR_OFFSET: usize = 184;
+const AMM_COIN_VAULT_OFFSET: usize = 336;
+const AMM_PC_VAULT_OFFSET: usize = 368;
+const AMM_COIN_MINT_OFFSET: usize = 400;
+const AMM_PC_MINT_OFFSET: usize = 432;
+
 impl Parser for RaydiumParser {
     fn program_id(&self) -> &str {
         RAYDIUM_AMM_PROGRAM_ID
     }
 
     fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // A reverted swap still carries its inner transfers in the instruction
+        // list, so it would otherwise be recorded as a trade.
+        if !tx.success {
+            return Vec::new();
+        }
         let mut events = Vec::new();
 
-        for ix in &tx.instructions {
+        for (pos, ix) in tx.instructions.iter().enumerate() {
             if ix.program_id != RAYDIUM_AMM_PROGRAM_ID {
                 continue;
             }
@@ -32,20 +62,57 @@ impl Parser for RaydiumParser {
             }
             match ix.data[0] {
                 IX_SWAP_BASE_IN | IX_SWAP_BASE_OUT => {
-                    // accounts: [amm, ...token_vaults..., user_source, user_dest, user_owner]
-                    events.push(ParsedEvent::Swap(SwapEvent {
-                        signature: tx.signature.clone(),
-                        block_time: tx.block_time,
+                    // accounts: [token_program, amm, ...vaults/serum..., user_source, user_dest, user_owner]
+                    let n = ix.accounts.len();
+                    if n < 4 {
+                        continue;
+                    }
+                    let user_source = &ix.accounts[n - 3];
+                    let user_dest = &ix.accounts[n - 2];
+                    let transfers = dex::inner_transfers(tx, pos);
+                    let Some(amount_in) = transfers.iter().find(|t| &t.source == user_source) else {
+                        continue;
+                    };
+                    let Some(amount_out) = transfers.iter().find(|t| &t.destination == user_dest) else {
+                        continue;
+                    };
+                    let (Some(input), Some(output)) = (
+                        dex::leg(tx, user_source, amount_in.amount),
+                        dex::leg(tx, user_dest, amount_out.amount),
+                    ) else {
+                        continue;
+                    };
+                    events.push(ParsedEvent::Swap(dex::build_swap(
+                        tx,
+                        ix,
+                        RAYDIUM_AMM_PROGRAM_ID,
+                        ix.accounts.get(1).cloned(),
+                        ix.accounts[n - 1].clone(),
+                        input,
+                        output,
+                    )));
+                }
+                IX_INITIALIZE2 => {
+                    // accounts: [.., 4 amm, .., 8 coin_mint, 9 pc_mint, 10 coin_vault, 11 pc_vault, ..]
+                    if ix.accounts.len() < 12 {
+                        continue;
+                    }
+                    let decimals = |vault: &str| tx.token_balance(vault).map(|b| b.decimals);
+                    let coin = (&ix.accounts[8], &ix.accounts[10]);
+                    let pc = (&ix.accounts[9], &ix.accounts[11]);
+                    let (base, quote) = if dex::orient(coin.0, pc.0) { (coin, pc) } else { (pc, coin) };
+                    events.push(ParsedEvent::Pool(PoolEvent {
                         program: RAYDIUM_AMM_PROGRAM_ID.into(),
-                        input_mint: ix.accounts.get(14).cloned().unwrap_or_default(),
-                        output_mint: ix.accounts.get(15).cloned().unwrap_or_default(),
-                        input_amount: if ix.data.len() >= 9 {
-                            u64::from_le_bytes(ix.data[1..9].try_into().unwrap_or_default())
-                        } else {
-                            0
-                        },
-                        output_amount: 0,
-                        user: ix.accounts.last().cloned().unwrap_or_default(),
+                        address: ix.accounts[4].clone(),
+                        slot: tx.slot,
+                        base_mint: base.0.clone(),
+                        quote_mint: quote.0.clone(),
+                        base_vault: Some(base.1.clone()),
+                        quote_vault: Some(quote.1.clone()),
+                        base_decimals: decimals(base.1),
+                        quote_decimals: decimals(quote.1),
+                        fee_bps: None,
+                        created: true,
                     }));
                 }
                 _ => {}
@@ -55,3 +122,114 @@ impl Parser for RaydiumParser {
         events
     }
 }
+
+impl AccountParser for AmmInfoParser {
+    fn owner(&self) -> &str {
+        RAYDIUM_AMM_PROGRAM_ID
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        data.len() == AMM_INFO_LEN
+    }
+
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        decode_amm_info(account).map(ParsedEvent::Pool).into_iter().collect()
+    }
+}
+
+/// Decode an `AmmInfo` account into a pool registry entry.
+fn decode_amm_info(account: &AccountUpdate) -> Option<PoolEvent> {
+    let data = &account.data;
+    if data.len() != AMM_INFO_LEN {
+        return None;
+    }
+    let u64_at = |off: usize| u64::from_le_bytes(data[off..off + 8].try_into().unwrap_or_default());
+    let key_at = |off: usize| bs58::encode(&data[off..off + 32]).into_string();
+
+    let coin = Leg {
+        mint: key_at(AMM_COIN_MINT_OFFSET),
+        amount: 0,
+        decimals: Some(u64_at(AMM_COIN_DECIMALS_OFFSET) as u8),
+    };
+    let pc = Leg {
+        mint: key_at(AMM_PC_MINT_OFFSET),
+        amount: 0,
+        decimals: Some(u64_at(AMM_PC_DECIMALS_OFFSET) as u8),
+    };
+    let coin_vault = key_at(AMM_COIN_VAULT_OFFSET);
+    let pc_vault = key_at(AMM_PC_VAULT_OFFSET);
+    let ((base, base_vault), (quote, quote_vault)) = if dex::orient(&coin.mint, &pc.mint) {
+        ((coin, coin_vault), (pc, pc_vault))
+    } else {
+        ((pc, pc_vault), (coin, coin_vault))
+    };
+
+    let fee_den = u64_at(AMM_SWAP_FEE_DENOMINATOR_OFFSET);
+    let fee_bps = (fee_den > 0)
+        .then(|| (u64_at(AMM_SWAP_FEE_NUMERATOR_OFFSET) * 10_000 / fee_den) as u32);
+
+    Some(PoolEvent {
+        program: RAYDIUM_AMM_PROGRAM_ID.into(),
+        address: account.pubkey.clone(),
+        slot: account.slot,
+        base_mint: base.mint,
+        quote_mint: quote.mint,
+        base_vault: Some(base_vault),
+        quote_vault: Some(quote_vault),
+        base_decimals: base.decimals,
+        quote_decimals: quote.decimals,
+        fee_bps,
+        created: false,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{SPL_TOKEN_PROGRAM_ID, USDC_MINT};
+    use crate::receiver::{RawInstruction, TokenBalance};
+
+    #[test]
+    fn test_swap_parsed_unless_failed() {
+        let transfer = |source: &str, destination: &str, amount: u64| {
+            let mut data = vec![3];
+            data.extend_from_slice(&amount.to_le_bytes());
+            RawInstruction {
+                program_id: SPL_TOKEN_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: Some(0),
+                data,
+                accounts: vec![source.into(), destination.into(), "authority".into()],
+            }
+        };
+        let mut tx = TransactionUpdate::test(vec![
+            RawInstruction {
+                program_id: RAYDIUM_AMM_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: None,
+                data: vec![IX_SWAP_BASE_IN],
+                accounts: ["token", "amm", "user_usdc", "user_bonk", "user"].map(String::from).to_vec(),
+            },
+            transfer("user_usdc", "vault_usdc", 5_000_000),
+            transfer("vault_bonk", "user_bonk", 42),
+        ]);
+        let balance = |account: &str, mint: &str, decimals| TokenBalance {
+            account: account.into(),
+            mint: mint.into(),
+            owner: "user".into(),
+            amount: 0,
+            decimals,
+        };
+        tx.post_token_balances = vec![balance("user_usdc", USDC_MINT, 6), balance("user_bonk", "BONK", 5)];
+
+        let events = RaydiumParser::new().parse(&tx);
+        let [ParsedEvent::Swap(swap)] = events.as_slice() else {
+            panic!("expected one swap, got {events:?}");
+        };
+        assert_eq!(swap.pool.as_deref(), Some("amm"));
+        assert_eq!(swap.user, "user");
+
+        tx.success = false;
+        assert!(RaydiumParser::new().parse(&tx).is_empty());
+    }
+}
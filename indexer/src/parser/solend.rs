@@ -0,0 +1,194 @@
+//! Solend parser (SPL token-lending instruction set).
+
+use crate::parser::lending::{self, amount_arg, mint_of, received_by, sent_from};
+use crate::parser::{LendingAction, ParsedEvent, Parser, SOLEND_PROGRAM_ID};
+use crate::receiver::TransactionUpdate;
+
+pub struct SolendParser;
+
+impl SolendParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Token-lending instruction discriminants
+const IX_BORROW: u8 = 10;
+const IX_REPAY: u8 = 11;
+const IX_LIQUIDATE: u8 = 12;
+const IX_DEPOSIT: u8 = 14;
+const IX_WITHDRAW: u8 = 15;
+const IX_LIQUIDATE_AND_REDEEM: u8 = 17;
+
+impl Parser for SolendParser {
+    fn program_id(&self) -> &str {
+        SOLEND_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            if ix.program_id != SOLEND_PROGRAM_ID || ix.data.is_empty() {
+                continue;
+            }
+            let acct = |i: usize| ix.accounts.get(i).cloned();
+            let arg = amount_arg(&ix.data, 1);
+            let sent = |i: usize| acct(i).and_then(|a| sent_from(tx, pos, &a));
+            let received = |i: usize| acct(i).and_then(|a| received_by(tx, pos, &a));
+            let mint = |i: usize| acct(i).and_then(|a| mint_of(tx, &a));
+
+            let event = match ix.data[0] {
+                IX_DEPOSIT => {
+                    // [source_liquidity, user_collateral, reserve, liquidity_supply, collateral_mint,
+                    //  market, market_authority, destination_collateral, obligation, obligation_owner, ..]
+                    let (Some(reserve), Some(obligation)) = (acct(2), acct(8)) else { continue };
+                    let mut e = lending::event(tx, ix, SOLEND_PROGRAM_ID, LendingAction::Deposit, reserve, obligation);
+                    e.owner = acct(9);
+                    e.mint = mint(0);
+                    e.amount = sent(0).or(arg);
+                    e
+                }
+                IX_WITHDRAW => {
+                    // [source_collateral, destination_collateral, reserve, obligation, market,
+                    //  market_authority, destination_liquidity, collateral_mint, liquidity_supply,
+                    //  obligation_owner, ..]; the argument is a collateral amount
+                    let (Some(reserve), Some(obligation)) = (acct(2), acct(3)) else { continue };
+                    let mut e = lending::event(tx, ix, SOLEND_PROGRAM_ID, LendingAction::Withdraw, reserve, obligation);
+                    e.owner = acct(9);
+                    e.mint = mint(6);
+                    e.amount = received(6);
+                    e
+                }
+                IX_BORROW => {
+                    // [source_liquidity, destination_liquidity, reserve, fee_receiver, obligation,
+                    //  market, market_authority, obligation_owner, ..]
+                    let (Some(reserve), Some(obligation)) = (acct(2), acct(4)) else { continue };
+                    let mut e = lending::event(tx, ix, SOLEND_PROGRAM_ID, LendingAction::Borrow, reserve, obligation);
+                    e.owner = acct(7);
+                    e.mint = mint(1);
+                    e.amount = received(1).or(arg);
+                    e
+                }
+                IX_REPAY => {
+                    // [source_liquidity, destination_liquidity, reserve, obligation, market,
+                    //  user_transfer_authority, ..]
+                    let (Some(reserve), Some(obligation)) = (acct(2), acct(3)) else { continue };
+                    let mut e = lending::event(tx, ix, SOLEND_PROGRAM_ID, LendingAction::Repay, reserve, obligation);
+                    e.owner = acct(5);
+                    e.mint = mint(0);
+                    e.amount = sent(0).or(arg);
+                    e
+                }
+                IX_LIQUIDATE => {
+                    // [source_liquidity, destination_collateral, repay_reserve, repay_supply,
+                    //  withdraw_reserve, withdraw_collateral_supply, obligation, market,
+                    //  market_authority, user_transfer_authority, ..]
+                    let (Some(reserve), Some(obligation)) = (acct(2), acct(6)) else { continue };
+                    let mut e =
+                        lending::event(tx, ix, SOLEND_PROGRAM_ID, LendingAction::Liquidate, reserve, obligation.clone());
+                    e.mint = mint(0);
+                    e.amount = sent(0).or(arg);
+                    e.liquidator = acct(9);
+                    e.liquidatee = Some(obligation);
+                    e.collateral_reserve = acct(4);
+                    e.collateral_mint = mint(1);
+                    e.collateral_amount = received(1);
+                    e
+                }
+                IX_LIQUIDATE_AND_REDEEM => {
+                    // [source_liquidity, destination_collateral, destination_liquidity, repay_reserve,
+                    //  repay_supply, withdraw_reserve, withdraw_collateral_mint, withdraw_collateral_supply,
+                    //  withdraw_liquidity_supply, fee_receiver, obligation, market, market_authority,
+                    //  user_transfer_authority, ..]
+                    let (Some(reserve), Some(obligation)) = (acct(3), acct(10)) else { continue };
+                    let mut e =
+                        lending::event(tx, ix, SOLEND_PROGRAM_ID, LendingAction::Liquidate, reserve, obligation.clone());
+                    e.mint = mint(0);
+                    e.amount = sent(0).or(arg);
+                    e.liquidator = acct(13);
+                    e.liquidatee = Some(obligation);
+                    e.collateral_reserve = acct(5);
+                    e.collateral_mint = mint(2);
+                    e.collateral_amount = received(2);
+                    e
+                }
+                _ => continue,
+            };
+            events.push(ParsedEvent::Lending(event));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::SPL_TOKEN_PROGRAM_ID;
+    use crate::receiver::{RawInstruction, TokenBalance};
+
+    #[test]
+    fn test_liquidation_parsed() {
+        let mut data = vec![IX_LIQUIDATE_AND_REDEEM];
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+        let transfer = |source: &str, destination: &str, amount: u64| {
+            let mut data = vec![3u8];
+            data.extend_from_slice(&amount.to_le_bytes());
+            RawInstruction {
+                program_id: SPL_TOKEN_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: Some(0),
+                data,
+                accounts: vec![source.into(), destination.into(), "auth".into()],
+            }
+        };
+        let balance = |account: &str, mint: &str| TokenBalance {
+            account: account.into(),
+            mint: mint.into(),
+            owner: "liquidator".into(),
+            amount: 0,
+            decimals: 6,
+        };
+        let accounts = [
+            "usdc_src", "c_dst", "sol_dst", "usdc_reserve", "usdc_supply", "sol_reserve", "c_mint",
+            "c_supply", "sol_supply", "fee", "obligation", "market", "market_auth", "liquidator",
+        ];
+        let mut tx = TransactionUpdate::test(vec![
+            RawInstruction {
+                program_id: SOLEND_PROGRAM_ID.into(),
+                instruction_index: 0,
+                inner_index: None,
+                data,
+                accounts: accounts.iter().map(|a| a.to_string()).collect(),
+            },
+            transfer("usdc_src", "usdc_supply", 150_000_000),
+            transfer("c_supply", "c_dst", 900_000_000),
+            transfer("sol_supply", "sol_dst", 1_050_000_000),
+        ]);
+        tx.post_token_balances = vec![balance("usdc_src", "usdc"), balance("sol_dst", "wsol")];
+
+        let events = SolendParser::new().parse(&tx);
+        assert_eq!(events.len(), 1);
+        if let ParsedEvent::Lending(e) = &events[0] {
+            assert_eq!(e.action, LendingAction::Liquidate);
+            assert_eq!(e.reserve, "usdc_reserve");
+            assert_eq!(e.mint.as_deref(), Some("usdc"));
+            assert_eq!(e.amount, Some(150_000_000));
+            assert_eq!(e.liquidator.as_deref(), Some("liquidator"));
+            assert_eq!(e.liquidatee.as_deref(), Some("obligation"));
+            assert_eq!(e.collateral_mint.as_deref(), Some("wsol"));
+            assert_eq!(e.collateral_amount, Some(1_050_000_000));
+        } else {
+            panic!("Expected Lending");
+        }
+
+        // A failed attempt has no inner transfers and must not be recorded.
+        tx.success = false;
+        tx.instructions.truncate(1);
+        assert!(SolendParser::new().parse(&tx).is_empty());
+    }
+}
@@ -1,37 +1,133 @@
 //! SPL Token transfer parser.
 //!
 //! Decodes transfer, transferChecked, mint, and burn instructions from the
-//! SPL Token and Token-2022 programs.
+//! SPL Token and Token-2022 programs. Movements of single-supply, 0-decimal
+//! mints are additionally reported as NFT transfers between wallet owners.
+//! `InitializeMint` registers the mint, and mints and burns of successful
+//! transactions are reported as supply changes. One parser is registered per
+//! token program, since the engine routes by program id.
 
+use crate::parser::nft;
 use crate::parser::{
-    ParsedEvent, Parser, TokenTransferEvent, SPL_TOKEN_2022_PROGRAM_ID, SPL_TOKEN_PROGRAM_ID,
+    MintEvent, NftTransferEvent, ParsedEvent, Parser, SupplyChange, TokenSupplyEvent, TokenTransferEvent,
+    METAPLEX_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID, SPL_TOKEN_PROGRAM_ID,
 };
-use crate::receiver::TransactionUpdate;
+use crate::receiver::{RawInstruction, TransactionUpdate};
 
-pub struct SplTokenParser;
+/// Decodes instructions of one token program.
+pub struct SplTokenParser {
+    program: &'static str,
+}
 
 impl SplTokenParser {
-    pub fn new() -> Self {
-        Self
+    pub fn new(program: &'static str) -> Self {
+        Self { program }
     }
 }
 
 // Instruction discriminants for the SPL Token program
+const IX_INITIALIZE_MINT: u8 = 0;
 const IX_TRANSFER: u8 = 3;
+const IX_MINT_TO: u8 = 7;
+const IX_BURN: u8 = 8;
 const IX_TRANSFER_CHECKED: u8 = 12;
+const IX_MINT_TO_CHECKED: u8 = 14;
+const IX_BURN_CHECKED: u8 = 15;
+const IX_INITIALIZE_MINT2: u8 = 20;
+
+/// `InitializeMint{,2} { decimals: u8, mint_authority: Pubkey, freeze_authority: COption<Pubkey> }`
+/// with a one-byte option tag; accounts `[mint, ..]`.
+fn decode_initialize_mint(tx: &TransactionUpdate, ix: &RawInstruction) -> Option<MintEvent> {
+    let d = &ix.data;
+    if d.len() < 35 {
+        return None;
+    }
+    let key = |off: usize| bs58::encode(&d[off..off + 32]).into_string();
+    Some(MintEvent {
+        address: ix.accounts.first()?.clone(),
+        slot: tx.slot,
+        program: ix.program_id.clone(),
+        decimals: d[1],
+        supply: 0,
+        mint_authority: Some(key(2)),
+        freeze_authority: (d[34] == 1 && d.len() >= 67).then(|| key(35)),
+        is_initialized: true,
+        extensions: None,
+        name: None,
+        symbol: None,
+    })
+}
+
+/// `MintTo{,Checked}` (accounts `[mint, destination, ..]`) and
+/// `Burn{,Checked}` (accounts `[account, mint, ..]`), amount at byte 1.
+fn decode_supply_change(tx: &TransactionUpdate, ix: &RawInstruction, change: SupplyChange) -> Option<TokenSupplyEvent> {
+    let amount = u64::from_le_bytes(ix.data.get(1..9)?.try_into().ok()?);
+    let (mint, account) = match change {
+        SupplyChange::Mint => (ix.accounts.first()?, ix.accounts.get(1)?),
+        SupplyChange::Burn => (ix.accounts.get(1)?, ix.accounts.first()?),
+    };
+    Some(TokenSupplyEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        instruction_index: ix.instruction_index,
+        inner_index: ix.inner_index,
+        mint: mint.clone(),
+        account: account.clone(),
+        change,
+        amount,
+    })
+}
+
+/// Heuristic for an NFT movement: one whole token of a 0-decimal mint whose
+/// balances across every account this transaction touches add up to 1.
+pub(crate) fn is_nft_movement(tx: &TransactionUpdate, mint: &str, amount: u64, decimals: u8) -> bool {
+    if amount != 1 || decimals != 0 {
+        return false;
+    }
+    let held: u64 = tx
+        .post_token_balances
+        .iter()
+        .filter(|b| b.mint == mint)
+        .map(|b| b.amount)
+        .sum();
+    held == 1
+}
+
+/// Wallet an NFT of `mint` is minted to by a `MintTo` in this transaction;
+/// `None` unless one whole token of a 0-decimal, single-supply mint is minted.
+pub(crate) fn nft_minted_to(tx: &TransactionUpdate, mint: &str) -> Option<String> {
+    tx.instructions
+        .iter()
+        .filter(|ix| ix.program_id == SPL_TOKEN_PROGRAM_ID || ix.program_id == SPL_TOKEN_2022_PROGRAM_ID)
+        .filter(|ix| matches!(ix.data.first(), Some(&(IX_MINT_TO | IX_MINT_TO_CHECKED))))
+        .filter_map(|ix| decode_supply_change(tx, ix, SupplyChange::Mint))
+        .find_map(|m| {
+            let balance = tx.token_balance(&m.account)?;
+            (m.mint == mint && is_nft_movement(tx, mint, m.amount, balance.decimals)).then(|| balance.owner.clone())
+        })
+}
+
+/// True if a Token Metadata `Transfer` in this transaction moves `mint`; the
+/// NFT parser already emits that (pNFT) transfer.
+fn has_metadata_transfer(tx: &TransactionUpdate, mint: &str) -> bool {
+    tx.instructions.iter().any(|ix| {
+        ix.program_id == METAPLEX_PROGRAM_ID
+            && ix.data.first() == Some(&nft::IX_TRANSFER)
+            && ix.accounts.get(4).map(String::as_str) == Some(mint)
+    })
+}
 
 impl Parser for SplTokenParser {
     fn program_id(&self) -> &str {
-        SPL_TOKEN_PROGRAM_ID
+        self.program
     }
 
     fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
         let mut events = Vec::new();
 
         for ix in &tx.instructions {
-            if ix.program_id != SPL_TOKEN_PROGRAM_ID
-                && ix.program_id != SPL_TOKEN_2022_PROGRAM_ID
-            {
+            if ix.program_id != self.program {
                 continue;
             }
             if ix.data.is_empty() {
@@ -39,21 +135,40 @@ impl Parser for SplTokenParser {
             }
 
             let discriminant = ix.data[0];
-            match discriminant {
+            let transfer = match discriminant {
+                IX_INITIALIZE_MINT | IX_INITIALIZE_MINT2 if tx.success => {
+                    events.extend(decode_initialize_mint(tx, ix).map(ParsedEvent::Mint));
+                    continue;
+                }
+                IX_MINT_TO | IX_MINT_TO_CHECKED if tx.success => {
+                    events.extend(decode_supply_change(tx, ix, SupplyChange::Mint).map(ParsedEvent::TokenSupply));
+                    continue;
+                }
+                IX_BURN | IX_BURN_CHECKED if tx.success => {
+                    events.extend(decode_supply_change(tx, ix, SupplyChange::Burn).map(ParsedEvent::TokenSupply));
+                    continue;
+                }
                 IX_TRANSFER => {
+                    // transfer: [source, destination, authority]
                     if ix.data.len() < 9 || ix.accounts.len() < 3 {
                         continue;
                     }
                     let amount = u64::from_le_bytes(ix.data[1..9].try_into().unwrap_or_default());
-                    events.push(ParsedEvent::TokenTransfer(TokenTransferEvent {
+                    let source = ix.accounts[0].clone();
+                    // Neither the mint nor its decimals are part of Transfer;
+                    // resolve them from the source's balance.
+                    let source_balance = tx.token_balance(&source);
+                    TokenTransferEvent {
                         signature: tx.signature.clone(),
                         block_time: tx.block_time,
-                        mint: ix.accounts.get(2).cloned().unwrap_or_default(),
-                        source: ix.accounts.first().cloned().unwrap_or_default(),
-                        destination: ix.accounts.get(1).cloned().unwrap_or_default(),
+                        instruction_index: ix.instruction_index,
+                        inner_index: ix.inner_index,
+                        mint: source_balance.map(|b| b.mint.clone()).unwrap_or_default(),
+                        source,
+                        destination: ix.accounts[1].clone(),
                         amount,
-                        decimals: 0, // decimals not in Transfer, only in TransferChecked
-                    }));
+                        decimals: source_balance.map_or(0, |b| b.decimals),
+                    }
                 }
                 IX_TRANSFER_CHECKED => {
                     // transferChecked: [source, mint, destination, authority, ...]
@@ -63,18 +178,44 @@ impl Parser for SplTokenParser {
                     }
                     let amount = u64::from_le_bytes(ix.data[1..9].try_into().unwrap_or_default());
                     let decimals = ix.data[9];
-                    events.push(ParsedEvent::TokenTransfer(TokenTransferEvent {
+                    TokenTransferEvent {
                         signature: tx.signature.clone(),
                         block_time: tx.block_time,
+                        instruction_index: ix.instruction_index,
+                        inner_index: ix.inner_index,
                         mint: ix.accounts.get(1).cloned().unwrap_or_default(),
                         source: ix.accounts.first().cloned().unwrap_or_default(),
                         destination: ix.accounts.get(2).cloned().unwrap_or_default(),
                         amount,
                         decimals,
-                    }));
+                    }
                 }
-                _ => {}
+                _ => continue,
+            };
+
+            // A failed transaction moved nothing, so ownership stays put.
+            if tx.success
+                && is_nft_movement(tx, &transfer.mint, transfer.amount, transfer.decimals)
+                && !has_metadata_transfer(tx, &transfer.mint)
+            {
+                let owner = |account: &str| {
+                    tx.token_balance(account)
+                        .map(|b| b.owner.clone())
+                        .unwrap_or_else(|| account.to_string())
+                };
+                let (marketplace, marketplace_ix) = nft::marketplace_for(tx, ix.instruction_index);
+                events.push(ParsedEvent::NftTransfer(NftTransferEvent {
+                    signature: tx.signature.clone(),
+                    slot: tx.slot,
+                    block_time: tx.block_time,
+                    mint: transfer.mint.clone(),
+                    from: Some(owner(&transfer.source)),
+                    to: owner(&transfer.destination),
+                    marketplace,
+                    marketplace_ix,
+                }));
             }
+            events.push(ParsedEvent::TokenTransfer(transfer));
         }
 
         events
@@ -84,31 +225,72 @@ impl Parser for SplTokenParser {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::receiver::RawInstruction;
-
-    fn make_tx(instructions: Vec<RawInstruction>) -> TransactionUpdate {
-        TransactionUpdate {
-            signature: "testsig".into(),
-            slot: 1,
-            block_time: 1_700_000_000,
-            success: true,
-            fee: 5000,
-            compute_units: 200_000,
-            accounts: vec![],
-            log_messages: vec![],
-            instructions,
+    use crate::parser::{ParserEngine, SPL_TOKEN_2022_PROGRAM_ID, SPL_TOKEN_PROGRAM_ID};
+    use crate::receiver::{RawInstruction, TokenBalance};
+
+    #[test]
+    fn test_token_2022_routed_through_engine() {
+        let mut init = vec![IX_INITIALIZE_MINT2, 9];
+        init.extend_from_slice(&[1u8; 32]);
+        init.push(0); // no freeze authority
+        let mut burn = vec![IX_BURN_CHECKED];
+        burn.extend_from_slice(&250u64.to_le_bytes());
+        burn.push(9);
+        let mut transfer = vec![IX_TRANSFER];
+        transfer.extend_from_slice(&1_000u64.to_le_bytes());
+
+        let ix = |instruction_index, data, accounts: &[&str]| RawInstruction {
+            program_id: SPL_TOKEN_2022_PROGRAM_ID.into(),
+            instruction_index,
+            inner_index: None,
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        };
+        let mut tx = TransactionUpdate::test(vec![
+            ix(0, init, &["mint"]),
+            ix(1, burn, &["ata", "mint", "owner"]),
+            ix(2, transfer, &["ata", "dst", "owner"]),
+        ]);
+        tx.post_token_balances = vec![TokenBalance {
+            account: "ata".into(),
+            mint: "mint".into(),
+            owner: "owner".into(),
+            amount: 500,
+            decimals: 9,
+        }];
+
+        let mut engine = ParserEngine::new();
+        for program in [SPL_TOKEN_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID] {
+            engine.register(Box::new(SplTokenParser::new(program)));
         }
+        let events: Vec<ParsedEvent> = engine
+            .parse(&tx)
+            .into_iter()
+            .filter(|e| !matches!(e, ParsedEvent::Instruction(_)))
+            .collect();
+        let [ParsedEvent::Mint(m), ParsedEvent::TokenSupply(b), ParsedEvent::TokenTransfer(t)] = events.as_slice()
+        else {
+            panic!("expected a mint, a burn and a transfer, got {events:?}");
+        };
+        assert_eq!((m.address.as_str(), m.decimals, m.supply), ("mint", 9, 0));
+        assert_eq!(m.program, SPL_TOKEN_2022_PROGRAM_ID);
+        assert_eq!(m.mint_authority, Some(bs58::encode([1u8; 32]).into_string()));
+        assert_eq!(m.freeze_authority, None);
+        assert_eq!((b.mint.as_str(), b.account.as_str()), ("mint", "ata"));
+        assert_eq!(b.delta(), -250);
+        // A plain Transfer takes its decimals from the source's token balance
+        assert_eq!((t.mint.as_str(), t.decimals), ("mint", 9));
     }
 
     #[test]
     fn test_transfer_checked_parsed() {
-        let parser = SplTokenParser::new();
+        let parser = SplTokenParser::new(SPL_TOKEN_PROGRAM_ID);
         let amount: u64 = 1_000_000;
         let mut data = vec![IX_TRANSFER_CHECKED];
         data.extend_from_slice(&amount.to_le_bytes());
         data.push(6); // decimals
 
-        let tx = make_tx(vec![RawInstruction {
+        let tx = TransactionUpdate::test(vec![RawInstruction {
             program_id: SPL_TOKEN_PROGRAM_ID.into(),
             instruction_index: 0,
             inner_index: None,
@@ -126,4 +308,48 @@ mod tests {
             panic!("Expected TokenTransfer");
         }
     }
+
+    #[test]
+    fn test_nft_transfer_detected_from_balances() {
+        let parser = SplTokenParser::new(SPL_TOKEN_PROGRAM_ID);
+        let mut data = vec![IX_TRANSFER];
+        data.extend_from_slice(&1u64.to_le_bytes());
+
+        let mut tx = TransactionUpdate::test(vec![RawInstruction {
+            program_id: SPL_TOKEN_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: vec!["src".into(), "dst".into(), "auth".into()],
+        }]);
+        let balance = |account: &str, owner: &str, amount| TokenBalance {
+            account: account.into(),
+            mint: "nftmint".into(),
+            owner: owner.into(),
+            amount,
+            decimals: 0,
+        };
+        tx.pre_token_balances = vec![balance("src", "alice", 1)];
+        tx.post_token_balances = vec![balance("src", "alice", 0), balance("dst", "bob", 1)];
+
+        let events = parser.parse(&tx);
+        assert_eq!(events.len(), 2);
+        if let ParsedEvent::NftTransfer(e) = &events[0] {
+            assert_eq!(e.mint, "nftmint");
+            assert_eq!(e.from.as_deref(), Some("alice"));
+            assert_eq!(e.to, "bob");
+            assert!(e.marketplace.is_none());
+        } else {
+            panic!("Expected NftTransfer");
+        }
+        if let ParsedEvent::TokenTransfer(e) = &events[1] {
+            assert_eq!(e.mint, "nftmint");
+        } else {
+            panic!("Expected TokenTransfer");
+        }
+
+        tx.success = false;
+        let events = parser.parse(&tx);
+        assert!(!events.iter().any(|e| matches!(e, ParsedEvent::NftTransfer(_))));
+    }
 }
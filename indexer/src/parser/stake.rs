@@ -0,0 +1,290 @@
+//! Stake program parser.
+//!
+//! Instructions are recorded as `Stake` events stamped with the epoch they
+//! happened in; stake account updates are decoded into the account's current
+//! authorities and delegation.
+
+use crate::parser::{
+    AccountParser, ParsedEvent, Parser, StakeAccountEvent, StakeAction, StakeEvent, STAKE_PROGRAM_ID,
+};
+use crate::receiver::{AccountUpdate, RawInstruction, TransactionUpdate};
+
+pub struct StakeParser;
+
+impl StakeParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Decodes stake accounts.
+pub struct StakeAccountParser;
+
+impl StakeAccountParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Slots per epoch on mainnet-beta, which has no warmup epochs left.
+pub const SLOTS_PER_EPOCH: u64 = 432_000;
+
+pub fn epoch_for_slot(slot: u64) -> u64 {
+    slot / SLOTS_PER_EPOCH
+}
+
+// Stake instruction indices (bincode u32 LE tag)
+const IX_INITIALIZE: u32 = 0;
+const IX_AUTHORIZE: u32 = 1;
+const IX_DELEGATE: u32 = 2;
+const IX_SPLIT: u32 = 3;
+const IX_WITHDRAW: u32 = 4;
+const IX_DEACTIVATE: u32 = 5;
+const IX_MERGE: u32 = 7;
+const IX_AUTHORIZE_WITH_SEED: u32 = 8;
+const IX_INITIALIZE_CHECKED: u32 = 9;
+const IX_AUTHORIZE_CHECKED: u32 = 10;
+const IX_AUTHORIZE_CHECKED_WITH_SEED: u32 = 11;
+const IX_DEACTIVATE_DELINQUENT: u32 = 14;
+
+// `StakeAuthorize` enum
+const AUTHORIZE_WITHDRAWER: u32 = 1;
+
+// `StakeStateV2` layout: tag(4) | Meta | Stake | StakeFlags
+const STATE_INITIALIZED: u32 = 1;
+const STATE_STAKE: u32 = 2;
+const META_RESERVE_OFFSET: usize = 4;
+const META_STAKER_OFFSET: usize = 12;
+const META_WITHDRAWER_OFFSET: usize = 44;
+const DELEGATION_VOTER_OFFSET: usize = 124;
+const DELEGATION_STAKE_OFFSET: usize = 156;
+const DELEGATION_ACTIVATION_OFFSET: usize = 164;
+const DELEGATION_DEACTIVATION_OFFSET: usize = 172;
+const STAKE_STATE_LEN: usize = 200;
+
+fn u32_at(data: &[u8], off: usize) -> Option<u32> {
+    Some(u32::from_le_bytes(data.get(off..off + 4)?.try_into().ok()?))
+}
+
+fn u64_at(data: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(off..off + 8)?.try_into().ok()?))
+}
+
+fn key_at(data: &[u8], off: usize) -> Option<String> {
+    Some(bs58::encode(data.get(off..off + 32)?).into_string())
+}
+
+/// `Authorize`-family instructions: `(new authority, role)`. The checked
+/// variants take the new authority as an account instead of an argument.
+fn decode_authorize(ix: &RawInstruction, tag: u32) -> Option<(String, u32)> {
+    let d = &ix.data;
+    match tag {
+        // Authorize(Pubkey, StakeAuthorize): accounts [stake, clock, authority, ..]
+        IX_AUTHORIZE => Some((key_at(d, 4)?, u32_at(d, 36)?)),
+        // AuthorizeWithSeed { new_authorized_pubkey, stake_authorize, .. }
+        IX_AUTHORIZE_WITH_SEED => Some((key_at(d, 4)?, u32_at(d, 36)?)),
+        // AuthorizeChecked(StakeAuthorize): accounts [stake, clock, authority, new_authority, ..]
+        IX_AUTHORIZE_CHECKED => Some((ix.accounts.get(3)?.clone(), u32_at(d, 4)?)),
+        // AuthorizeCheckedWithSeed { stake_authorize, .. }: accounts [stake, base, clock, new_authority, ..]
+        IX_AUTHORIZE_CHECKED_WITH_SEED => Some((ix.accounts.get(3)?.clone(), u32_at(d, 4)?)),
+        _ => None,
+    }
+}
+
+fn decode(tx: &TransactionUpdate, ix: &RawInstruction) -> Option<StakeEvent> {
+    let d = &ix.data;
+    let tag = u32_at(d, 0)?;
+    let acct = |i: usize| ix.accounts.get(i).cloned();
+    let mut event = StakeEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        epoch: epoch_for_slot(tx.slot),
+        instruction_index: ix.instruction_index,
+        inner_index: ix.inner_index,
+        action: StakeAction::Initialize,
+        stake_account: acct(0)?,
+        vote_account: None,
+        staker: None,
+        withdrawer: None,
+        related_account: None,
+        lamports: None,
+    };
+
+    match tag {
+        IX_INITIALIZE => {
+            // Initialize(Authorized { staker, withdrawer }, Lockup)
+            event.staker = key_at(d, 4);
+            event.withdrawer = key_at(d, 36);
+        }
+        IX_INITIALIZE_CHECKED => {
+            // accounts: [stake, rent, staker, withdrawer]
+            event.staker = acct(2);
+            event.withdrawer = acct(3);
+        }
+        IX_AUTHORIZE | IX_AUTHORIZE_WITH_SEED | IX_AUTHORIZE_CHECKED | IX_AUTHORIZE_CHECKED_WITH_SEED => {
+            let (authority, role) = decode_authorize(ix, tag)?;
+            if role == AUTHORIZE_WITHDRAWER {
+                event.action = StakeAction::AuthorizeWithdrawer;
+                event.withdrawer = Some(authority);
+            } else {
+                event.action = StakeAction::AuthorizeStaker;
+                event.staker = Some(authority);
+            }
+        }
+        IX_DELEGATE => {
+            // accounts: [stake, vote, clock, stake_history, config, stake_authority]
+            event.action = StakeAction::Delegate;
+            event.vote_account = acct(1);
+            event.staker = acct(5);
+        }
+        IX_DEACTIVATE => {
+            // accounts: [stake, clock, stake_authority]
+            event.action = StakeAction::Deactivate;
+            event.staker = acct(2);
+        }
+        IX_DEACTIVATE_DELINQUENT => {
+            // accounts: [stake, delinquent_vote, reference_vote]
+            event.action = StakeAction::Deactivate;
+            event.vote_account = acct(1);
+        }
+        IX_SPLIT => {
+            // Split(lamports): accounts [stake, split_stake, stake_authority]
+            event.action = StakeAction::Split;
+            event.related_account = acct(1);
+            event.lamports = u64_at(d, 4);
+        }
+        IX_WITHDRAW => {
+            // Withdraw(lamports): accounts [stake, recipient, clock, stake_history, withdraw_authority, ..]
+            event.action = StakeAction::Withdraw;
+            event.related_account = acct(1);
+            event.withdrawer = acct(4);
+            event.lamports = u64_at(d, 4);
+        }
+        IX_MERGE => {
+            // accounts: [destination, source, clock, stake_history, stake_authority]
+            event.action = StakeAction::Merge;
+            event.related_account = acct(1);
+            event.staker = acct(4);
+        }
+        _ => return None,
+    }
+    Some(event)
+}
+
+/// Decode `StakeStateV2` from a stake account. Uninitialized (or closed)
+/// accounts are reported with no authorities so the writer can drop them.
+fn decode_stake_account(account: &AccountUpdate) -> StakeAccountEvent {
+    let d = &account.data;
+    let tag = if d.len() == STAKE_STATE_LEN { u32_at(d, 0) } else { None };
+    let initialized = matches!(tag, Some(STATE_INITIALIZED | STATE_STAKE));
+    let delegated = tag == Some(STATE_STAKE);
+    let meta_key = |off| initialized.then(|| key_at(d, off)).flatten();
+    let delegation = |off| delegated.then(|| u64_at(d, off)).flatten();
+
+    StakeAccountEvent {
+        address: account.pubkey.clone(),
+        slot: account.slot,
+        lamports: account.lamports,
+        rent_exempt_reserve: initialized.then(|| u64_at(d, META_RESERVE_OFFSET)).flatten(),
+        staker: meta_key(META_STAKER_OFFSET),
+        withdrawer: meta_key(META_WITHDRAWER_OFFSET),
+        vote_account: delegated.then(|| key_at(d, DELEGATION_VOTER_OFFSET)).flatten(),
+        delegated_stake: delegation(DELEGATION_STAKE_OFFSET),
+        activation_epoch: delegation(DELEGATION_ACTIVATION_OFFSET),
+        // `u64::MAX` marks a delegation that has not been deactivated.
+        deactivation_epoch: delegation(DELEGATION_DEACTIVATION_OFFSET).filter(|e| *e != u64::MAX),
+    }
+}
+
+impl Parser for StakeParser {
+    fn program_id(&self) -> &str {
+        STAKE_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // A reverted instruction must not touch the current delegations.
+        if !tx.success {
+            return Vec::new();
+        }
+        tx.instructions
+            .iter()
+            .filter(|ix| ix.program_id == STAKE_PROGRAM_ID)
+            .filter_map(|ix| decode(tx, ix))
+            .map(ParsedEvent::Stake)
+            .collect()
+    }
+}
+
+impl AccountParser for StakeAccountParser {
+    fn owner(&self) -> &str {
+        STAKE_PROGRAM_ID
+    }
+
+    // Uninitialized and closed accounts are recorded too, with no authorities.
+    fn matches(&self, _data: &[u8]) -> bool {
+        true
+    }
+
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        vec![ParsedEvent::StakeAccount(decode_stake_account(account))]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_delegated_stake_account() {
+        let mut data = vec![0u8; STAKE_STATE_LEN];
+        data[..4].copy_from_slice(&STATE_STAKE.to_le_bytes());
+        data[META_RESERVE_OFFSET..META_RESERVE_OFFSET + 8].copy_from_slice(&2_282_880u64.to_le_bytes());
+        data[META_STAKER_OFFSET..META_STAKER_OFFSET + 32].copy_from_slice(&[1u8; 32]);
+        data[META_WITHDRAWER_OFFSET..META_WITHDRAWER_OFFSET + 32].copy_from_slice(&[2u8; 32]);
+        data[DELEGATION_VOTER_OFFSET..DELEGATION_VOTER_OFFSET + 32].copy_from_slice(&[3u8; 32]);
+        data[DELEGATION_STAKE_OFFSET..DELEGATION_STAKE_OFFSET + 8].copy_from_slice(&5_000_000_000u64.to_le_bytes());
+        data[DELEGATION_ACTIVATION_OFFSET..DELEGATION_ACTIVATION_OFFSET + 8].copy_from_slice(&600u64.to_le_bytes());
+        data[DELEGATION_DEACTIVATION_OFFSET..DELEGATION_DEACTIVATION_OFFSET + 8]
+            .copy_from_slice(&u64::MAX.to_le_bytes());
+
+        let state = decode_stake_account(&AccountUpdate {
+            pubkey: "stake".into(),
+            slot: 260_000_000,
+            owner: STAKE_PROGRAM_ID.into(),
+            lamports: 5_002_282_880,
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: 0,
+        });
+        assert_eq!(state.staker, Some(bs58::encode([1u8; 32]).into_string()));
+        assert_eq!(state.vote_account, Some(bs58::encode([3u8; 32]).into_string()));
+        assert_eq!(state.delegated_stake, Some(5_000_000_000));
+        assert_eq!(state.activation_epoch, Some(600));
+        assert_eq!(state.deactivation_epoch, None);
+    }
+
+    #[test]
+    fn test_delegate_parsed_unless_failed() {
+        let mut tx = TransactionUpdate::test(vec![RawInstruction {
+            program_id: STAKE_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data: IX_DELEGATE.to_le_bytes().to_vec(),
+            accounts: ["stake", "vote", "clock", "history", "config", "staker"].map(String::from).to_vec(),
+        }]);
+        tx.slot = 3 * SLOTS_PER_EPOCH + 5;
+
+        let events = StakeParser::new().parse(&tx);
+        let [ParsedEvent::Stake(e)] = events.as_slice() else {
+            panic!("expected one stake event, got {events:?}");
+        };
+        assert!(matches!(e.action, StakeAction::Delegate));
+        assert_eq!((e.stake_account.as_str(), e.vote_account.as_deref()), ("stake", Some("vote")));
+        assert_eq!(e.epoch, 3);
+
+        tx.success = false;
+        assert!(StakeParser::new().parse(&tx).is_empty());
+    }
+}
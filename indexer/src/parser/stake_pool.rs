@@ -0,0 +1,204 @@
+//! SPL Stake Pool parser (also used by Jito's JitoSOL pool).
+//!
+//! Deposits mint pool tokens (the LST) and withdrawals burn them. SOL amounts
+//! come from the instruction argument for deposits and from the System or
+//! Stake program CPIs that pay the user for withdrawals.
+
+use crate::parser::{
+    system, ParsedEvent, Parser, StakePoolAction, StakePoolEvent, SPL_STAKE_POOL_PROGRAM_ID,
+    STAKE_PROGRAM_ID, SYSTEM_PROGRAM_ID,
+};
+use crate::receiver::{RawInstruction, TransactionUpdate};
+
+pub struct StakePoolParser;
+
+impl StakePoolParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Stake pool instruction discriminants
+const IX_DEPOSIT_STAKE: u8 = 9;
+const IX_WITHDRAW_STAKE: u8 = 10;
+const IX_DEPOSIT_SOL: u8 = 14;
+const IX_WITHDRAW_SOL: u8 = 16;
+const IX_DEPOSIT_STAKE_WITH_SLIPPAGE: u8 = 23;
+const IX_WITHDRAW_STAKE_WITH_SLIPPAGE: u8 = 24;
+const IX_DEPOSIT_SOL_WITH_SLIPPAGE: u8 = 25;
+const IX_WITHDRAW_SOL_WITH_SLIPPAGE: u8 = 26;
+
+// Stake program instruction indices (bincode u32 LE tag)
+const STAKE_IX_SPLIT: u32 = 3;
+const STAKE_IX_WITHDRAW: u32 = 4;
+
+fn u64_at(data: &[u8], off: usize) -> Option<u64> {
+    Some(u64::from_le_bytes(data.get(off..off + 8)?.try_into().ok()?))
+}
+
+fn tag(ix: &RawInstruction) -> Option<u32> {
+    Some(u32::from_le_bytes(ix.data.get(..4)?.try_into().ok()?))
+}
+
+/// Lamports paid to `recipient` by the System transfers and Stake withdrawals
+/// invoked by the instruction at `pos`.
+pub(crate) fn lamports_paid_to(tx: &TransactionUpdate, pos: usize, recipient: &str) -> Option<u64> {
+    let paid: u64 = tx
+        .inner_instructions(pos)
+        .iter()
+        .filter(|i| i.accounts.get(1).map(String::as_str) == Some(recipient))
+        .filter(|i| {
+            (i.program_id == SYSTEM_PROGRAM_ID && tag(i) == Some(system::IX_TRANSFER))
+                || (i.program_id == STAKE_PROGRAM_ID && tag(i) == Some(STAKE_IX_WITHDRAW))
+        })
+        .filter_map(|i| u64_at(&i.data, 4))
+        .sum();
+    (paid > 0).then_some(paid)
+}
+
+/// Lamports split off into the stake account handed to the user.
+pub(crate) fn lamports_split(tx: &TransactionUpdate, pos: usize) -> Option<u64> {
+    tx.inner_instructions(pos)
+        .iter()
+        .find(|i| i.program_id == STAKE_PROGRAM_ID && tag(i) == Some(STAKE_IX_SPLIT))
+        .and_then(|i| u64_at(&i.data, 4))
+}
+
+impl Parser for StakePoolParser {
+    fn program_id(&self) -> &str {
+        SPL_STAKE_POOL_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // Failed transactions mint and burn no pool tokens.
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        for (pos, ix) in tx.instructions.iter().enumerate() {
+            if ix.program_id != SPL_STAKE_POOL_PROGRAM_ID || ix.data.is_empty() {
+                continue;
+            }
+            let acct = |i: usize| ix.accounts.get(i).cloned();
+            let owner = |account: &str| tx.token_balance(account).map(|b| b.owner.clone());
+            // (action, user, sol_amount, lst_mint, lst_amount)
+            let decoded = match ix.data[0] {
+                IX_DEPOSIT_SOL | IX_DEPOSIT_SOL_WITH_SLIPPAGE => {
+                    // accounts: [pool, withdraw_authority, reserve, lamports_from, pool_tokens_to,
+                    //            manager_fee, referrer_fee, pool_mint, ..]
+                    let Some(tokens_to) = acct(4) else { continue };
+                    (
+                        StakePoolAction::DepositSol,
+                        acct(3),
+                        u64_at(&ix.data, 1),
+                        acct(7),
+                        tx.token_balance_increase(&tokens_to),
+                    )
+                }
+                IX_DEPOSIT_STAKE | IX_DEPOSIT_STAKE_WITH_SLIPPAGE => {
+                    // accounts: [pool, validator_list, deposit_authority, withdraw_authority,
+                    //            deposit_stake, validator_stake, reserve, pool_tokens_to,
+                    //            manager_fee, referrer_fee, pool_mint, ..]
+                    let Some(tokens_to) = acct(7) else { continue };
+                    (
+                        StakePoolAction::DepositStake,
+                        owner(&tokens_to),
+                        None,
+                        acct(10),
+                        tx.token_balance_increase(&tokens_to),
+                    )
+                }
+                IX_WITHDRAW_SOL | IX_WITHDRAW_SOL_WITH_SLIPPAGE => {
+                    // accounts: [pool, withdraw_authority, user_transfer_authority, pool_tokens_from,
+                    //            reserve, lamports_to, manager_fee, pool_mint, ..]
+                    let Some(lamports_to) = acct(5) else { continue };
+                    (
+                        StakePoolAction::WithdrawSol,
+                        acct(2),
+                        lamports_paid_to(tx, pos, &lamports_to),
+                        acct(7),
+                        u64_at(&ix.data, 1),
+                    )
+                }
+                IX_WITHDRAW_STAKE | IX_WITHDRAW_STAKE_WITH_SLIPPAGE => {
+                    // accounts: [pool, validator_list, withdraw_authority, stake_to_split,
+                    //            stake_to_receive, user_stake_authority, user_transfer_authority,
+                    //            pool_tokens_from, manager_fee, pool_mint, ..]
+                    (
+                        StakePoolAction::WithdrawStake,
+                        acct(6),
+                        lamports_split(tx, pos),
+                        acct(9),
+                        u64_at(&ix.data, 1),
+                    )
+                }
+                _ => continue,
+            };
+            let (action, user, sol_amount, lst_mint, lst_amount) = decoded;
+            let (Some(pool), Some(user)) = (acct(0), user) else {
+                continue;
+            };
+
+            events.push(ParsedEvent::StakePool(StakePoolEvent {
+                signature: tx.signature.clone(),
+                slot: tx.slot,
+                block_time: tx.block_time,
+                instruction_index: ix.instruction_index,
+                inner_index: ix.inner_index,
+                program: SPL_STAKE_POOL_PROGRAM_ID.into(),
+                pool,
+                action,
+                user,
+                sol_amount,
+                lst_mint,
+                lst_amount,
+            }));
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::TokenBalance;
+
+    #[test]
+    fn test_deposit_sol_parsed() {
+        let mut data = vec![IX_DEPOSIT_SOL];
+        data.extend_from_slice(&2_000_000_000u64.to_le_bytes());
+        let accounts = ["pool", "wauth", "reserve", "alice", "aliceata", "fee", "ref", "jitosol"];
+        let mut tx = TransactionUpdate::test(vec![RawInstruction {
+            program_id: SPL_STAKE_POOL_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        }]);
+        tx.post_token_balances = vec![TokenBalance {
+            account: "aliceata".into(),
+            mint: "jitosol".into(),
+            owner: "alice".into(),
+            amount: 1_750_000_000,
+            decimals: 9,
+        }];
+
+        let events = StakePoolParser::new().parse(&tx);
+        assert_eq!(events.len(), 1);
+        if let ParsedEvent::StakePool(e) = &events[0] {
+            assert_eq!(e.pool, "pool");
+            assert_eq!(e.user, "alice");
+            assert_eq!(e.action, StakePoolAction::DepositSol);
+            assert_eq!(e.sol_amount, Some(2_000_000_000));
+            assert_eq!(e.lst_mint.as_deref(), Some("jitosol"));
+            assert_eq!(e.lst_amount, Some(1_750_000_000));
+        } else {
+            panic!("Expected StakePool");
+        }
+
+        tx.success = false;
+        assert!(StakePoolParser::new().parse(&tx).is_empty());
+    }
+}
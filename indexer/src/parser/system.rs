@@ -0,0 +1,303 @@
+//! System program parser.
+//!
+//! Every instruction that moves lamports (transfers, account funding, nonce
+//! withdrawals) is reported as a `SolTransfer`; account lifecycle and durable
+//! nonce instructions are reported as `SystemAccount` events.
+
+use crate::parser::{
+    ParsedEvent, Parser, SolTransferEvent, SystemAccountEvent, SystemAction, SYSTEM_PROGRAM_ID,
+};
+use crate::receiver::{RawInstruction, TransactionUpdate};
+
+pub struct SystemParser;
+
+impl SystemParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// System instruction indices (bincode u32 LE tag)
+const IX_CREATE_ACCOUNT: u32 = 0;
+const IX_ASSIGN: u32 = 1;
+pub(crate) const IX_TRANSFER: u32 = 2;
+const IX_CREATE_ACCOUNT_WITH_SEED: u32 = 3;
+const IX_ADVANCE_NONCE: u32 = 4;
+const IX_WITHDRAW_NONCE: u32 = 5;
+const IX_INITIALIZE_NONCE: u32 = 6;
+const IX_AUTHORIZE_NONCE: u32 = 7;
+const IX_ALLOCATE: u32 = 8;
+const IX_ALLOCATE_WITH_SEED: u32 = 9;
+const IX_ASSIGN_WITH_SEED: u32 = 10;
+const IX_TRANSFER_WITH_SEED: u32 = 11;
+const IX_UPGRADE_NONCE: u32 = 12;
+
+/// Cursor over bincode-encoded instruction arguments.
+struct Args<'a>(&'a [u8]);
+
+impl Args<'_> {
+    fn take(&mut self, n: usize) -> Option<&[u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn pubkey(&mut self) -> Option<String> {
+        Some(bs58::encode(self.take(32)?).into_string())
+    }
+
+    /// bincode strings are prefixed with a u64 length.
+    fn seed(&mut self) -> Option<String> {
+        let len = self.u64()? as usize;
+        Some(String::from_utf8_lossy(self.take(len)?).into_owned())
+    }
+}
+
+/// Decoded instruction: the lamport movement, if any, and the account event, if any.
+type Decoded = (Option<(String, String, u64)>, Option<SystemAccountEvent>);
+
+fn decode(tx: &TransactionUpdate, ix: &RawInstruction) -> Option<Decoded> {
+    let tag = u32::from_le_bytes(ix.data.get(..4)?.try_into().ok()?);
+    let args = &mut Args(&ix.data[4..]);
+    let acct = |i: usize| ix.accounts.get(i).cloned();
+    let event = |action, account: String| SystemAccountEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        instruction_index: ix.instruction_index,
+        inner_index: ix.inner_index,
+        action,
+        account,
+        owner: None,
+        space: None,
+        lamports: None,
+        base: None,
+        seed: None,
+        authority: None,
+    };
+
+    let decoded = match tag {
+        IX_TRANSFER => {
+            // accounts: [from, to]
+            (Some((acct(0)?, acct(1)?, args.u64()?)), None)
+        }
+        IX_TRANSFER_WITH_SEED => {
+            // accounts: [from, base, to]
+            (Some((acct(0)?, acct(2)?, args.u64()?)), None)
+        }
+        IX_CREATE_ACCOUNT => {
+            // accounts: [funder, new_account]
+            let (lamports, space, owner) = (args.u64()?, args.u64()?, args.pubkey()?);
+            let (funder, account) = (acct(0)?, acct(1)?);
+            let created = SystemAccountEvent {
+                owner: Some(owner),
+                space: Some(space),
+                lamports: Some(lamports),
+                ..event(SystemAction::CreateAccount, account.clone())
+            };
+            (Some((funder, account, lamports)), Some(created))
+        }
+        IX_CREATE_ACCOUNT_WITH_SEED => {
+            // accounts: [funder, created_account, (base)]
+            let (base, seed) = (args.pubkey()?, args.seed()?);
+            let (lamports, space, owner) = (args.u64()?, args.u64()?, args.pubkey()?);
+            let (funder, account) = (acct(0)?, acct(1)?);
+            let created = SystemAccountEvent {
+                owner: Some(owner),
+                space: Some(space),
+                lamports: Some(lamports),
+                base: Some(base),
+                seed: Some(seed),
+                ..event(SystemAction::CreateAccount, account.clone())
+            };
+            (Some((funder, account, lamports)), Some(created))
+        }
+        IX_ASSIGN => {
+            // accounts: [account]
+            let assigned = SystemAccountEvent {
+                owner: Some(args.pubkey()?),
+                ..event(SystemAction::Assign, acct(0)?)
+            };
+            (None, Some(assigned))
+        }
+        IX_ASSIGN_WITH_SEED => {
+            // accounts: [account, base]
+            let (base, seed, owner) = (args.pubkey()?, args.seed()?, args.pubkey()?);
+            let assigned = SystemAccountEvent {
+                owner: Some(owner),
+                base: Some(base),
+                seed: Some(seed),
+                ..event(SystemAction::Assign, acct(0)?)
+            };
+            (None, Some(assigned))
+        }
+        IX_ALLOCATE => {
+            // accounts: [account]
+            let allocated = SystemAccountEvent {
+                space: Some(args.u64()?),
+                ..event(SystemAction::Allocate, acct(0)?)
+            };
+            (None, Some(allocated))
+        }
+        IX_ALLOCATE_WITH_SEED => {
+            // accounts: [account, base]
+            let (base, seed) = (args.pubkey()?, args.seed()?);
+            let (space, owner) = (args.u64()?, args.pubkey()?);
+            let allocated = SystemAccountEvent {
+                owner: Some(owner),
+                space: Some(space),
+                base: Some(base),
+                seed: Some(seed),
+                ..event(SystemAction::Allocate, acct(0)?)
+            };
+            (None, Some(allocated))
+        }
+        IX_INITIALIZE_NONCE => {
+            // accounts: [nonce, recent_blockhashes, rent]
+            let initialized = SystemAccountEvent {
+                authority: Some(args.pubkey()?),
+                ..event(SystemAction::InitializeNonce, acct(0)?)
+            };
+            (None, Some(initialized))
+        }
+        IX_ADVANCE_NONCE => {
+            // accounts: [nonce, recent_blockhashes, authority]
+            let advanced = SystemAccountEvent {
+                authority: acct(2),
+                ..event(SystemAction::AdvanceNonce, acct(0)?)
+            };
+            (None, Some(advanced))
+        }
+        IX_WITHDRAW_NONCE => {
+            // accounts: [nonce, recipient, recent_blockhashes, rent, authority]
+            let lamports = args.u64()?;
+            let (nonce, recipient) = (acct(0)?, acct(1)?);
+            let withdrawn = SystemAccountEvent {
+                lamports: Some(lamports),
+                authority: acct(4),
+                ..event(SystemAction::WithdrawNonce, nonce.clone())
+            };
+            (Some((nonce, recipient, lamports)), Some(withdrawn))
+        }
+        IX_AUTHORIZE_NONCE => {
+            // accounts: [nonce, authority]; data carries the new authority
+            let authorized = SystemAccountEvent {
+                authority: Some(args.pubkey()?),
+                ..event(SystemAction::AuthorizeNonce, acct(0)?)
+            };
+            (None, Some(authorized))
+        }
+        IX_UPGRADE_NONCE => {
+            // accounts: [nonce]
+            (None, Some(event(SystemAction::UpgradeNonce, acct(0)?)))
+        }
+        _ => return None,
+    };
+    Some(decoded)
+}
+
+impl Parser for SystemParser {
+    fn program_id(&self) -> &str {
+        SYSTEM_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // Failed transactions move no lamports and create no accounts.
+        if !tx.success {
+            return Vec::new();
+        }
+        let mut events = Vec::new();
+
+        for ix in &tx.instructions {
+            if ix.program_id != SYSTEM_PROGRAM_ID {
+                continue;
+            }
+            let Some((transfer, account)) = decode(tx, ix) else {
+                continue;
+            };
+            if let Some((source, destination, lamports)) = transfer {
+                events.push(ParsedEvent::SolTransfer(SolTransferEvent {
+                    signature: tx.signature.clone(),
+                    slot: tx.slot,
+                    block_time: tx.block_time,
+                    instruction_index: ix.instruction_index,
+                    inner_index: ix.inner_index,
+                    source,
+                    destination,
+                    lamports,
+                }));
+            }
+            if let Some(account) = account {
+                events.push(ParsedEvent::SystemAccount(account));
+            }
+        }
+
+        events
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn make_tx(data: Vec<u8>, accounts: &[&str]) -> TransactionUpdate {
+        TransactionUpdate::test(vec![RawInstruction {
+            program_id: SYSTEM_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: accounts.iter().map(|a| a.to_string()).collect(),
+        }])
+    }
+
+    #[test]
+    fn test_transfer_parsed() {
+        let mut data = IX_TRANSFER.to_le_bytes().to_vec();
+        data.extend_from_slice(&1_500_000_000u64.to_le_bytes());
+
+        let mut tx = make_tx(data, &["alice", "bob"]);
+        let events = SystemParser::new().parse(&tx);
+        assert_eq!(events.len(), 1);
+        if let ParsedEvent::SolTransfer(t) = &events[0] {
+            assert_eq!(t.source, "alice");
+            assert_eq!(t.destination, "bob");
+            assert_eq!(t.lamports, 1_500_000_000);
+        } else {
+            panic!("Expected SolTransfer");
+        }
+
+        tx.success = false;
+        assert!(SystemParser::new().parse(&tx).is_empty());
+    }
+
+    #[test]
+    fn test_create_account_with_seed_parsed() {
+        let owner = [7u8; 32];
+        let mut data = IX_CREATE_ACCOUNT_WITH_SEED.to_le_bytes().to_vec();
+        data.extend_from_slice(&[1u8; 32]); // base
+        data.extend_from_slice(&4u64.to_le_bytes());
+        data.extend_from_slice(b"seed");
+        data.extend_from_slice(&2_039_280u64.to_le_bytes());
+        data.extend_from_slice(&165u64.to_le_bytes());
+        data.extend_from_slice(&owner);
+
+        let events = SystemParser::new().parse(&make_tx(data, &["funder", "created", "base"]));
+        assert_eq!(events.len(), 2);
+        assert!(matches!(&events[0], ParsedEvent::SolTransfer(t) if t.lamports == 2_039_280));
+        if let ParsedEvent::SystemAccount(a) = &events[1] {
+            assert_eq!(a.account, "created");
+            assert_eq!(a.seed.as_deref(), Some("seed"));
+            assert_eq!(a.space, Some(165));
+            assert_eq!(a.owner, Some(bs58::encode(owner).into_string()));
+        } else {
+            panic!("Expected SystemAccount");
+        }
+    }
+}
@@ -0,0 +1,364 @@
+//! SPL Token and Token-2022 account decoders.
+//!
+//! Token accounts and mints share an owner program, so they are told apart by
+//! length: 165 and 82 bytes for the base layouts, and the account-type byte
+//! that follows the (padded) base layout for Token-2022 accounts carrying
+//! extensions. Closed accounts (no lamports left) decode to nothing; the
+//! writer removes their balances.
+
+use serde_json::{json, Map, Value};
+
+use crate::parser::nft::{de, trim};
+use crate::parser::{
+    AccountParser, MintEvent, ParsedEvent, TokenAccountEvent, TokenAccountState,
+};
+use crate::receiver::AccountUpdate;
+
+pub const TOKEN_ACCOUNT_LEN: usize = 165;
+pub const MINT_LEN: usize = 82;
+const MULTISIG_LEN: usize = 355;
+
+// Token-2022 `AccountType`, stored at offset 165 of extended accounts
+const ACCOUNT_TYPE_MINT: u8 = 1;
+const ACCOUNT_TYPE_ACCOUNT: u8 = 2;
+/// Extensions follow the account type as (type u16, length u16, value) entries.
+const EXTENSIONS_OFFSET: usize = TOKEN_ACCOUNT_LEN + 1;
+
+/// Token-2022 `ExtensionType` names, indexed by type.
+const EXTENSION_NAMES: [&str; 28] = [
+    "Uninitialized",
+    "TransferFeeConfig",
+    "TransferFeeAmount",
+    "MintCloseAuthority",
+    "ConfidentialTransferMint",
+    "ConfidentialTransferAccount",
+    "DefaultAccountState",
+    "ImmutableOwner",
+    "MemoTransfer",
+    "NonTransferable",
+    "InterestBearingConfig",
+    "CpiGuard",
+    "PermanentDelegate",
+    "NonTransferableAccount",
+    "TransferHook",
+    "TransferHookAccount",
+    "ConfidentialTransferFeeConfig",
+    "ConfidentialTransferFeeAmount",
+    "MetadataPointer",
+    "TokenMetadata",
+    "GroupPointer",
+    "TokenGroup",
+    "GroupMemberPointer",
+    "TokenGroupMember",
+    "ConfidentialMintBurn",
+    "ScaledUiAmount",
+    "Pausable",
+    "PausableAccount",
+];
+const EXT_TRANSFER_FEE_CONFIG: u16 = 1;
+const EXT_MINT_CLOSE_AUTHORITY: u16 = 3;
+const EXT_DEFAULT_ACCOUNT_STATE: u16 = 6;
+const EXT_INTEREST_BEARING_CONFIG: u16 = 10;
+const EXT_PERMANENT_DELEGATE: u16 = 12;
+const EXT_TRANSFER_HOOK: u16 = 14;
+const EXT_METADATA_POINTER: u16 = 18;
+const EXT_TOKEN_METADATA: u16 = 19;
+const EXT_GROUP_POINTER: u16 = 20;
+const EXT_GROUP_MEMBER_POINTER: u16 = 22;
+
+// Token account layout
+const ACCOUNT_MINT_OFFSET: usize = 0;
+const ACCOUNT_OWNER_OFFSET: usize = 32;
+const ACCOUNT_AMOUNT_OFFSET: usize = 64;
+const ACCOUNT_DELEGATE_OFFSET: usize = 72;
+const ACCOUNT_STATE_OFFSET: usize = 108;
+const ACCOUNT_IS_NATIVE_OFFSET: usize = 109;
+const ACCOUNT_DELEGATED_AMOUNT_OFFSET: usize = 121;
+const ACCOUNT_CLOSE_AUTHORITY_OFFSET: usize = 129;
+
+// Mint layout
+const MINT_AUTHORITY_OFFSET: usize = 0;
+const MINT_SUPPLY_OFFSET: usize = 36;
+const MINT_DECIMALS_OFFSET: usize = 44;
+const MINT_INITIALIZED_OFFSET: usize = 45;
+const MINT_FREEZE_AUTHORITY_OFFSET: usize = 46;
+
+/// Token-2022 account type of an extended account, if `data` is one.
+fn extended_type(data: &[u8]) -> Option<u8> {
+    (data.len() > TOKEN_ACCOUNT_LEN && data.len() != MULTISIG_LEN).then(|| data[TOKEN_ACCOUNT_LEN])
+}
+
+fn u64_at(d: &[u8], off: usize) -> u64 {
+    u64::from_le_bytes(d[off..off + 8].try_into().unwrap_or_default())
+}
+
+fn key_at(d: &[u8], off: usize) -> String {
+    bs58::encode(&d[off..off + 32]).into_string()
+}
+
+/// `COption<Pubkey>`: a u32 tag followed by the key.
+fn optional_key(d: &[u8], off: usize) -> Option<String> {
+    (d[off..off + 4] != [0; 4]).then(|| key_at(d, off + 4))
+}
+
+/// Extension `OptionalNonZeroPubkey`: all zeroes means none.
+fn nonzero_key(v: &[u8], off: usize) -> Value {
+    match v.get(off..off + 32) {
+        Some(k) if k != [0; 32] => json!(bs58::encode(k).into_string()),
+        _ => Value::Null,
+    }
+}
+
+fn ext_u64(v: &[u8], off: usize) -> Value {
+    v.get(off..off + 8)
+        .and_then(|b| b.try_into().ok())
+        .map_or(Value::Null, |b| json!(u64::from_le_bytes(b)))
+}
+
+/// `TokenMetadata { update_authority, mint, name, symbol, uri, .. }`
+fn token_metadata(v: &[u8]) -> Option<(String, String, String)> {
+    let buf = &mut v.get(64..)?;
+    Some((trim(de(buf).ok()?), trim(de(buf).ok()?), trim(de(buf).ok()?)))
+}
+
+/// Extensions of an extended Token-2022 mint as `{name: details}`, with the
+/// name and symbol of its `TokenMetadata` extension. Only the commonly
+/// queried fields of each extension are decoded; the rest are listed with
+/// empty details.
+fn decode_extensions(d: &[u8]) -> (Map<String, Value>, Option<(String, String)>) {
+    let mut extensions = Map::new();
+    let mut metadata = None;
+    let mut off = EXTENSIONS_OFFSET;
+    while let Some(header) = d.get(off..off + 4) {
+        let ty = u16::from_le_bytes([header[0], header[1]]);
+        let len = u16::from_le_bytes([header[2], header[3]]) as usize;
+        let Some(v) = d.get(off + 4..off + 4 + len) else { break };
+        if ty == 0 {
+            break; // unused space after the last extension
+        }
+        off += 4 + len;
+
+        let details = match ty {
+            // The newer of the two fee schedules, which applies from `epoch` on
+            EXT_TRANSFER_FEE_CONFIG => json!({
+                "authority": nonzero_key(v, 0),
+                "withdraw_withheld_authority": nonzero_key(v, 32),
+                "epoch": ext_u64(v, 90),
+                "maximum_fee": ext_u64(v, 98),
+                "basis_points": v.get(106..108).map(|b| u16::from_le_bytes([b[0], b[1]])),
+            }),
+            EXT_MINT_CLOSE_AUTHORITY => json!({ "authority": nonzero_key(v, 0) }),
+            EXT_DEFAULT_ACCOUNT_STATE => json!({
+                "state": match v.first() {
+                    Some(1) => "initialized",
+                    Some(2) => "frozen",
+                    _ => "uninitialized",
+                },
+            }),
+            EXT_INTEREST_BEARING_CONFIG => json!({
+                "rate_authority": nonzero_key(v, 0),
+                "current_rate": v.get(50..52).map(|b| i16::from_le_bytes([b[0], b[1]])),
+            }),
+            EXT_PERMANENT_DELEGATE => json!({ "delegate": nonzero_key(v, 0) }),
+            EXT_TRANSFER_HOOK => json!({ "authority": nonzero_key(v, 0), "program_id": nonzero_key(v, 32) }),
+            EXT_METADATA_POINTER => json!({ "authority": nonzero_key(v, 0), "metadata_address": nonzero_key(v, 32) }),
+            EXT_GROUP_POINTER => json!({ "authority": nonzero_key(v, 0), "group_address": nonzero_key(v, 32) }),
+            EXT_GROUP_MEMBER_POINTER => json!({ "authority": nonzero_key(v, 0), "member_address": nonzero_key(v, 32) }),
+            EXT_TOKEN_METADATA => {
+                let fields = token_metadata(v);
+                let uri = fields.as_ref().map(|(_, _, uri)| uri.clone());
+                metadata = fields.map(|(name, symbol, _)| (name, symbol));
+                json!({ "update_authority": nonzero_key(v, 0), "uri": uri })
+            }
+            _ => json!({}),
+        };
+        let name = EXTENSION_NAMES
+            .get(ty as usize)
+            .map_or_else(|| format!("Unknown{ty}"), |n| n.to_string());
+        extensions.insert(name, details);
+    }
+    (extensions, metadata)
+}
+
+/// Decodes token accounts of one token program.
+pub struct TokenAccountParser {
+    program: &'static str,
+}
+
+impl TokenAccountParser {
+    pub fn new(program: &'static str) -> Self {
+        Self { program }
+    }
+}
+
+impl AccountParser for TokenAccountParser {
+    fn owner(&self) -> &str {
+        self.program
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        data.len() == TOKEN_ACCOUNT_LEN || extended_type(data) == Some(ACCOUNT_TYPE_ACCOUNT)
+    }
+
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        if account.lamports == 0 {
+            return Vec::new();
+        }
+        let d = &account.data;
+        let state = match d[ACCOUNT_STATE_OFFSET] {
+            1 => TokenAccountState::Initialized,
+            2 => TokenAccountState::Frozen,
+            _ => TokenAccountState::Uninitialized,
+        };
+        let is_native = (d[ACCOUNT_IS_NATIVE_OFFSET..ACCOUNT_IS_NATIVE_OFFSET + 4] != [0; 4])
+            .then(|| u64_at(d, ACCOUNT_IS_NATIVE_OFFSET + 4));
+
+        vec![ParsedEvent::TokenAccount(TokenAccountEvent {
+            address: account.pubkey.clone(),
+            slot: account.slot,
+            program: self.program.into(),
+            mint: key_at(d, ACCOUNT_MINT_OFFSET),
+            owner: key_at(d, ACCOUNT_OWNER_OFFSET),
+            amount: u64_at(d, ACCOUNT_AMOUNT_OFFSET),
+            delegate: optional_key(d, ACCOUNT_DELEGATE_OFFSET),
+            delegated_amount: u64_at(d, ACCOUNT_DELEGATED_AMOUNT_OFFSET),
+            state,
+            is_native,
+            close_authority: optional_key(d, ACCOUNT_CLOSE_AUTHORITY_OFFSET),
+        })]
+    }
+}
+
+/// Decodes mints of one token program.
+pub struct MintParser {
+    program: &'static str,
+}
+
+impl MintParser {
+    pub fn new(program: &'static str) -> Self {
+        Self { program }
+    }
+}
+
+impl AccountParser for MintParser {
+    fn owner(&self) -> &str {
+        self.program
+    }
+
+    fn matches(&self, data: &[u8]) -> bool {
+        data.len() == MINT_LEN || extended_type(data) == Some(ACCOUNT_TYPE_MINT)
+    }
+
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        let d = &account.data;
+        let (extensions, metadata) = decode_extensions(d);
+        let (name, symbol) = metadata.unzip();
+        vec![ParsedEvent::Mint(MintEvent {
+            address: account.pubkey.clone(),
+            slot: account.slot,
+            program: self.program.into(),
+            decimals: d[MINT_DECIMALS_OFFSET],
+            supply: u64_at(d, MINT_SUPPLY_OFFSET),
+            mint_authority: optional_key(d, MINT_AUTHORITY_OFFSET),
+            freeze_authority: optional_key(d, MINT_FREEZE_AUTHORITY_OFFSET),
+            is_initialized: d[MINT_INITIALIZED_OFFSET] != 0,
+            extensions: Some(Value::Object(extensions)),
+            name,
+            symbol,
+        })]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parser::{ParserEngine, SPL_TOKEN_2022_PROGRAM_ID, SPL_TOKEN_PROGRAM_ID};
+
+    fn engine() -> ParserEngine {
+        let mut engine = ParserEngine::new();
+        for program in [SPL_TOKEN_PROGRAM_ID, SPL_TOKEN_2022_PROGRAM_ID] {
+            engine.register_account(Box::new(TokenAccountParser::new(program)));
+            engine.register_account(Box::new(MintParser::new(program)));
+        }
+        engine
+    }
+
+    fn update(owner: &str, data: Vec<u8>) -> AccountUpdate {
+        AccountUpdate {
+            pubkey: "acct".into(),
+            slot: 300_000_000,
+            owner: owner.into(),
+            lamports: 2_039_280,
+            executable: false,
+            rent_epoch: 0,
+            data,
+            write_version: 0,
+        }
+    }
+
+    #[test]
+    fn test_decode_token_account() {
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[ACCOUNT_MINT_OFFSET..ACCOUNT_MINT_OFFSET + 32].copy_from_slice(&[1u8; 32]);
+        data[ACCOUNT_OWNER_OFFSET..ACCOUNT_OWNER_OFFSET + 32].copy_from_slice(&[2u8; 32]);
+        data[ACCOUNT_AMOUNT_OFFSET..ACCOUNT_AMOUNT_OFFSET + 8].copy_from_slice(&1_500_000u64.to_le_bytes());
+        data[ACCOUNT_STATE_OFFSET] = 2;
+        data[ACCOUNT_CLOSE_AUTHORITY_OFFSET] = 1;
+        data[ACCOUNT_CLOSE_AUTHORITY_OFFSET + 4..ACCOUNT_CLOSE_AUTHORITY_OFFSET + 36].copy_from_slice(&[3u8; 32]);
+
+        let events = engine().parse_account(&update(SPL_TOKEN_PROGRAM_ID, data));
+        let [ParsedEvent::TokenAccount(a)] = events.as_slice() else {
+            panic!("expected a token account, got {events:?}");
+        };
+        assert_eq!(a.mint, bs58::encode([1u8; 32]).into_string());
+        assert_eq!(a.owner, bs58::encode([2u8; 32]).into_string());
+        assert_eq!(a.amount, 1_500_000);
+        assert_eq!(a.state, TokenAccountState::Frozen);
+        assert_eq!(a.delegate, None);
+        assert_eq!(a.close_authority, Some(bs58::encode([3u8; 32]).into_string()));
+    }
+
+    #[test]
+    fn test_closed_token_account_decodes_to_nothing() {
+        let mut closed = update(SPL_TOKEN_PROGRAM_ID, vec![0u8; TOKEN_ACCOUNT_LEN]);
+        closed.lamports = 0;
+        assert!(engine().parse_account(&closed).is_empty());
+    }
+
+    #[test]
+    fn test_decode_extended_token_2022_mint() {
+        // Base mint padded to the account length, then the account type and TLV extensions.
+        let mut data = vec![0u8; TOKEN_ACCOUNT_LEN];
+        data[MINT_SUPPLY_OFFSET..MINT_SUPPLY_OFFSET + 8].copy_from_slice(&42_000u64.to_le_bytes());
+        data[MINT_DECIMALS_OFFSET] = 6;
+        data[MINT_INITIALIZED_OFFSET] = 1;
+        data[MINT_AUTHORITY_OFFSET] = 1;
+        data[MINT_AUTHORITY_OFFSET + 4..MINT_AUTHORITY_OFFSET + 36].copy_from_slice(&[9u8; 32]);
+        data.push(ACCOUNT_TYPE_MINT);
+        data.extend_from_slice(&EXT_PERMANENT_DELEGATE.to_le_bytes());
+        data.extend_from_slice(&32u16.to_le_bytes());
+        data.extend_from_slice(&[4u8; 32]);
+        let mut metadata = [[5u8; 32], [6u8; 32]].concat();
+        for field in ["Example\0\0", "EXM", "https://example.com/exm.json"] {
+            metadata.extend_from_slice(&(field.len() as u32).to_le_bytes());
+            metadata.extend_from_slice(field.as_bytes());
+        }
+        metadata.extend_from_slice(&0u32.to_le_bytes()); // additional_metadata
+        data.extend_from_slice(&EXT_TOKEN_METADATA.to_le_bytes());
+        data.extend_from_slice(&(metadata.len() as u16).to_le_bytes());
+        data.extend_from_slice(&metadata);
+
+        let events = engine().parse_account(&update(SPL_TOKEN_2022_PROGRAM_ID, data));
+        let [ParsedEvent::Mint(m)] = events.as_slice() else {
+            panic!("expected a mint, got {events:?}");
+        };
+        assert_eq!(m.program, SPL_TOKEN_2022_PROGRAM_ID);
+        assert_eq!((m.decimals, m.supply), (6, 42_000));
+        assert_eq!(m.mint_authority, Some(bs58::encode([9u8; 32]).into_string()));
+        assert_eq!(m.freeze_authority, None);
+        assert_eq!((m.name.as_deref(), m.symbol.as_deref()), (Some("Example"), Some("EXM")));
+        let extensions = m.extensions.as_ref().unwrap();
+        assert_eq!(extensions["PermanentDelegate"]["delegate"], bs58::encode([4u8; 32]).into_string());
+        assert_eq!(extensions["TokenMetadata"]["uri"], "https://example.com/exm.json");
+    }
+}
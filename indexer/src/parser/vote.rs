@@ -0,0 +1,274 @@
+//! Vote program parser.
+//!
+//! Vote instructions are reported with their latency (slots between the
+//! newest voted slot and the slot the vote landed in). Vote account updates
+//! are decoded for the validator identity, commission and epoch credits.
+
+use crate::parser::{
+    AccountParser, EpochCredits, ParsedEvent, Parser, VoteAccountEvent, VoteEvent, VOTE_PROGRAM_ID,
+};
+use crate::receiver::{AccountUpdate, RawInstruction, TransactionUpdate};
+
+pub struct VoteParser;
+
+impl VoteParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+/// Decodes vote accounts.
+pub struct VoteAccountParser;
+
+impl VoteAccountParser {
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+// Vote instruction indices (bincode u32 LE tag)
+const IX_VOTE: u32 = 2;
+const IX_VOTE_SWITCH: u32 = 6;
+const IX_UPDATE_VOTE_STATE: u32 = 8;
+const IX_UPDATE_VOTE_STATE_SWITCH: u32 = 9;
+const IX_COMPACT_UPDATE_VOTE_STATE: u32 = 12;
+const IX_COMPACT_UPDATE_VOTE_STATE_SWITCH: u32 = 13;
+const IX_TOWER_SYNC: u32 = 14;
+const IX_TOWER_SYNC_SWITCH: u32 = 15;
+
+// `VoteStateVersions` tags
+const STATE_V1_14_11: u32 = 1;
+const STATE_CURRENT: u32 = 2;
+
+// `prior_voters`: 32 x (Pubkey, Epoch, Epoch), idx u64, is_empty bool
+const PRIOR_VOTERS_LEN: usize = 32 * 48 + 8 + 1;
+
+/// Cursor over bincode-encoded instruction and account data.
+struct Args<'a>(&'a [u8]);
+
+impl Args<'_> {
+    fn take(&mut self, n: usize) -> Option<&[u8]> {
+        if self.0.len() < n {
+            return None;
+        }
+        let (head, tail) = self.0.split_at(n);
+        self.0 = tail;
+        Some(head)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_le_bytes(self.take(4)?.try_into().ok()?))
+    }
+
+    fn u64(&mut self) -> Option<u64> {
+        Some(u64::from_le_bytes(self.take(8)?.try_into().ok()?))
+    }
+
+    fn pubkey(&mut self) -> Option<String> {
+        Some(bs58::encode(self.take(32)?).into_string())
+    }
+
+    fn option_u64(&mut self) -> Option<Option<u64>> {
+        match self.u8()? {
+            0 => Some(None),
+            _ => Some(Some(self.u64()?)),
+        }
+    }
+
+    /// LEB128 integer, as used by `serde_varint` and `short_vec` lengths.
+    fn varint(&mut self) -> Option<u64> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.u8()?;
+            value |= u64::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return Some(value);
+            }
+        }
+        None
+    }
+}
+
+/// `(newest voted slot, root slot)` from a vote instruction's arguments.
+fn decode_vote(tag: u32, args: &mut Args) -> Option<(u64, Option<u64>)> {
+    match tag {
+        // Vote { slots: Vec<Slot>, hash, timestamp }
+        IX_VOTE | IX_VOTE_SWITCH => {
+            let len = args.u64()?;
+            let mut newest = None;
+            for _ in 0..len {
+                newest = newest.max(Some(args.u64()?));
+            }
+            Some((newest?, None))
+        }
+        // VoteStateUpdate { lockouts: VecDeque<Lockout>, root: Option<Slot>, hash, timestamp }
+        IX_UPDATE_VOTE_STATE | IX_UPDATE_VOTE_STATE_SWITCH => {
+            let len = args.u64()?;
+            let mut newest = None;
+            for _ in 0..len {
+                newest = Some(args.u64()?);
+                args.u32()?;
+            }
+            Some((newest?, args.option_u64()?))
+        }
+        // Compact encoding: root (u64::MAX for none) followed by a short_vec of
+        // (varint slot offset, confirmation count) relative to the previous slot.
+        IX_COMPACT_UPDATE_VOTE_STATE
+        | IX_COMPACT_UPDATE_VOTE_STATE_SWITCH
+        | IX_TOWER_SYNC
+        | IX_TOWER_SYNC_SWITCH => {
+            let root = args.u64()?;
+            let root = (root != u64::MAX).then_some(root);
+            let len = args.varint()?;
+            let mut slot = root.unwrap_or_default();
+            for _ in 0..len {
+                slot = slot.checked_add(args.varint()?)?;
+                args.u8()?;
+            }
+            (len > 0).then_some((slot, root))
+        }
+        _ => None,
+    }
+}
+
+fn decode(tx: &TransactionUpdate, ix: &RawInstruction) -> Option<VoteEvent> {
+    let args = &mut Args(&ix.data);
+    let tag = args.u32()?;
+    let (voted_slot, root_slot) = decode_vote(tag, args)?;
+    // accounts: Vote/VoteSwitch [vote, slot_hashes, clock, authority]; others [vote, authority]
+    let authority = match tag {
+        IX_VOTE | IX_VOTE_SWITCH => ix.accounts.get(3),
+        _ => ix.accounts.get(1),
+    };
+    Some(VoteEvent {
+        signature: tx.signature.clone(),
+        slot: tx.slot,
+        block_time: tx.block_time,
+        vote_account: ix.accounts.first()?.clone(),
+        authority: authority.cloned(),
+        voted_slot,
+        root_slot,
+        latency: tx.slot.saturating_sub(voted_slot),
+    })
+}
+
+/// Decode `VoteStateVersions` (1.14.11 or current) from a vote account.
+fn decode_vote_account(account: &AccountUpdate) -> Option<VoteAccountEvent> {
+    let args = &mut Args(&account.data);
+    // Lockouts are (slot u64, confirmation_count u32); current versions
+    // prefix each with its latency byte.
+    let lockout_len = match args.u32()? {
+        STATE_V1_14_11 => 12,
+        STATE_CURRENT => 13,
+        _ => return None,
+    };
+    let node = args.pubkey()?;
+    args.pubkey()?; // authorized_withdrawer
+    let commission = args.u8()?;
+    let votes = args.u64()? as usize;
+    args.take(votes.checked_mul(lockout_len)?)?;
+    let root_slot = args.option_u64()?;
+    let authorized_voters = args.u64()? as usize;
+    args.take(authorized_voters.checked_mul(40)?)?;
+    args.take(PRIOR_VOTERS_LEN)?;
+    let epochs = args.u64()? as usize;
+    args.take(epochs.saturating_sub(1).checked_mul(24)?)?;
+    let epoch_credits = if epochs > 0 {
+        Some(EpochCredits {
+            epoch: args.u64()?,
+            credits: args.u64()?,
+            prev_credits: args.u64()?,
+        })
+    } else {
+        None
+    };
+
+    Some(VoteAccountEvent {
+        address: account.pubkey.clone(),
+        slot: account.slot,
+        node,
+        commission,
+        root_slot,
+        epoch_credits,
+    })
+}
+
+impl Parser for VoteParser {
+    fn program_id(&self) -> &str {
+        VOTE_PROGRAM_ID
+    }
+
+    fn parse(&self, tx: &TransactionUpdate) -> Vec<ParsedEvent> {
+        // Failed votes never reach the vote account.
+        if !tx.success {
+            return Vec::new();
+        }
+        tx.instructions
+            .iter()
+            .filter(|ix| ix.program_id == VOTE_PROGRAM_ID)
+            .filter_map(|ix| decode(tx, ix))
+            .map(ParsedEvent::Vote)
+            .collect()
+    }
+}
+
+impl AccountParser for VoteAccountParser {
+    fn owner(&self) -> &str {
+        VOTE_PROGRAM_ID
+    }
+
+    // Every vote-owned account is a vote account; the version tag is checked while decoding.
+    fn matches(&self, _data: &[u8]) -> bool {
+        true
+    }
+
+    fn parse(&self, account: &AccountUpdate) -> Vec<ParsedEvent> {
+        decode_vote_account(account)
+            .map(ParsedEvent::VoteAccount)
+            .into_iter()
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tower_sync_latency() {
+        // root 1000, lockouts at 1001, 1003, 1004
+        let mut data = IX_TOWER_SYNC.to_le_bytes().to_vec();
+        data.extend_from_slice(&1000u64.to_le_bytes());
+        data.push(3);
+        data.extend_from_slice(&[1, 3, 2, 2, 1, 1]);
+        data.extend_from_slice(&[0u8; 32]); // hash
+        data.push(0); // timestamp
+        data.extend_from_slice(&[0u8; 32]); // block_id
+
+        let mut tx = TransactionUpdate::test(vec![RawInstruction {
+            program_id: VOTE_PROGRAM_ID.into(),
+            instruction_index: 0,
+            inner_index: None,
+            data,
+            accounts: vec!["vote".into(), "auth".into()],
+        }]);
+        tx.slot = 1006;
+        tx.compute_units = 2100;
+
+        let events = VoteParser::new().parse(&tx);
+        assert_eq!(events.len(), 1);
+        if let ParsedEvent::Vote(v) = &events[0] {
+            assert_eq!(v.vote_account, "vote");
+            assert_eq!(v.authority.as_deref(), Some("auth"));
+            assert_eq!(v.voted_slot, 1004);
+            assert_eq!(v.root_slot, Some(1000));
+            assert_eq!(v.latency, 2);
+        } else {
+            panic!("Expected Vote");
+        }
+    }
+}
@@ -18,6 +18,9 @@ pub struct AccountUpdate {
     pub executable: bool,
     pub rent_epoch: u64,
     pub data: Vec<u8>,
+    /// Orders updates of the same account within a slot.
+    #[serde(default)]
+    pub write_version: u64,
 }
 
 /// Simplified transaction update from the gRPC stream.
@@ -25,6 +28,8 @@ pub struct AccountUpdate {
 pub struct TransactionUpdate {
     pub signature: String,
     pub slot: u64,
+    /// Position of the transaction within its block.
+    pub index: u64,
     pub block_time: i64,
     pub success: bool,
     pub fee: u64,
@@ -32,9 +37,87 @@ pub struct TransactionUpdate {
     pub accounts: Vec<String>,
     pub log_messages: Vec<String>,
     pub instructions: Vec<RawInstruction>,
+    /// SPL token balances of the token accounts touched by the transaction,
+    /// before and after execution (from the transaction meta).
+    pub pre_token_balances: Vec<TokenBalance>,
+    pub post_token_balances: Vec<TokenBalance>,
+}
+
+impl TransactionUpdate {
+    /// Token balance entry for a token account touched by this transaction,
+    /// preferring the post-execution state.
+    pub fn token_balance(&self, account: &str) -> Option<&TokenBalance> {
+        self.post_token_balances
+            .iter()
+            .chain(self.pre_token_balances.iter())
+            .find(|b| b.account == account)
+    }
+
+    /// Increase of a token account's balance over the transaction; `None` if
+    /// the account is not in the post balances or its balance went down.
+    pub fn token_balance_increase(&self, account: &str) -> Option<u64> {
+        let post = self.post_token_balances.iter().find(|b| b.account == account)?.amount;
+        let pre = self
+            .pre_token_balances
+            .iter()
+            .find(|b| b.account == account)
+            .map_or(0, |b| b.amount);
+        post.checked_sub(pre)
+    }
+
+    /// Inner instructions invoked after the instruction at `pos` within the
+    /// same outer instruction.
+    pub fn inner_instructions(&self, pos: usize) -> &[RawInstruction] {
+        let Some(ix) = self.instructions.get(pos) else {
+            return &[];
+        };
+        let rest = &self.instructions[pos + 1..];
+        let len = rest
+            .iter()
+            .take_while(|i| i.instruction_index == ix.instruction_index && i.inner_index.is_some())
+            .count();
+        &rest[..len]
+    }
+}
+
+#[cfg(test)]
+impl TransactionUpdate {
+    /// A successful transaction in slot 1 running `instructions`, for parser
+    /// tests; other fields are set on the result as needed.
+    pub fn test(instructions: Vec<RawInstruction>) -> Self {
+        Self {
+            signature: "sig".into(),
+            slot: 1,
+            index: 0,
+            block_time: 1_700_000_000,
+            success: true,
+            fee: 5000,
+            compute_units: 200_000,
+            accounts: vec![],
+            log_messages: vec![],
+            instructions,
+            pre_token_balances: vec![],
+            post_token_balances: vec![],
+        }
+    }
+}
+
+/// Token balance entry from the transaction meta, with the account index
+/// already resolved to the token account's pubkey.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenBalance {
+    pub account: String,
+    pub mint: String,
+    pub owner: String,
+    pub amount: u64,
+    pub decimals: u8,
 }
 
 /// A single instruction extracted from a transaction.
+///
+/// Instructions are flattened in execution order: each outer instruction
+/// (`inner_index == None`) is followed by the inner instructions it invoked,
+/// which share its `instruction_index`.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RawInstruction {
     pub program_id: String,
@@ -53,6 +136,40 @@ pub struct BlockUpdate {
     pub block_height: u64,
     pub leader: String,
     pub tip_slot: u64,
+    /// Rewards credited by the block (from the block meta).
+    pub rewards: Vec<Reward>,
+}
+
+/// A reward credited to an account by a block.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reward {
+    pub pubkey: String,
+    /// Negative for rent debits.
+    pub lamports: i64,
+    pub post_balance: u64,
+    pub reward_type: Option<RewardType>,
+    /// Vote account commission, for staking and voting rewards.
+    pub commission: Option<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RewardType {
+    Fee,
+    Rent,
+    Staking,
+    Voting,
+}
+
+impl RewardType {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            RewardType::Fee => "fee",
+            RewardType::Rent => "rent",
+            RewardType::Staking => "staking",
+            RewardType::Voting => "voting",
+        }
+    }
 }
 
 /// Tagged union of all update types from the stream.
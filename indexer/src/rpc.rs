@@ -0,0 +1,65 @@
+//! Minimal Solana JSON-RPC client for data the Geyser stream does not carry.
+
+use anyhow::{Context, Result};
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+use crate::parser::stake::SLOTS_PER_EPOCH;
+
+pub struct RpcClient {
+    http: reqwest::Client,
+    url: String,
+}
+
+#[derive(Deserialize)]
+struct RpcResponse<T> {
+    result: Option<T>,
+    error: Option<serde_json::Value>,
+}
+
+impl RpcClient {
+    pub fn new(url: String) -> Self {
+        Self {
+            http: reqwest::Client::new(),
+            url,
+        }
+    }
+
+    async fn call<T>(&self, method: &str, params: serde_json::Value) -> Result<Option<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let response: RpcResponse<T> = self
+            .http
+            .post(&self.url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .with_context(|| format!("{method} request failed"))?
+            .error_for_status()?
+            .json()
+            .await
+            .with_context(|| format!("Invalid {method} response"))?;
+        if let Some(error) = response.error {
+            anyhow::bail!("{method} failed: {error}");
+        }
+        Ok(response.result)
+    }
+
+    /// Leader schedule for `epoch` as `(slot, validator identity)` pairs, sorted
+    /// by slot. Empty if the node does not know the epoch yet.
+    pub async fn leader_schedule(&self, epoch: u64) -> Result<Vec<(u64, String)>> {
+        let first_slot = epoch * SLOTS_PER_EPOCH;
+        // identity → slot indices relative to the first slot of the epoch
+        let schedule: Option<HashMap<String, Vec<u64>>> =
+            self.call("getLeaderSchedule", json!([first_slot])).await?;
+        let mut leaders: Vec<(u64, String)> = schedule
+            .unwrap_or_default()
+            .into_iter()
+            .flat_map(|(identity, slots)| slots.into_iter().map(move |i| (first_slot + i, identity.clone())))
+            .collect();
+        leaders.sort_unstable();
+        Ok(leaders)
+    }
+}
@@ -0,0 +1,162 @@
+//! Account state writes: the latest state per account, plus history in
+//! `account_states` as far as the configured retention allows, and accounts
+//! decoded through Anchor IDLs.
+
+use super::{column, newest, Writer};
+use crate::parser::AnchorAccountEvent;
+use crate::receiver::AccountUpdate;
+use sqlx::types::Json;
+use sqlx::PgConnection;
+use std::collections::HashMap;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_account_states(&mut self, db: &mut PgConnection, accounts: &[AccountUpdate]) -> bool {
+        // One statement cannot upsert the same row twice; keep the newest write per key.
+        let mut latest: HashMap<&str, &AccountUpdate> = HashMap::new();
+        let mut versions: HashMap<(&str, u64), &AccountUpdate> = HashMap::new();
+        for a in accounts {
+            let newer = |b: &&AccountUpdate| (a.slot, a.write_version) >= (b.slot, b.write_version);
+            if latest.get(a.pubkey.as_str()).is_none_or(newer) {
+                latest.insert(&a.pubkey, a);
+            }
+            if self.account_history.keeps(&a.owner)
+                && versions.get(&(a.pubkey.as_str(), a.slot)).is_none_or(newer)
+            {
+                versions.insert((&a.pubkey, a.slot), a);
+            }
+        }
+        let latest: Vec<&AccountUpdate> = latest.into_values().collect();
+        let history: Vec<&AccountUpdate> = versions.into_values().collect();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO accounts_latest (pubkey, slot, write_version, owner, lamports, executable, rent_epoch,
+                                         data, updated_at)
+            SELECT *, NOW() FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::BIGINT[], $4::TEXT[], $5::BIGINT[],
+                                        $6::BOOL[], $7::BIGINT[], $8::BYTEA[])
+            ON CONFLICT (pubkey) DO UPDATE
+            SET slot = EXCLUDED.slot, write_version = EXCLUDED.write_version,
+                owner = EXCLUDED.owner, lamports = EXCLUDED.lamports,
+                executable = EXCLUDED.executable, rent_epoch = EXCLUDED.rent_epoch,
+                data = EXCLUDED.data, updated_at = NOW()
+            WHERE (accounts_latest.slot, accounts_latest.write_version)
+                < (EXCLUDED.slot, EXCLUDED.write_version)
+            "#,
+        )
+        .bind(column(&latest, |a| a.pubkey.clone()))
+        .bind(column(&latest, |a| a.slot as i64))
+        .bind(column(&latest, |a| a.write_version as i64))
+        .bind(column(&latest, |a| a.owner.clone()))
+        .bind(column(&latest, |a| a.lamports as i64))
+        .bind(column(&latest, |a| a.executable))
+        .bind(column(&latest, |a| a.rent_epoch as i64))
+        .bind(column(&latest, |a| a.data.clone()))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} latest account states: {e}", latest.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        if !history.is_empty() && !self.write_account_history(db, &history).await {
+            return false;
+        }
+
+        self.publish_all(accounts.iter().map(|a| (format!("account:{}", a.pubkey), a)));
+        true
+    }
+
+    async fn write_account_history(&mut self, db: &mut PgConnection, rows: &[&AccountUpdate]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO account_states (pubkey, slot, write_version, owner, lamports, executable, rent_epoch, data)
+            SELECT * FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::BIGINT[], $4::TEXT[], $5::BIGINT[],
+                                 $6::BOOL[], $7::BIGINT[], $8::BYTEA[])
+            ON CONFLICT (pubkey, slot) DO UPDATE
+            SET write_version = EXCLUDED.write_version,
+                owner = EXCLUDED.owner, lamports = EXCLUDED.lamports,
+                executable = EXCLUDED.executable, rent_epoch = EXCLUDED.rent_epoch,
+                data = EXCLUDED.data
+            WHERE account_states.write_version <= EXCLUDED.write_version
+            "#,
+        )
+        .bind(column(rows, |a| a.pubkey.clone()))
+        .bind(column(rows, |a| a.slot as i64))
+        .bind(column(rows, |a| a.write_version as i64))
+        .bind(column(rows, |a| a.owner.clone()))
+        .bind(column(rows, |a| a.lamports as i64))
+        .bind(column(rows, |a| a.executable))
+        .bind(column(rows, |a| a.rent_epoch as i64))
+        .bind(column(rows, |a| a.data.clone()))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} account states: {e}", rows.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        let Some(keep) = self.account_history.versions else {
+            return true;
+        };
+        // Drop everything older than the N-th newest version of each touched account.
+        let mut pubkeys = column(rows, |a| a.pubkey.clone());
+        pubkeys.sort_unstable();
+        pubkeys.dedup();
+        let result = sqlx::query(
+            r#"
+            DELETE FROM account_states a
+            USING UNNEST($1::TEXT[]) AS t(pubkey)
+            WHERE a.pubkey = t.pubkey
+              AND a.slot < (SELECT s.slot FROM account_states s
+                            WHERE s.pubkey = t.pubkey
+                            ORDER BY s.slot DESC
+                            OFFSET $2 - 1 LIMIT 1)
+            "#,
+        )
+        .bind(&pubkeys)
+        .bind(keep as i64)
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to prune history of {} accounts: {e}", pubkeys.len());
+            self.db_error(&e);
+            return false;
+        }
+        true
+    }
+
+    pub(super) async fn write_anchor_accounts(&mut self, db: &mut PgConnection, accounts: &[AnchorAccountEvent]) -> bool {
+        let rows = newest(accounts, |a| (&a.address, a.slot));
+        let result = sqlx::query(
+            r#"
+            INSERT INTO anchor_accounts (address, program, account_type, data, slot, updated_at)
+            SELECT *, NOW() FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::JSONB[], $5::BIGINT[])
+            ON CONFLICT (address) DO UPDATE
+            SET program = EXCLUDED.program, account_type = EXCLUDED.account_type,
+                data = EXCLUDED.data, slot = EXCLUDED.slot, updated_at = NOW()
+            WHERE anchor_accounts.slot <= EXCLUDED.slot
+            "#,
+        )
+        .bind(column(&rows, |a| a.address.clone()))
+        .bind(column(&rows, |a| a.program.clone()))
+        .bind(column(&rows, |a| a.account_type.clone()))
+        .bind(column(&rows, |a| Json(&a.data)))
+        .bind(column(&rows, |a| a.slot as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} Anchor accounts: {e}", rows.len());
+            self.db_error(&e);
+            return false;
+        }
+        self.publish_all(rows.iter().map(|a| (format!("account_decoded:{}", a.address), a)));
+        true
+    }
+}
@@ -0,0 +1,123 @@
+//! Compressed NFT writes — Merkle tree nodes, tree roots and leaves.
+//!
+//! Every upsert is guarded by the tree sequence number so that replayed or
+//! out-of-order change logs never overwrite newer state. Events are applied
+//! one at a time: consecutive changes to a tree rewrite the same path nodes,
+//! which a single batched upsert cannot touch twice.
+
+use super::Writer;
+use crate::parser::{CnftAction, CnftEvent};
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_cnft_events(&mut self, db: &mut PgConnection, events: &[CnftEvent]) -> bool {
+        for c in events {
+            if let Err(e) = self.write_cnft_event(db, c).await {
+                error!("Failed to write cNFT {} leaf {}: {e}", c.tree, c.leaf_index);
+                self.db_error(&e);
+                return false;
+            }
+
+            if let Some(leaf) = &c.leaf {
+                let channel = format!("cnft:{}", leaf.asset_id);
+                self.publish(&channel, c);
+            }
+        }
+        true
+    }
+
+    async fn write_cnft_event(&mut self, db: &mut PgConnection, c: &CnftEvent) -> Result<(), sqlx::Error> {
+        let node_indexes: Vec<i64> = c.path.iter().map(|n| n.index as i64).collect();
+        let node_hashes: Vec<Vec<u8>> = c.path.iter().map(|n| n.hash.to_vec()).collect();
+
+        sqlx::query(
+            r#"
+            INSERT INTO cnft_tree_nodes (tree, node_index, hash, seq, slot)
+            SELECT $1, idx, hash, $4, $5
+            FROM UNNEST($2::BIGINT[], $3::BYTEA[]) AS n(idx, hash)
+            ON CONFLICT (tree, node_index) DO UPDATE
+            SET hash = EXCLUDED.hash, seq = EXCLUDED.seq, slot = EXCLUDED.slot
+            WHERE cnft_tree_nodes.seq < EXCLUDED.seq
+            "#,
+        )
+        .bind(&c.tree)
+        .bind(&node_indexes)
+        .bind(&node_hashes)
+        .bind(c.seq as i64)
+        .bind(c.slot as i64)
+        .execute(&mut *db)
+        .await?;
+
+        // path runs leaf → root, so its length is max_depth + 1
+        sqlx::query(
+            r#"
+            INSERT INTO cnft_trees (tree, max_depth, seq, root, updated_slot)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (tree) DO UPDATE
+            SET seq = EXCLUDED.seq, root = EXCLUDED.root, updated_slot = EXCLUDED.updated_slot
+            WHERE cnft_trees.seq < EXCLUDED.seq
+            "#,
+        )
+        .bind(&c.tree)
+        .bind(c.path.len().saturating_sub(1) as i16)
+        .bind(c.seq as i64)
+        .bind(c.path.last().map(|n| n.hash.to_vec()))
+        .bind(c.slot as i64)
+        .execute(&mut *db)
+        .await?;
+
+        let leaf = c.leaf.as_ref();
+        let metadata = c.metadata.as_ref();
+        sqlx::query(
+            r#"
+            INSERT INTO cnft_leaves (tree, leaf_index, asset_id, owner, delegate, nonce, data_hash,
+                                     creator_hash, leaf_hash, name, symbol, uri, collection,
+                                     collection_verified, burned, last_action, seq,
+                                     created_slot, updated_slot)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17,
+                    $18, $18)
+            ON CONFLICT (tree, leaf_index) DO UPDATE
+            SET asset_id            = COALESCE(EXCLUDED.asset_id, cnft_leaves.asset_id),
+                owner               = COALESCE(EXCLUDED.owner, cnft_leaves.owner),
+                delegate            = COALESCE(EXCLUDED.delegate, cnft_leaves.delegate),
+                nonce               = COALESCE(EXCLUDED.nonce, cnft_leaves.nonce),
+                data_hash           = COALESCE(EXCLUDED.data_hash, cnft_leaves.data_hash),
+                creator_hash        = COALESCE(EXCLUDED.creator_hash, cnft_leaves.creator_hash),
+                leaf_hash           = EXCLUDED.leaf_hash,
+                name                = COALESCE(EXCLUDED.name, cnft_leaves.name),
+                symbol              = COALESCE(EXCLUDED.symbol, cnft_leaves.symbol),
+                uri                 = COALESCE(EXCLUDED.uri, cnft_leaves.uri),
+                collection          = COALESCE(EXCLUDED.collection, cnft_leaves.collection),
+                collection_verified = EXCLUDED.collection_verified OR cnft_leaves.collection_verified,
+                burned              = EXCLUDED.burned,
+                last_action         = EXCLUDED.last_action,
+                seq                 = EXCLUDED.seq,
+                updated_slot        = EXCLUDED.updated_slot
+            WHERE cnft_leaves.seq < EXCLUDED.seq
+            "#,
+        )
+        .bind(&c.tree)
+        .bind(c.leaf_index as i64)
+        .bind(leaf.map(|l| &l.asset_id))
+        .bind(leaf.map(|l| &l.owner))
+        .bind(leaf.map(|l| &l.delegate))
+        .bind(leaf.map(|l| l.nonce as i64))
+        .bind(leaf.map(|l| l.data_hash.to_vec()))
+        .bind(leaf.map(|l| l.creator_hash.to_vec()))
+        .bind(c.path.first().map(|n| n.hash.to_vec()))
+        .bind(metadata.map(|m| &m.name))
+        .bind(metadata.map(|m| &m.symbol))
+        .bind(metadata.map(|m| &m.uri))
+        .bind(metadata.and_then(|m| m.collection.as_ref()))
+        .bind(metadata.is_some_and(|m| m.collection_verified))
+        .bind(matches!(c.action, CnftAction::Burn | CnftAction::Redeem))
+        .bind(c.action.as_str())
+        .bind(c.seq as i64)
+        .bind(c.slot as i64)
+        .execute(&mut *db)
+        .await?;
+
+        Ok(())
+    }
+}
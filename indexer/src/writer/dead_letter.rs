@@ -0,0 +1,197 @@
+//! Dead-letter store for batches that could not be written.
+//!
+//! Events are kept as `ParsedEvent` JSON so they can be re-driven through the
+//! normal write path once the cause has been fixed.
+
+use super::Writer;
+use crate::parser::ParsedEvent;
+use anyhow::Result;
+use serde::Serialize;
+use sqlx::types::Json;
+use sqlx::Row;
+use tracing::{error, info, warn};
+
+/// A dead-lettered event as listed by the CLI.
+#[derive(Debug, Serialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub kind: String,
+    pub error: String,
+    pub attempts: i32,
+    pub failed_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl Writer {
+    /// Move a batch that exhausted its retries into `dead_letter_events`.
+    /// Deferred transaction events go with it so they cannot poison later batches.
+    pub(super) async fn dead_letter(&mut self, batch: &[ParsedEvent], reason: &str, attempts: u32) {
+        let mut events: Vec<ParsedEvent> = batch.to_vec();
+        events.append(&mut self.deferred);
+        self.store_dead_letters(&events, reason, attempts).await;
+    }
+
+    pub(super) async fn store_dead_letters(&mut self, events: &[ParsedEvent], reason: &str, attempts: u32) {
+        let kinds: Vec<&str> = events.iter().map(event_kind).collect();
+        let payloads: Vec<Json<&ParsedEvent>> = events.iter().map(Json).collect();
+
+        for attempt in 1..=super::FLUSH_ATTEMPTS {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO dead_letter_events (kind, event, error, attempts)
+                SELECT kind, event, $3, $4 FROM UNNEST($1::TEXT[], $2::JSONB[]) AS t(kind, event)
+                "#,
+            )
+            .bind(&kinds)
+            .bind(&payloads)
+            .bind(reason)
+            .bind(attempts as i32)
+            .execute(&self.pool)
+            .await;
+
+            match result {
+                Ok(_) => {
+                    warn!("Dead-lettered {} events: {reason}", events.len());
+                    self.metrics.dead_letter_events.increment(events.len() as u64);
+                    return;
+                }
+                Err(e) => {
+                    warn!("Failed to dead-letter {} events (attempt {attempt}): {e}", events.len());
+                    tokio::time::sleep(super::backoff(attempt)).await;
+                }
+            }
+        }
+        error!("Lost {} events that could not be dead-lettered: {reason}", events.len());
+        self.metrics.db_errors.increment(1);
+    }
+
+    /// Most recent dead letters, newest first.
+    pub async fn list_dead_letters(&self, limit: i64) -> Result<Vec<DeadLetter>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, kind, error, attempts, failed_at
+            FROM dead_letter_events
+            ORDER BY id DESC
+            LIMIT $1
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|r| DeadLetter {
+                id: r.get("id"),
+                kind: r.get("kind"),
+                error: r.get("error"),
+                attempts: r.get("attempts"),
+                failed_at: r.get("failed_at"),
+            })
+            .collect())
+    }
+
+    /// Re-drive up to `limit` dead letters, oldest first, one batch at a time.
+    /// Rows are deleted once their batch commits; the first failing batch
+    /// stops the replay and stays in the table.
+    pub async fn replay_dead_letters(&mut self, limit: i64) -> Result<usize> {
+        let mut replayed = 0;
+        let mut after = 0i64;
+        while (replayed as i64) < limit {
+            let chunk = (limit - replayed as i64).min(self.batch_size as i64);
+            let rows = sqlx::query(
+                "SELECT id, event FROM dead_letter_events WHERE id > $1 ORDER BY id LIMIT $2",
+            )
+            .bind(after)
+            .bind(chunk)
+            .fetch_all(&self.pool)
+            .await?;
+            let Some(last) = rows.last() else { break };
+            after = last.get("id");
+
+            // Undecodable rows are left in place for inspection.
+            let mut ids: Vec<i64> = Vec::with_capacity(rows.len());
+            let mut batch = Vec::with_capacity(rows.len());
+            for r in &rows {
+                let id: i64 = r.get("id");
+                match r.try_get::<Json<ParsedEvent>, _>("event") {
+                    Ok(Json(event)) => {
+                        ids.push(id);
+                        batch.push(event);
+                    }
+                    Err(e) => warn!("Skipping undecodable dead letter {id}: {e}"),
+                }
+            }
+
+            // Blocks of replayed transactions were committed earlier (or are in
+            // this batch), so nothing is held back.
+            self.written_blocks
+                .lock()
+                .unwrap()
+                .extend(batch.iter().filter_map(super::event_slot));
+            if !self.flush_batch(&batch).await {
+                let reason = self.last_error.clone().unwrap_or_default();
+                anyhow::bail!("replay stopped after {replayed} events: {reason}");
+            }
+
+            sqlx::query("DELETE FROM dead_letter_events WHERE id = ANY($1)")
+                .bind(&ids)
+                .execute(&self.pool)
+                .await?;
+            replayed += ids.len();
+            info!("Replayed {replayed} dead letters");
+        }
+        Ok(replayed)
+    }
+}
+
+/// The `ParsedEvent` variant name, e.g. `Transaction`.
+fn event_kind(event: &ParsedEvent) -> &'static str {
+    match event {
+        ParsedEvent::Transaction(_) => "Transaction",
+        ParsedEvent::Instruction(_) => "Instruction",
+        ParsedEvent::TokenTransfer(_) => "TokenTransfer",
+        ParsedEvent::SolTransfer(_) => "SolTransfer",
+        ParsedEvent::SystemAccount(_) => "SystemAccount",
+        ParsedEvent::Stake(_) => "Stake",
+        ParsedEvent::StakeAccount(_) => "StakeAccount",
+        ParsedEvent::StakePool(_) => "StakePool",
+        ParsedEvent::Lending(_) => "Lending",
+        ParsedEvent::Vote(_) => "Vote",
+        ParsedEvent::VoteAccount(_) => "VoteAccount",
+        ParsedEvent::LeaderSchedule(_) => "LeaderSchedule",
+        ParsedEvent::Bundle(_) => "Bundle",
+        ParsedEvent::Mev(_) => "Mev",
+        ParsedEvent::AccountUpdate(_) => "AccountUpdate",
+        ParsedEvent::Block(_) => "Block",
+        ParsedEvent::Pool(_) => "Pool",
+        ParsedEvent::TokenAccount(_) => "TokenAccount",
+        ParsedEvent::Mint(_) => "Mint",
+        ParsedEvent::TokenSupply(_) => "TokenSupply",
+        ParsedEvent::AnchorAccount(_) => "AnchorAccount",
+        ParsedEvent::Swap(_) => "Swap",
+        ParsedEvent::NftMint(_) => "NftMint",
+        ParsedEvent::NftTransfer(_) => "NftTransfer",
+        ParsedEvent::NftMetadata(_) => "NftMetadata",
+        ParsedEvent::NftSale(_) => "NftSale",
+        ParsedEvent::Cnft(_) => "Cnft",
+        ParsedEvent::CoreAsset(_) => "CoreAsset",
+    }
+}
+
+/// Whether a failed write is worth retrying as-is.
+pub(super) fn is_transient(e: &sqlx::Error) -> bool {
+    match e {
+        sqlx::Error::Io(_)
+        | sqlx::Error::Tls(_)
+        | sqlx::Error::Protocol(_)
+        | sqlx::Error::PoolTimedOut
+        | sqlx::Error::PoolClosed
+        | sqlx::Error::WorkerCrashed => true,
+        // serialization_failure, deadlock_detected, admin_shutdown and connection_* classes
+        sqlx::Error::Database(db) => matches!(
+            db.code().as_deref(),
+            Some("40001" | "40P01" | "57P01" | "08000" | "08003" | "08006")
+        ),
+        _ => false,
+    }
+}
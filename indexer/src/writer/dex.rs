@@ -0,0 +1,101 @@
+//! Pool registry and DEX trade writes.
+
+use super::{column, Writer};
+use crate::parser::{PoolEvent, SwapEvent};
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_pools(&mut self, db: &mut PgConnection, pools: &[PoolEvent]) -> bool {
+        for p in pools {
+            let result = sqlx::query(
+                r#"
+                INSERT INTO pools (address, program, base_mint, quote_mint, base_vault, quote_vault,
+                                   base_decimals, quote_decimals, fee_bps, created_slot, updated_slot)
+                VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11)
+                ON CONFLICT (address) DO UPDATE
+                SET base_mint      = EXCLUDED.base_mint,
+                    quote_mint     = EXCLUDED.quote_mint,
+                    base_vault     = COALESCE(EXCLUDED.base_vault, pools.base_vault),
+                    quote_vault    = COALESCE(EXCLUDED.quote_vault, pools.quote_vault),
+                    base_decimals  = COALESCE(EXCLUDED.base_decimals, pools.base_decimals),
+                    quote_decimals = COALESCE(EXCLUDED.quote_decimals, pools.quote_decimals),
+                    fee_bps        = COALESCE(EXCLUDED.fee_bps, pools.fee_bps),
+                    created_slot   = COALESCE(pools.created_slot, EXCLUDED.created_slot),
+                    updated_slot   = GREATEST(pools.updated_slot, EXCLUDED.updated_slot),
+                    updated_at     = NOW()
+                "#,
+            )
+            .bind(&p.address)
+            .bind(&p.program)
+            .bind(&p.base_mint)
+            .bind(&p.quote_mint)
+            .bind(&p.base_vault)
+            .bind(&p.quote_vault)
+            .bind(p.base_decimals.map(|d| d as i16))
+            .bind(p.quote_decimals.map(|d| d as i16))
+            .bind(p.fee_bps.map(|f| f as i32))
+            .bind(p.created.then_some(p.slot as i64))
+            .bind(p.slot as i64)
+            .execute(&mut *db)
+            .await;
+
+            if let Err(e) = result {
+                error!("Failed to write pool {}: {e}", p.address);
+                self.db_error(&e);
+                return false;
+            }
+        }
+        self.publish_all(pools.iter().map(|p| (format!("account_decoded:{}", p.address), p)));
+        true
+    }
+
+    pub(super) async fn write_swaps(&mut self, db: &mut PgConnection, swaps: &[SwapEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO dex_trades (signature, slot, block_time, program, pool, trader,
+                                    instruction_index, inner_index, side, base_mint, quote_mint,
+                                    base_amount, quote_amount, price, route)
+            SELECT signature, slot, to_timestamp(block_time), program, pool, trader,
+                   instruction_index, inner_index, side, base_mint, quote_mint,
+                   base_amount, quote_amount, price, route
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::TEXT[], $5::TEXT[], $6::TEXT[],
+                        $7::INT[], $8::INT[], $9::TEXT[], $10::TEXT[], $11::TEXT[], $12::BIGINT[],
+                        $13::BIGINT[], $14::FLOAT8[], $15::TEXT[])
+                AS t(signature, slot, block_time, program, pool, trader, instruction_index, inner_index,
+                     side, base_mint, quote_mint, base_amount, quote_amount, price, route)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(swaps, |s| s.signature.clone()))
+        .bind(column(swaps, |s| s.slot as i64))
+        .bind(column(swaps, |s| s.block_time as f64))
+        .bind(column(swaps, |s| s.program.clone()))
+        .bind(column(swaps, |s| s.pool.clone()))
+        .bind(column(swaps, |s| s.user.clone()))
+        .bind(column(swaps, |s| s.instruction_index as i32))
+        .bind(column(swaps, |s| s.inner_index.map(|i| i as i32)))
+        .bind(column(swaps, |s| s.side.as_str()))
+        .bind(column(swaps, |s| s.base_mint.clone()))
+        .bind(column(swaps, |s| s.quote_mint.clone()))
+        .bind(column(swaps, |s| s.base_amount as i64))
+        .bind(column(swaps, |s| s.quote_amount as i64))
+        .bind(column(swaps, |s| s.price))
+        .bind(column(swaps, |s| s.route.clone()))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} swaps: {e}", swaps.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        self.publish_all(
+            swaps
+                .iter()
+                .map(|s| (format!("swap:{}", s.pool.as_deref().unwrap_or(&s.program)), s)),
+        );
+        true
+    }
+}
@@ -0,0 +1,80 @@
+//! Priority fee samples for fee analytics.
+
+use super::{column, Writer};
+use crate::parser::{TransactionEvent, VOTE_PROGRAM_ID};
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    /// One sample per transaction and one per program it invoked. Vote
+    /// transactions pay no priority fees and are left out.
+    pub(super) async fn write_priority_fees(&mut self, db: &mut PgConnection, txs: &[TransactionEvent]) -> bool {
+        let txs: Vec<&TransactionEvent> = txs
+            .iter()
+            .filter(|tx| !tx.programs.iter().any(|p| p == VOTE_PROGRAM_ID))
+            .collect();
+        if txs.is_empty() {
+            return true;
+        }
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO priority_fees (signature, slot, block_time, success, compute_unit_limit,
+                                       compute_unit_price, priority_fee, compute_units)
+            SELECT signature, slot, to_timestamp(block_time), success, compute_unit_limit,
+                   compute_unit_price, priority_fee, compute_units
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::BOOL[], $5::BIGINT[],
+                        $6::BIGINT[], $7::BIGINT[], $8::BIGINT[])
+                AS t(signature, slot, block_time, success, compute_unit_limit,
+                     compute_unit_price, priority_fee, compute_units)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(&txs, |tx| tx.signature.clone()))
+        .bind(column(&txs, |tx| tx.slot as i64))
+        .bind(column(&txs, |tx| tx.block_time as f64))
+        .bind(column(&txs, |tx| tx.success))
+        .bind(column(&txs, |tx| tx.compute_unit_limit as i64))
+        .bind(column(&txs, |tx| tx.compute_unit_price as i64))
+        .bind(column(&txs, |tx| tx.priority_fee as i64))
+        .bind(column(&txs, |tx| tx.compute_units as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} priority fees: {e}", txs.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        let programs: Vec<(&TransactionEvent, &String)> = txs
+            .iter()
+            .flat_map(|tx| tx.programs.iter().map(move |p| (*tx, p)))
+            .collect();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO program_priority_fees (signature, slot, block_time, program_id,
+                                               compute_unit_price, priority_fee)
+            SELECT signature, slot, to_timestamp(block_time), program_id, compute_unit_price, priority_fee
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::TEXT[], $5::BIGINT[], $6::BIGINT[])
+                AS t(signature, slot, block_time, program_id, compute_unit_price, priority_fee)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(&programs, |(tx, _)| tx.signature.clone()))
+        .bind(column(&programs, |(tx, _)| tx.slot as i64))
+        .bind(column(&programs, |(tx, _)| tx.block_time as f64))
+        .bind(column(&programs, |(_, p)| p.to_string()))
+        .bind(column(&programs, |(tx, _)| tx.compute_unit_price as i64))
+        .bind(column(&programs, |(tx, _)| tx.priority_fee as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} program priority fees: {e}", programs.len());
+            self.db_error(&e);
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,57 @@
+//! Lending protocol event writes.
+
+use super::{column, Writer};
+use crate::parser::LendingEvent;
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_lending_events(&mut self, db: &mut PgConnection, events: &[LendingEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO lending_events (signature, slot, block_time, instruction_index, inner_index, program,
+                                        action, reserve, obligation, owner, mint, amount, liquidator,
+                                        liquidatee, collateral_reserve, collateral_mint, collateral_amount)
+            SELECT signature, slot, to_timestamp(block_time), instruction_index, inner_index, program,
+                   action, reserve, obligation, owner, mint, amount, liquidator, liquidatee,
+                   collateral_reserve, collateral_mint, collateral_amount
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::INT[], $5::INT[], $6::TEXT[],
+                        $7::TEXT[], $8::TEXT[], $9::TEXT[], $10::TEXT[], $11::TEXT[], $12::BIGINT[],
+                        $13::TEXT[], $14::TEXT[], $15::TEXT[], $16::TEXT[], $17::BIGINT[])
+                AS t(signature, slot, block_time, instruction_index, inner_index, program, action, reserve,
+                     obligation, owner, mint, amount, liquidator, liquidatee, collateral_reserve,
+                     collateral_mint, collateral_amount)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(events, |e| e.signature.clone()))
+        .bind(column(events, |e| e.slot as i64))
+        .bind(column(events, |e| e.block_time as f64))
+        .bind(column(events, |e| e.instruction_index as i32))
+        .bind(column(events, |e| e.inner_index.map(|i| i as i32)))
+        .bind(column(events, |e| e.program.clone()))
+        .bind(column(events, |e| e.action.as_str()))
+        .bind(column(events, |e| e.reserve.clone()))
+        .bind(column(events, |e| e.obligation.clone()))
+        .bind(column(events, |e| e.owner.clone()))
+        .bind(column(events, |e| e.mint.clone()))
+        .bind(column(events, |e| e.amount.map(|a| a as i64)))
+        .bind(column(events, |e| e.liquidator.clone()))
+        .bind(column(events, |e| e.liquidatee.clone()))
+        .bind(column(events, |e| e.collateral_reserve.clone()))
+        .bind(column(events, |e| e.collateral_mint.clone()))
+        .bind(column(events, |e| e.collateral_amount.map(|a| a as i64)))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} lending events: {e}", events.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        // Webhook subscribers filter these on `action`, `program`, `reserve`, ...
+        self.publish_all(events.iter().map(|e| (format!("lending:{}", e.program), e)));
+        true
+    }
+}
@@ -0,0 +1,84 @@
+//! Jito bundle and MEV event writes. Signature and pool lists go in as JSONB
+//! arrays, since `UNNEST` cannot unpack an array of arrays row by row.
+
+use super::{column, Writer};
+use crate::parser::{BundleEvent, MevEvent};
+use sqlx::types::Json;
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_bundles(&mut self, db: &mut PgConnection, bundles: &[BundleEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO bundles (slot, block_time, tip_signature, tip_payer, tip_lamports, tx_count,
+                                 signatures, first_index, last_index)
+            SELECT slot, to_timestamp(block_time), tip_signature, tip_payer, tip_lamports, tx_count,
+                   ARRAY(SELECT jsonb_array_elements_text(signatures)), first_index, last_index
+            FROM UNNEST($1::BIGINT[], $2::FLOAT8[], $3::TEXT[], $4::TEXT[], $5::BIGINT[], $6::SMALLINT[],
+                        $7::JSONB[], $8::BIGINT[], $9::BIGINT[])
+                AS t(slot, block_time, tip_signature, tip_payer, tip_lamports, tx_count, signatures,
+                     first_index, last_index)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(bundles, |b| b.slot as i64))
+        .bind(column(bundles, |b| b.block_time as f64))
+        .bind(column(bundles, |b| b.tip_signature.clone()))
+        .bind(column(bundles, |b| b.tip_payer.clone()))
+        .bind(column(bundles, |b| b.tip_lamports as i64))
+        .bind(column(bundles, |b| b.signatures.len() as i16))
+        .bind(column(bundles, |b| Json(b.signatures.clone())))
+        .bind(column(bundles, |b| b.first_index as i64))
+        .bind(column(bundles, |b| b.last_index as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} bundles: {e}", bundles.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        self.publish_all(bundles.iter().map(|b| (format!("bundle:{}", b.tip_payer), b)));
+        true
+    }
+
+    pub(super) async fn write_mev_events(&mut self, db: &mut PgConnection, events: &[MevEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO mev_events (slot, block_time, kind, searcher, signatures, victim_signatures,
+                                    pools, profit_mint, profit_amount)
+            SELECT slot, to_timestamp(block_time), kind, searcher,
+                   ARRAY(SELECT jsonb_array_elements_text(signatures)),
+                   ARRAY(SELECT jsonb_array_elements_text(victim_signatures)),
+                   ARRAY(SELECT jsonb_array_elements_text(pools)), profit_mint, profit_amount
+            FROM UNNEST($1::BIGINT[], $2::FLOAT8[], $3::TEXT[], $4::TEXT[], $5::JSONB[], $6::JSONB[],
+                        $7::JSONB[], $8::TEXT[], $9::BIGINT[])
+                AS t(slot, block_time, kind, searcher, signatures, victim_signatures, pools, profit_mint,
+                     profit_amount)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(events, |e| e.slot as i64))
+        .bind(column(events, |e| e.block_time as f64))
+        .bind(column(events, |e| e.kind.as_str()))
+        .bind(column(events, |e| e.searcher.clone()))
+        .bind(column(events, |e| Json(e.signatures.clone())))
+        .bind(column(events, |e| Json(e.victim_signatures.clone())))
+        .bind(column(events, |e| Json(e.pools.clone())))
+        .bind(column(events, |e| e.profit_mint.clone()))
+        .bind(column(events, |e| e.profit_amount))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} MEV events: {e}", events.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        self.publish_all(events.iter().map(|e| (format!("mev:{}", e.kind.as_str()), e)));
+        true
+    }
+}
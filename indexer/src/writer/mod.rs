@@ -1,21 +1,64 @@
 //! DB writer — batches `ParsedEvent`s and persists them to PostgreSQL/TimescaleDB.
 //! Also publishes real-time update summaries to Redis pub/sub.
 
+mod accounts;
+mod cnft;
+mod dead_letter;
+mod mpl_core;
+mod dex;
+mod fees;
+mod lending;
+mod mev;
+mod nft;
+mod shards;
+mod stake;
+mod system;
+mod tokens;
+mod validator;
+
+pub use shards::{WriterMessage, WriterPool};
+
+use crate::config::AccountHistory;
 use crate::metrics::Metrics;
-use crate::parser::{ParsedEvent, TokenTransferEvent, TransactionEvent};
+use crate::parser::{InstructionEvent, ParsedEvent, TokenTransferEvent, TransactionEvent};
 use crate::receiver::{AccountUpdate, BlockUpdate};
 use redis::aio::ConnectionManager;
-use sqlx::PgPool;
-use std::sync::Arc;
+use serde::Serialize;
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool};
+use std::collections::{BTreeSet, HashMap};
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
 use std::time::Instant;
 use tokio::sync::mpsc::Receiver;
-use tracing::{debug, error, info};
+use tracing::{debug, error, info, warn};
+
+/// Attempts per batch before it is dead-lettered; only transient errors are retried.
+const FLUSH_ATTEMPTS: u32 = 5;
+/// How far behind the newest block a held-back transaction may fall before
+/// its slot is treated as skipped and the transaction dropped.
+const MAX_DEFERRED_SLOTS: u64 = 150;
 
 pub struct Writer {
     pool: PgPool,
     redis: ConnectionManager,
     batch_size: usize,
     metrics: Arc<Metrics>,
+    /// Recently committed block slots, for the `transactions.slot` foreign key.
+    /// Shared by all shards of a `WriterPool`.
+    written_blocks: Arc<Mutex<BTreeSet<u64>>>,
+    /// Transactions and their instructions waiting for their block to be written.
+    deferred: Vec<ParsedEvent>,
+    /// Messages published once the current batch commits.
+    outbox: Vec<(String, String)>,
+    /// The last database error and whether it is worth retrying.
+    last_error: Option<String>,
+    last_error_transient: bool,
+    /// Coordinator link when running as a pool shard.
+    shard: Option<shards::ShardLink>,
+    /// Finished slots received since the last progress report.
+    marks: Vec<u64>,
+    account_history: AccountHistory,
 }
 
 impl Writer {
@@ -30,11 +73,31 @@ impl Writer {
             redis,
             batch_size,
             metrics,
+            written_blocks: Arc::new(Mutex::new(BTreeSet::new())),
+            deferred: Vec::new(),
+            outbox: Vec::new(),
+            last_error: None,
+            last_error_transient: false,
+            shard: None,
+            marks: Vec::new(),
+            account_history: AccountHistory::default(),
         }
     }
 
+    pub fn with_account_history(mut self, history: AccountHistory) -> Self {
+        self.account_history = history;
+        self
+    }
+
+    /// Run as one shard of a `WriterPool`.
+    fn into_shard(mut self, link: shards::ShardLink, written_blocks: Arc<Mutex<BTreeSet<u64>>>) -> Self {
+        self.shard = Some(link);
+        self.written_blocks = written_blocks;
+        self
+    }
+
     /// Drain the channel, accumulating events into batches, then flush.
-    pub async fn run(mut self, mut rx: Receiver<ParsedEvent>) {
+    pub async fn run(mut self, mut rx: Receiver<WriterMessage>) {
         let mut batch: Vec<ParsedEvent> = Vec::with_capacity(self.batch_size);
 
         loop {
@@ -44,7 +107,8 @@ impl Writer {
 
             loop {
                 match tokio::time::timeout_at(deadline, rx.recv()).await {
-                    Ok(Some(event)) => {
+                    Ok(Some(WriterMessage::SlotDone(slot))) => self.marks.push(slot),
+                    Ok(Some(WriterMessage::Event(event))) => {
                         batch.push(event);
                         if batch.len() >= self.batch_size {
                             break;
@@ -53,7 +117,7 @@ impl Writer {
                     Ok(None) => {
                         // Channel closed
                         if !batch.is_empty() {
-                            self.flush_batch(&batch).await;
+                            self.flush_with_retry(&batch).await;
                         }
                         info!("Writer channel closed; exiting");
                         return;
@@ -62,189 +126,520 @@ impl Writer {
                 }
             }
 
-            if !batch.is_empty() {
-                self.flush_batch(&batch).await;
+            // Deferred transactions are retried even without new events.
+            if !batch.is_empty() || !self.deferred.is_empty() {
+                self.flush_with_retry(&batch).await;
                 batch.clear();
             }
+            self.report_progress();
         }
     }
 
-    async fn flush_batch(&mut self, batch: &[ParsedEvent]) {
+    /// Tell the coordinator the newest finished slot whose events this shard
+    /// has all committed (or dead-lettered).
+    fn report_progress(&mut self) {
+        let Some(link) = &self.shard else {
+            self.marks.clear();
+            return;
+        };
+        let pending = self.deferred.iter().filter_map(event_slot).min();
+        let (done, waiting): (Vec<u64>, Vec<u64>) =
+            self.marks.iter().partition(|&&m| pending.is_none_or(|p| m < p));
+        if let Some(&slot) = done.iter().max() {
+            let _ = link.progress.send((link.id, slot));
+        }
+        self.marks = waiting;
+    }
+
+    /// Flush a batch, retrying transient failures with backoff. Batches that
+    /// still fail are moved to the dead-letter table rather than dropped.
+    async fn flush_with_retry(&mut self, batch: &[ParsedEvent]) {
+        for attempt in 1..=FLUSH_ATTEMPTS {
+            if self.flush_batch(batch).await {
+                return;
+            }
+            if !self.last_error_transient || attempt == FLUSH_ATTEMPTS {
+                let reason = self.last_error.take().unwrap_or_default();
+                self.dead_letter(batch, &reason, attempt).await;
+                return;
+            }
+            warn!("Batch of {} events rolled back (attempt {attempt}/{FLUSH_ATTEMPTS}); retrying", batch.len());
+            tokio::time::sleep(backoff(attempt)).await;
+        }
+    }
+
+    /// Write a batch inside one Postgres transaction, so it lands entirely or
+    /// not at all. Redis messages are only published after the commit.
+    async fn flush_batch(&mut self, batch: &[ParsedEvent]) -> bool {
         let start = Instant::now();
+        let mut candidates = Vec::new();
         let mut txs = Vec::new();
+        let mut instructions = Vec::new();
         let mut transfers = Vec::new();
+        let mut sol_transfers = Vec::new();
+        let mut system_accounts = Vec::new();
+        let mut stake_events = Vec::new();
+        let mut stake_accounts = Vec::new();
+        let mut stake_pool_events = Vec::new();
+        let mut lending_events = Vec::new();
+        let mut votes = Vec::new();
+        let mut vote_accounts = Vec::new();
+        let mut leader_schedules = Vec::new();
+        let mut bundles = Vec::new();
+        let mut mev_events = Vec::new();
         let mut accounts = Vec::new();
         let mut blocks = Vec::new();
+        let mut pools = Vec::new();
+        let mut swaps = Vec::new();
+        let mut nft_mints = Vec::new();
+        let mut nft_transfers = Vec::new();
+        let mut nft_metadata = Vec::new();
+        let mut nft_sales = Vec::new();
+        let mut cnfts = Vec::new();
+        let mut core_assets = Vec::new();
+        let mut token_accounts = Vec::new();
+        let mut mints = Vec::new();
+        let mut supply_changes = Vec::new();
+        let mut anchor_accounts = Vec::new();
 
         for event in batch {
             match event {
-                ParsedEvent::Transaction(e) => txs.push(e.clone()),
+                ParsedEvent::Transaction(_) | ParsedEvent::Instruction(_) => candidates.push(event.clone()),
                 ParsedEvent::TokenTransfer(e) => transfers.push(e.clone()),
+                ParsedEvent::SolTransfer(e) => sol_transfers.push(e.clone()),
+                ParsedEvent::SystemAccount(e) => system_accounts.push(e.clone()),
+                ParsedEvent::Stake(e) => stake_events.push(e.clone()),
+                ParsedEvent::StakeAccount(e) => stake_accounts.push(e.clone()),
+                ParsedEvent::StakePool(e) => stake_pool_events.push(e.clone()),
+                ParsedEvent::Lending(e) => lending_events.push(e.clone()),
+                ParsedEvent::Vote(e) => votes.push(e.clone()),
+                ParsedEvent::VoteAccount(e) => vote_accounts.push(e.clone()),
+                ParsedEvent::LeaderSchedule(e) => leader_schedules.push(e.clone()),
+                ParsedEvent::Bundle(e) => bundles.push(e.clone()),
+                ParsedEvent::Mev(e) => mev_events.push(e.clone()),
                 ParsedEvent::AccountUpdate(e) => accounts.push(e.clone()),
                 ParsedEvent::Block(e) => blocks.push(e.clone()),
-                _ => {} // Swaps, NFTs — would have their own tables
+                ParsedEvent::Pool(e) => pools.push(e.clone()),
+                ParsedEvent::TokenAccount(e) => token_accounts.push(e.clone()),
+                ParsedEvent::Mint(e) => mints.push(e.clone()),
+                ParsedEvent::TokenSupply(e) => supply_changes.push(e.clone()),
+                ParsedEvent::AnchorAccount(e) => anchor_accounts.push(e.clone()),
+                ParsedEvent::Swap(e) => swaps.push(e.clone()),
+                ParsedEvent::NftMint(e) => nft_mints.push(e.clone()),
+                ParsedEvent::NftTransfer(e) => nft_transfers.push(e.clone()),
+                ParsedEvent::NftMetadata(e) => nft_metadata.push(e.clone()),
+                ParsedEvent::NftSale(e) => nft_sales.push(e.clone()),
+                ParsedEvent::Cnft(e) => cnfts.push(e.clone()),
+                ParsedEvent::CoreAsset(e) => core_assets.push(e.clone()),
+            }
+        }
+
+        // Transactions usually stream in before their slot's block, so they (and
+        // their instructions) are held back until the block row exists.
+        let block_slots: Vec<u64> = blocks.iter().map(|b| b.slot).collect();
+        let written_blocks = self.written_blocks.lock().unwrap().clone();
+        let newest_block = block_slots.iter().chain(written_blocks.iter()).max().copied();
+        let mut waiting = Vec::new();
+        let mut expired = Vec::new();
+        for event in self.deferred.iter().cloned().chain(candidates) {
+            let Some(slot) = event_slot(&event) else { continue };
+            if block_slots.contains(&slot) || written_blocks.contains(&slot) {
+                match event {
+                    ParsedEvent::Transaction(e) => txs.push(e),
+                    ParsedEvent::Instruction(e) => instructions.push(e),
+                    _ => {}
+                }
+            } else if newest_block.is_some_and(|n| slot + MAX_DEFERRED_SLOTS < n) {
+                expired.push(event);
+            } else {
+                waiting.push(event);
             }
         }
 
+        if batch.is_empty() && txs.is_empty() && instructions.is_empty() {
+            self.deferred = waiting;
+            self.dead_letter_expired(&expired).await;
+            return true;
+        }
+
+        self.last_error = None;
+        let mut dbtx = match self.pool.begin().await {
+            Ok(dbtx) => dbtx,
+            Err(e) => {
+                error!("Failed to begin batch transaction: {e}");
+                self.db_error(&e);
+                return false;
+            }
+        };
+        let db = &mut *dbtx;
+
+        // Parents first: transactions reference blocks.
         let mut ok = true;
+        if !blocks.is_empty() {
+            ok = ok && self.write_blocks(db, &blocks).await;
+            ok = ok && self.write_block_rewards(db, &blocks).await;
+        }
         if !txs.is_empty() {
-            ok &= self.write_transactions(&txs).await;
+            ok = ok && self.write_transactions(db, &txs).await;
+            ok = ok && self.write_priority_fees(db, &txs).await;
+        }
+        if !instructions.is_empty() {
+            ok = ok && self.write_instructions(db, &instructions).await;
         }
         if !transfers.is_empty() {
-            ok &= self.write_token_transfers(&transfers).await;
+            ok = ok && self.write_token_transfers(db, &transfers).await;
+        }
+        if !sol_transfers.is_empty() {
+            ok = ok && self.write_sol_transfers(db, &sol_transfers).await;
+        }
+        if !system_accounts.is_empty() {
+            ok = ok && self.write_system_accounts(db, &system_accounts).await;
+        }
+        if !stake_events.is_empty() {
+            ok = ok && self.write_stake_events(db, &stake_events).await;
+        }
+        if !stake_accounts.is_empty() {
+            ok = ok && self.write_stake_accounts(db, &stake_accounts).await;
+        }
+        if !stake_pool_events.is_empty() {
+            ok = ok && self.write_stake_pool_events(db, &stake_pool_events).await;
+        }
+        if !lending_events.is_empty() {
+            ok = ok && self.write_lending_events(db, &lending_events).await;
         }
         if !accounts.is_empty() {
-            ok &= self.write_account_states(&accounts).await;
+            ok = ok && self.write_account_states(db, &accounts).await;
         }
-        if !blocks.is_empty() {
-            ok &= self.write_blocks(&blocks).await;
+        if !token_accounts.is_empty() {
+            ok = ok && self.write_token_balances(db, &token_accounts).await;
+        }
+        let closed: Vec<AccountUpdate> = accounts.iter().filter(|a| a.lamports == 0).cloned().collect();
+        if !closed.is_empty() {
+            ok = ok && self.remove_closed_token_accounts(db, &closed).await;
+        }
+        if !mints.is_empty() {
+            ok = ok && self.write_mints(db, &mints).await;
+        }
+        if !supply_changes.is_empty() {
+            ok = ok && self.write_supply_changes(db, &supply_changes).await;
+        }
+        if !anchor_accounts.is_empty() {
+            ok = ok && self.write_anchor_accounts(db, &anchor_accounts).await;
+        }
+        if !bundles.is_empty() {
+            ok = ok && self.write_bundles(db, &bundles).await;
+        }
+        if !mev_events.is_empty() {
+            ok = ok && self.write_mev_events(db, &mev_events).await;
+        }
+        if !votes.is_empty() {
+            ok = ok && self.write_vote_events(db, &votes).await;
+        }
+        if !vote_accounts.is_empty() {
+            ok = ok && self.write_vote_accounts(db, &vote_accounts).await;
+        }
+        if !leader_schedules.is_empty() {
+            ok = ok && self.write_leader_schedules(db, &leader_schedules).await;
+        }
+        if !pools.is_empty() {
+            ok = ok && self.write_pools(db, &pools).await;
+        }
+        if !swaps.is_empty() {
+            ok = ok && self.write_swaps(db, &swaps).await;
+        }
+        if !nft_mints.is_empty() {
+            ok = ok && self.write_nft_mints(db, &nft_mints).await;
+        }
+        if !nft_transfers.is_empty() {
+            ok = ok && self.write_nft_transfers(db, &nft_transfers).await;
+        }
+        if !nft_metadata.is_empty() {
+            ok = ok && self.write_nft_metadata(db, &nft_metadata).await;
+        }
+        if !nft_sales.is_empty() {
+            ok = ok && self.write_nft_sales(db, &nft_sales).await;
+        }
+        if !cnfts.is_empty() {
+            ok = ok && self.write_cnft_events(db, &cnfts).await;
         }
+        if !core_assets.is_empty() {
+            ok = ok && self.write_core_events(db, &core_assets).await;
+        }
+
+        if !ok {
+            // Dropping the transaction rolls it back.
+            self.outbox.clear();
+            return false;
+        }
+        if let Err(e) = dbtx.commit().await {
+            error!("Failed to commit batch of {} events: {e}", batch.len());
+            self.db_error(&e);
+            self.outbox.clear();
+            return false;
+        }
+
+        self.deferred = waiting;
+        {
+            let mut written = self.written_blocks.lock().unwrap();
+            written.extend(block_slots);
+            if let Some(newest) = newest_block {
+                *written = written.split_off(&newest.saturating_sub(MAX_DEFERRED_SLOTS));
+            }
+        }
+        self.dead_letter_expired(&expired).await;
+        self.send_outbox().await;
 
         let elapsed = start.elapsed();
         self.metrics
             .db_write_latency_ms
             .record(elapsed.as_secs_f64() * 1000.0);
-        if ok {
-            debug!("Flushed {} events in {:.1}ms", batch.len(), elapsed.as_secs_f64() * 1000.0);
-        }
-    }
-
-    async fn write_transactions(&mut self, txs: &[TransactionEvent]) -> bool {
-        for tx in txs {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO transactions (signature, slot, block_time, success, fee, compute_units, accounts, log_messages)
-                VALUES ($1, $2, to_timestamp($3), $4, $5, $6, $7, $8)
-                ON CONFLICT (signature) DO NOTHING
-                "#,
-            )
-            .bind(&tx.signature)
-            .bind(tx.slot as i64)
-            .bind(tx.block_time as f64)
-            .bind(tx.success)
-            .bind(tx.fee as i64)
-            .bind(tx.compute_units as i64)
-            .bind(&tx.accounts)
-            .bind(&tx.log_messages)
-            .execute(&self.pool)
-            .await;
+        debug!("Flushed {} events in {:.1}ms", batch.len(), elapsed.as_secs_f64() * 1000.0);
+        true
+    }
 
-            if let Err(e) = result {
-                error!("Failed to write transaction {}: {e}", tx.signature);
-                self.metrics.db_errors.increment(1);
-                return false;
-            }
+    /// Transaction events whose block never arrived are dead-lettered, so
+    /// `dead-letter replay` can re-drive them once the block is written.
+    async fn dead_letter_expired(&mut self, expired: &[ParsedEvent]) {
+        if expired.is_empty() {
+            return;
+        }
+        let reason = format!("no block within {MAX_DEFERRED_SLOTS} slots");
+        self.store_dead_letters(expired, &reason, 0).await;
+    }
+
+    async fn write_transactions(&mut self, db: &mut PgConnection, txs: &[TransactionEvent]) -> bool {
+        // Per-row arrays travel as JSONB since UNNEST flattens nested arrays.
+        let accounts: Vec<Json<&[String]>> = txs.iter().map(|t| Json(t.accounts.as_slice())).collect();
+        let logs: Vec<Json<&[String]>> = txs.iter().map(|t| Json(t.log_messages.as_slice())).collect();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO transactions (signature, slot, block_time, success, fee, compute_units, accounts, log_messages,
+                                      compute_unit_limit, compute_unit_price, priority_fee, base_fee,
+                                      tx_index, jito_tip)
+            SELECT signature, slot, to_timestamp(block_time), success, fee, compute_units,
+                   ARRAY(SELECT jsonb_array_elements_text(accounts)),
+                   ARRAY(SELECT jsonb_array_elements_text(log_messages)),
+                   compute_unit_limit, compute_unit_price, priority_fee, base_fee, tx_index, jito_tip
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::BOOL[], $5::BIGINT[], $6::BIGINT[],
+                        $7::JSONB[], $8::JSONB[], $9::BIGINT[], $10::BIGINT[], $11::BIGINT[], $12::BIGINT[],
+                        $13::BIGINT[], $14::BIGINT[])
+                AS t(signature, slot, block_time, success, fee, compute_units, accounts, log_messages,
+                     compute_unit_limit, compute_unit_price, priority_fee, base_fee, tx_index, jito_tip)
+            ON CONFLICT (signature) DO NOTHING
+            "#,
+        )
+        .bind(column(txs, |t| t.signature.clone()))
+        .bind(column(txs, |t| t.slot as i64))
+        .bind(column(txs, |t| t.block_time as f64))
+        .bind(column(txs, |t| t.success))
+        .bind(column(txs, |t| t.fee as i64))
+        .bind(column(txs, |t| t.compute_units as i64))
+        .bind(accounts)
+        .bind(logs)
+        .bind(column(txs, |t| t.compute_unit_limit as i64))
+        .bind(column(txs, |t| t.compute_unit_price as i64))
+        .bind(column(txs, |t| t.priority_fee as i64))
+        .bind(column(txs, |t| t.base_fee as i64))
+        .bind(column(txs, |t| t.index as i64))
+        .bind(column(txs, |t| t.jito_tip.map(|tip| tip as i64)))
+        .execute(&mut *db)
+        .await;
 
-            // Publish to Redis
-            let channel = format!("tx:account:{}", tx.accounts.first().cloned().unwrap_or_default());
-            let payload = serde_json::to_string(tx).unwrap_or_default();
-            let _ = redis::cmd("PUBLISH")
-                .arg(&channel)
-                .arg(&payload)
-                .query_async::<()>(&mut self.redis)
-                .await;
+        if let Err(e) = result {
+            error!("Failed to write {} transactions: {e}", txs.len());
+            self.db_error(&e);
+            return false;
         }
+
+        self.publish_all(txs.iter().map(|tx| {
+            (format!("tx:account:{}", tx.accounts.first().cloned().unwrap_or_default()), tx)
+        }));
         true
     }
 
-    async fn write_token_transfers(&mut self, transfers: &[TokenTransferEvent]) -> bool {
-        for t in transfers {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO token_transfers (signature, block_time, mint, source, destination, amount, decimals)
-                VALUES ($1, to_timestamp($2), $3, $4, $5, $6, $7)
-                ON CONFLICT DO NOTHING
-                "#,
-            )
-            .bind(&t.signature)
-            .bind(t.block_time as f64)
-            .bind(&t.mint)
-            .bind(&t.source)
-            .bind(&t.destination)
-            .bind(t.amount as i64)
-            .bind(t.decimals as i16)
-            .execute(&self.pool)
-            .await;
-
-            if let Err(e) = result {
-                error!("Failed to write token transfer: {e}");
-                self.metrics.db_errors.increment(1);
-                return false;
-            }
+    async fn write_instructions(&mut self, db: &mut PgConnection, instructions: &[InstructionEvent]) -> bool {
+        let accounts: Vec<Json<&[String]>> = instructions.iter().map(|i| Json(i.accounts.as_slice())).collect();
+        let parsed: Vec<Option<Json<&serde_json::Value>>> =
+            instructions.iter().map(|i| i.parsed.as_ref().map(Json)).collect();
+        let result = sqlx::query(
+            r#"
+            INSERT INTO instructions (signature, slot, block_time, program_id, instruction_index, inner_index,
+                                      data, accounts, parsed)
+            SELECT signature, slot, to_timestamp(block_time), program_id, instruction_index, inner_index,
+                   data, ARRAY(SELECT jsonb_array_elements_text(accounts)), parsed
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::TEXT[], $5::INT[], $6::INT[],
+                        $7::BYTEA[], $8::JSONB[], $9::JSONB[])
+                AS t(signature, slot, block_time, program_id, instruction_index, inner_index,
+                     data, accounts, parsed)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(instructions, |i| i.signature.clone()))
+        .bind(column(instructions, |i| i.slot as i64))
+        .bind(column(instructions, |i| i.block_time as f64))
+        .bind(column(instructions, |i| i.program_id.clone()))
+        .bind(column(instructions, |i| i.instruction_index as i32))
+        .bind(column(instructions, |i| i.inner_index.map(|x| x as i32)))
+        .bind(column(instructions, |i| i.data.clone()))
+        .bind(accounts)
+        .bind(parsed)
+        .execute(&mut *db)
+        .await;
 
-            let channel = format!("token_transfer:{}", t.mint);
-            let payload = serde_json::to_string(t).unwrap_or_default();
-            let _ = redis::cmd("PUBLISH")
-                .arg(&channel)
-                .arg(&payload)
-                .query_async::<()>(&mut self.redis)
-                .await;
+        if let Err(e) = result {
+            error!("Failed to write {} instructions: {e}", instructions.len());
+            self.db_error(&e);
+            return false;
         }
         true
     }
 
-    async fn write_account_states(&mut self, accounts: &[AccountUpdate]) -> bool {
-        for a in accounts {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO account_states (pubkey, slot, owner, lamports, executable, rent_epoch, data)
-                VALUES ($1, $2, $3, $4, $5, $6, $7)
-                ON CONFLICT (pubkey, slot) DO UPDATE
-                SET owner = EXCLUDED.owner, lamports = EXCLUDED.lamports,
-                    executable = EXCLUDED.executable, rent_epoch = EXCLUDED.rent_epoch,
-                    data = EXCLUDED.data
-                "#,
-            )
-            .bind(&a.pubkey)
-            .bind(a.slot as i64)
-            .bind(&a.owner)
-            .bind(a.lamports as i64)
-            .bind(a.executable)
-            .bind(a.rent_epoch as i64)
-            .bind(&a.data)
-            .execute(&self.pool)
+    async fn write_token_transfers(&mut self, db: &mut PgConnection, transfers: &[TokenTransferEvent]) -> bool {
+        // A plain `Transfer` does not carry decimals and the parser only knows
+        // them if the source is in the token balances; the mint registry wins.
+        let mut mints = column(transfers, |t| t.mint.clone());
+        mints.sort_unstable();
+        mints.dedup();
+        let known = sqlx::query_as::<_, (String, i16)>("SELECT address, decimals FROM mints WHERE address = ANY($1)")
+            .bind(&mints)
+            .fetch_all(&mut *db)
             .await;
-
-            if let Err(e) = result {
-                error!("Failed to write account state {}: {e}", a.pubkey);
-                self.metrics.db_errors.increment(1);
+        let decimals: HashMap<String, i16> = match known {
+            Ok(known) => known.into_iter().collect(),
+            Err(e) => {
+                error!("Failed to look up decimals of {} mints: {e}", mints.len());
+                self.db_error(&e);
                 return false;
             }
+        };
+        let transfers: Vec<TokenTransferEvent> = transfers
+            .iter()
+            .cloned()
+            .map(|mut t| {
+                if let Some(&d) = decimals.get(&t.mint) {
+                    t.decimals = d as u8;
+                }
+                t
+            })
+            .collect();
+        let transfers = transfers.as_slice();
 
-            let channel = format!("account:{}", a.pubkey);
-            let payload = serde_json::to_string(a).unwrap_or_default();
-            let _ = redis::cmd("PUBLISH")
-                .arg(&channel)
-                .arg(&payload)
-                .query_async::<()>(&mut self.redis)
-                .await;
+        let result = sqlx::query(
+            r#"
+            INSERT INTO token_transfers (signature, block_time, mint, source, destination, amount, decimals)
+            SELECT signature, to_timestamp(block_time), mint, source, destination, amount, decimals
+            FROM UNNEST($1::TEXT[], $2::FLOAT8[], $3::TEXT[], $4::TEXT[], $5::TEXT[], $6::BIGINT[], $7::SMALLINT[])
+                AS t(signature, block_time, mint, source, destination, amount, decimals)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(transfers, |t| t.signature.clone()))
+        .bind(column(transfers, |t| t.block_time as f64))
+        .bind(column(transfers, |t| t.mint.clone()))
+        .bind(column(transfers, |t| t.source.clone()))
+        .bind(column(transfers, |t| t.destination.clone()))
+        .bind(column(transfers, |t| t.amount as i64))
+        .bind(column(transfers, |t| t.decimals as i16))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} token transfers: {e}", transfers.len());
+            self.db_error(&e);
+            return false;
         }
+
+        self.publish_all(transfers.iter().map(|t| (format!("token_transfer:{}", t.mint), t)));
         true
     }
 
-    async fn write_blocks(&mut self, blocks: &[BlockUpdate]) -> bool {
-        for b in blocks {
-            let result = sqlx::query(
-                r#"
-                INSERT INTO blocks (slot, parent_slot, block_time, block_height, leader, indexed_at)
-                VALUES ($1, $2, to_timestamp($3), $4, $5, NOW())
-                ON CONFLICT (slot) DO NOTHING
-                "#,
-            )
-            .bind(b.slot as i64)
-            .bind(b.parent_slot as i64)
-            .bind(b.block_time as f64)
-            .bind(b.block_height as i64)
-            .bind(&b.leader)
-            .execute(&self.pool)
-            .await;
+    async fn write_blocks(&mut self, db: &mut PgConnection, blocks: &[BlockUpdate]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO blocks (slot, parent_slot, block_time, block_height, leader, rewards, indexed_at)
+            SELECT slot, parent_slot, to_timestamp(block_time), block_height, leader, rewards, NOW()
+            FROM UNNEST($1::BIGINT[], $2::BIGINT[], $3::FLOAT8[], $4::BIGINT[], $5::TEXT[], $6::JSONB[])
+                AS t(slot, parent_slot, block_time, block_height, leader, rewards)
+            ON CONFLICT (slot) DO NOTHING
+            "#,
+        )
+        .bind(column(blocks, |b| b.slot as i64))
+        .bind(column(blocks, |b| b.parent_slot as i64))
+        .bind(column(blocks, |b| b.block_time as f64))
+        .bind(column(blocks, |b| b.block_height as i64))
+        .bind(column(blocks, |b| b.leader.clone()))
+        .bind(column(blocks, |b| Json(b.rewards.clone())))
+        .execute(&mut *db)
+        .await;
 
-            if let Err(e) = result {
-                error!("Failed to write block {}: {e}", b.slot);
-                self.metrics.db_errors.increment(1);
-                return false;
-            }
+        if let Err(e) = result {
+            error!("Failed to write {} blocks: {e}", blocks.len());
+            self.db_error(&e);
+            return false;
         }
         true
     }
+
+    /// Record a failed statement for the retry policy.
+    fn db_error(&mut self, e: &sqlx::Error) {
+        self.metrics.db_errors.increment(1);
+        self.last_error = Some(e.to_string());
+        self.last_error_transient = dead_letter::is_transient(e);
+    }
+
+    /// Queue an event for a Redis pub/sub channel; sent after the batch commits.
+    fn publish<T: Serialize>(&mut self, channel: &str, event: &T) {
+        let payload = serde_json::to_string(event).unwrap_or_default();
+        self.outbox.push((channel.to_string(), payload));
+    }
+
+    fn publish_all<'a, T: Serialize + 'a>(&mut self, messages: impl IntoIterator<Item = (String, &'a T)>) {
+        for (channel, event) in messages {
+            self.publish(&channel, event);
+        }
+    }
+
+    /// Best-effort publish of the queued messages in a single pipelined round trip.
+    async fn send_outbox(&mut self) {
+        if self.outbox.is_empty() {
+            return;
+        }
+        let mut pipe = redis::pipe();
+        for (channel, payload) in self.outbox.drain(..) {
+            pipe.cmd("PUBLISH").arg(channel).arg(payload).ignore();
+        }
+        let _ = pipe.query_async::<()>(&mut self.redis).await;
+    }
+}
+
+/// Slot of a transaction or instruction event, the ones held back for their block.
+fn event_slot(event: &ParsedEvent) -> Option<u64> {
+    match event {
+        ParsedEvent::Transaction(e) => Some(e.slot),
+        ParsedEvent::Instruction(e) => Some(e.slot),
+        _ => None,
+    }
+}
+
+/// Exponential backoff starting at 250ms, capped at 8s.
+fn backoff(attempt: u32) -> std::time::Duration {
+    std::time::Duration::from_millis(250 << attempt.saturating_sub(1).min(5))
+}
+
+/// One column of a batch, for binding as a Postgres array to `UNNEST`.
+fn column<R, T>(rows: &[R], f: impl Fn(&R) -> T) -> Vec<T> {
+    rows.iter().map(f).collect()
+}
+
+/// The newest row per key, by slot; later rows win ties. One upsert
+/// statement cannot touch the same row twice.
+fn newest<'a, R, K: Hash + Eq>(rows: &'a [R], key: impl Fn(&'a R) -> (K, u64)) -> Vec<&'a R> {
+    let mut latest: HashMap<K, &R> = HashMap::new();
+    for r in rows {
+        let (k, slot) = key(r);
+        if latest.get(&k).is_none_or(|prev| key(prev).1 <= slot) {
+            latest.insert(k, r);
+        }
+    }
+    latest.into_values().collect()
 }
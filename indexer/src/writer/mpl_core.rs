@@ -0,0 +1,169 @@
+//! Metaplex Core asset, collection and plugin writes. Events are applied one
+//! at a time, in order: an asset created, transferred and updated within one
+//! batch needs each step to see the previous one.
+
+use super::Writer;
+use crate::parser::{CoreAssetEvent, CorePlugin, CoreUpdate};
+use sqlx::types::Json;
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_core_events(&mut self, db: &mut PgConnection, events: &[CoreAssetEvent]) -> bool {
+        for c in events {
+            if let Err(e) = self.write_core_event(db, c).await {
+                error!("Failed to write Core asset {}: {e}", c.address);
+                self.db_error(&e);
+                return false;
+            }
+        }
+        true
+    }
+
+    async fn write_core_event(&mut self, db: &mut PgConnection, c: &CoreAssetEvent) -> Result<(), sqlx::Error> {
+        let slot = c.slot as i64;
+        match &c.update {
+            CoreUpdate::AssetCreated {
+                owner,
+                update_authority,
+                collection,
+                name,
+                uri,
+                plugins,
+            } => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO core_assets (address, owner, update_authority, collection, name, uri,
+                                             created_slot, updated_slot)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $7)
+                    ON CONFLICT (address) DO NOTHING
+                    "#,
+                )
+                .bind(&c.address)
+                .bind(owner)
+                .bind(update_authority)
+                .bind(collection)
+                .bind(name)
+                .bind(uri)
+                .bind(slot)
+                .execute(&mut *db)
+                .await?;
+                self.write_core_plugins(db, &c.address, plugins, slot).await?;
+            }
+            CoreUpdate::CollectionCreated {
+                update_authority,
+                name,
+                uri,
+                plugins,
+            } => {
+                sqlx::query(
+                    r#"
+                    INSERT INTO core_collections (address, update_authority, name, uri, created_slot, updated_slot)
+                    VALUES ($1, $2, $3, $4, $5, $5)
+                    ON CONFLICT (address) DO NOTHING
+                    "#,
+                )
+                .bind(&c.address)
+                .bind(update_authority)
+                .bind(name)
+                .bind(uri)
+                .bind(slot)
+                .execute(&mut *db)
+                .await?;
+                self.write_core_plugins(db, &c.address, plugins, slot).await?;
+            }
+            CoreUpdate::Transferred { new_owner } => {
+                sqlx::query(
+                    r#"
+                    UPDATE core_assets SET owner = $2, updated_slot = $3
+                    WHERE address = $1 AND updated_slot <= $3
+                    "#,
+                )
+                .bind(&c.address)
+                .bind(new_owner)
+                .bind(slot)
+                .execute(&mut *db)
+                .await?;
+            }
+            CoreUpdate::Updated {
+                name,
+                uri,
+                update_authority,
+            } => {
+                // The same instruction shape updates assets and collections.
+                for table in ["core_assets", "core_collections"] {
+                    sqlx::query(&format!(
+                        r#"
+                        UPDATE {table}
+                        SET name = COALESCE($2, name), uri = COALESCE($3, uri),
+                            update_authority = COALESCE($4, update_authority), updated_slot = $5
+                        WHERE address = $1 AND updated_slot <= $5
+                        "#
+                    ))
+                    .bind(&c.address)
+                    .bind(name)
+                    .bind(uri)
+                    .bind(update_authority)
+                    .bind(slot)
+                    .execute(&mut *db)
+                    .await?;
+                }
+            }
+            CoreUpdate::Burned => {
+                for table in ["core_assets", "core_collections"] {
+                    sqlx::query(&format!(
+                        "UPDATE {table} SET burned = TRUE, updated_slot = GREATEST(updated_slot, $2) WHERE address = $1"
+                    ))
+                    .bind(&c.address)
+                    .bind(slot)
+                    .execute(&mut *db)
+                    .await?;
+                }
+            }
+            CoreUpdate::PluginSet(plugin) => {
+                self.write_core_plugins(db, &c.address, std::slice::from_ref(plugin), slot)
+                    .await?;
+            }
+            CoreUpdate::PluginRemoved { plugin_type } => {
+                sqlx::query(
+                    "DELETE FROM core_plugins WHERE address = $1 AND plugin_type = $2 AND updated_slot <= $3",
+                )
+                .bind(&c.address)
+                .bind(plugin_type)
+                .bind(slot)
+                .execute(&mut *db)
+                .await?;
+            }
+        }
+        Ok(())
+    }
+
+    async fn write_core_plugins(
+        &mut self,
+        db: &mut PgConnection,
+        address: &str,
+        plugins: &[CorePlugin],
+        slot: i64,
+    ) -> Result<(), sqlx::Error> {
+        for p in plugins {
+            sqlx::query(
+                r#"
+                INSERT INTO core_plugins (address, plugin_type, authority, data, updated_slot)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (address, plugin_type) DO UPDATE
+                SET authority = COALESCE(EXCLUDED.authority, core_plugins.authority),
+                    data = EXCLUDED.data, updated_slot = EXCLUDED.updated_slot
+                WHERE core_plugins.updated_slot <= EXCLUDED.updated_slot
+                "#,
+            )
+            .bind(address)
+            .bind(&p.plugin_type)
+            .bind(&p.authority)
+            .bind(Json(&p.data))
+            .bind(slot)
+            .execute(&mut *db)
+            .await?;
+        }
+        Ok(())
+    }
+}
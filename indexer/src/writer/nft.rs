@@ -0,0 +1,305 @@
+//! NFT mint, transfer, sale and metadata writes.
+
+use super::{column, Writer};
+use crate::parser::{
+    MetadataUpdate, NftMetadataEvent, NftMintEvent, NftSaleEvent, NftTransferEvent,
+};
+use sqlx::types::Json;
+use sqlx::PgConnection;
+use std::collections::HashMap;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_nft_mints(&mut self, db: &mut PgConnection, mints: &[NftMintEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO nft_mints (signature, slot, block_time, mint, owner, metadata_uri, collection)
+            SELECT signature, slot, to_timestamp(block_time), mint, owner, metadata_uri, collection
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::TEXT[], $5::TEXT[], $6::TEXT[],
+                        $7::TEXT[])
+                AS t(signature, slot, block_time, mint, owner, metadata_uri, collection)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(mints, |m| m.signature.clone()))
+        .bind(column(mints, |m| m.slot as i64))
+        .bind(column(mints, |m| m.block_time as f64))
+        .bind(column(mints, |m| m.mint.clone()))
+        .bind(column(mints, |m| m.owner.clone()))
+        .bind(column(mints, |m| m.metadata_uri.clone()))
+        .bind(column(mints, |m| m.collection.clone()))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} NFT mints: {e}", mints.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        self.publish_all(mints.iter().map(|m| (format!("nft:{}", m.collection.as_deref().unwrap_or(&m.mint)), m)));
+        true
+    }
+
+    pub(super) async fn write_nft_transfers(&mut self, db: &mut PgConnection, transfers: &[NftTransferEvent]) -> bool {
+        // Core transfers name no previous owner: it is the asset's indexed
+        // owner, or the recipient of an earlier transfer in this batch.
+        let core: Vec<String> = transfers.iter().filter(|t| t.from.is_none()).map(|t| t.mint.clone()).collect();
+        let mut owners: HashMap<String, String> = HashMap::new();
+        if !core.is_empty() {
+            let result = sqlx::query_as::<_, (String, String)>(
+                "SELECT address, owner FROM core_assets WHERE address = ANY($1)",
+            )
+            .bind(core)
+            .fetch_all(&mut *db)
+            .await;
+            match result {
+                Ok(rows) => owners.extend(rows),
+                Err(e) => {
+                    error!("Failed to resolve owners of Core assets: {e}");
+                    self.db_error(&e);
+                    return false;
+                }
+            }
+        }
+        let transfers: Vec<NftTransferEvent> = transfers
+            .iter()
+            .map(|t| {
+                let mut t = t.clone();
+                if t.from.is_none() {
+                    t.from = owners.get(&t.mint).cloned();
+                }
+                owners.insert(t.mint.clone(), t.to.clone());
+                t
+            })
+            .collect();
+        let transfers = transfers.as_slice();
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO nft_transfers (signature, slot, block_time, mint, source, destination,
+                                       marketplace, marketplace_ix)
+            SELECT signature, slot, to_timestamp(block_time), mint, source, destination,
+                   marketplace, marketplace_ix
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::TEXT[], $5::TEXT[], $6::TEXT[],
+                        $7::TEXT[], $8::INT[])
+                AS t(signature, slot, block_time, mint, source, destination, marketplace, marketplace_ix)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(transfers, |t| t.signature.clone()))
+        .bind(column(transfers, |t| t.slot as i64))
+        .bind(column(transfers, |t| t.block_time as f64))
+        .bind(column(transfers, |t| t.mint.clone()))
+        .bind(column(transfers, |t| t.from.clone()))
+        .bind(column(transfers, |t| t.to.clone()))
+        .bind(column(transfers, |t| t.marketplace.clone()))
+        .bind(column(transfers, |t| t.marketplace_ix.map(|i| i as i32)))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} NFT transfers: {e}", transfers.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        // Published on the collection channel, like mints, so it is resolved
+        // from whatever metadata is already indexed.
+        let collections = match collections(db, column(transfers, |t| t.mint.clone())).await {
+            Ok(collections) => collections,
+            Err(e) => {
+                error!("Failed to resolve collections of {} NFT transfers: {e}", transfers.len());
+                self.db_error(&e);
+                return false;
+            }
+        };
+        self.publish_all(transfers.iter().map(|t| {
+            let collection = collections.get(&t.mint).unwrap_or(&t.mint);
+            (format!("nft:{collection}"), t)
+        }));
+        true
+    }
+
+    pub(super) async fn write_nft_sales(&mut self, db: &mut PgConnection, sales: &[NftSaleEvent]) -> bool {
+        for s in sales {
+            // The collection is resolved from whatever metadata is already indexed
+            // so sales can be aggregated per collection without a join.
+            let result = sqlx::query_scalar::<_, Option<String>>(
+                r#"
+                INSERT INTO nft_sales (signature, slot, block_time, marketplace, instruction_index, mint,
+                                       collection, buyer, seller, price, marketplace_fee, royalty)
+                VALUES ($1, $2, to_timestamp($3), $4, $5, $6,
+                        COALESCE((SELECT collection FROM nft_metadata WHERE mint = $6),
+                                 (SELECT collection FROM cnft_leaves WHERE asset_id = $6),
+                                 (SELECT collection FROM core_assets WHERE address = $6)),
+                        $7, $8, $9, $10, $11)
+                ON CONFLICT DO NOTHING
+                RETURNING collection
+                "#,
+            )
+            .bind(&s.signature)
+            .bind(s.slot as i64)
+            .bind(s.block_time as f64)
+            .bind(&s.marketplace)
+            .bind(s.instruction_index as i32)
+            .bind(&s.mint)
+            .bind(&s.buyer)
+            .bind(&s.seller)
+            .bind(s.price as i64)
+            .bind(s.marketplace_fee as i64)
+            .bind(s.royalty as i64)
+            .fetch_optional(&mut *db)
+            .await;
+
+            let collection = match result {
+                Ok(collection) => collection.flatten(),
+                Err(e) => {
+                    error!("Failed to write NFT sale {}: {e}", s.mint);
+                    self.db_error(&e);
+                    return false;
+                }
+            };
+
+            let channel = format!("nft:{}", collection.as_deref().unwrap_or(&s.mint));
+            self.publish(&channel, s);
+        }
+        true
+    }
+
+    pub(super) async fn write_nft_metadata(&mut self, db: &mut PgConnection, updates: &[NftMetadataEvent]) -> bool {
+        for m in updates {
+            let query = match &m.update {
+                MetadataUpdate::Upsert(f) => sqlx::query(
+                    r#"
+                    INSERT INTO nft_metadata (metadata, mint, update_authority, name, symbol, uri,
+                                              seller_fee_basis_points, creators, collection,
+                                              collection_verified, token_standard, is_mutable,
+                                              created_slot, updated_slot)
+                    VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $13)
+                    ON CONFLICT (metadata) DO UPDATE
+                    SET mint                    = COALESCE(EXCLUDED.mint, nft_metadata.mint),
+                        update_authority        = COALESCE(EXCLUDED.update_authority, nft_metadata.update_authority),
+                        name                    = EXCLUDED.name,
+                        symbol                  = EXCLUDED.symbol,
+                        uri                     = EXCLUDED.uri,
+                        seller_fee_basis_points = EXCLUDED.seller_fee_basis_points,
+                        creators                = EXCLUDED.creators,
+                        collection              = EXCLUDED.collection,
+                        collection_verified     = EXCLUDED.collection_verified,
+                        token_standard          = COALESCE(EXCLUDED.token_standard, nft_metadata.token_standard),
+                        is_mutable              = COALESCE(EXCLUDED.is_mutable, nft_metadata.is_mutable),
+                        updated_slot            = EXCLUDED.updated_slot,
+                        updated_at              = NOW()
+                    WHERE nft_metadata.updated_slot <= EXCLUDED.updated_slot
+                    "#,
+                )
+                .bind(&m.metadata)
+                .bind(&f.mint)
+                .bind(&f.update_authority)
+                .bind(&f.name)
+                .bind(&f.symbol)
+                .bind(&f.uri)
+                .bind(f.seller_fee_basis_points as i32)
+                .bind(Json(&f.creators))
+                .bind(&f.collection)
+                .bind(f.collection_verified)
+                .bind(f.token_standard.map(|t| t as i16))
+                .bind(f.is_mutable)
+                .bind(m.slot as i64),
+                MetadataUpdate::Collection { collection, verified } => sqlx::query(
+                    r#"
+                    INSERT INTO nft_metadata (metadata, collection, collection_verified, updated_slot)
+                    VALUES ($1, $2, $3, $4)
+                    ON CONFLICT (metadata) DO UPDATE
+                    SET collection          = EXCLUDED.collection,
+                        collection_verified = EXCLUDED.collection_verified,
+                        updated_slot        = EXCLUDED.updated_slot,
+                        updated_at          = NOW()
+                    WHERE nft_metadata.updated_slot <= EXCLUDED.updated_slot
+                    "#,
+                )
+                .bind(&m.metadata)
+                .bind(collection)
+                .bind(verified)
+                .bind(m.slot as i64),
+                MetadataUpdate::Burned => sqlx::query(
+                    r#"
+                    INSERT INTO nft_metadata (metadata, burned, updated_slot)
+                    VALUES ($1, TRUE, $2)
+                    ON CONFLICT (metadata) DO UPDATE
+                    SET burned = TRUE, updated_slot = GREATEST(nft_metadata.updated_slot, EXCLUDED.updated_slot),
+                        updated_at = NOW()
+                    "#,
+                )
+                .bind(&m.metadata)
+                .bind(m.slot as i64),
+            };
+
+            if let Err(e) = query.execute(&mut *db).await {
+                error!("Failed to write NFT metadata {}: {e}", m.metadata);
+                self.db_error(&e);
+                return false;
+            }
+        }
+
+        // Name and symbol of fungible and non-fungible mints alike, unless the
+        // mint carries its own Token-2022 metadata.
+        // Later updates of a mint replace earlier ones in the batch.
+        let named: HashMap<&str, (&str, &str)> = updates
+            .iter()
+            .filter_map(|m| match &m.update {
+                MetadataUpdate::Upsert(f) => Some((f.mint.as_deref()?, (f.name.as_str(), f.symbol.as_str()))),
+                _ => None,
+            })
+            .collect();
+        let named: Vec<(&str, (&str, &str))> = named.into_iter().collect();
+        if !named.is_empty() {
+            let result = sqlx::query(
+                r#"
+                UPDATE mints m SET name = t.name, symbol = t.symbol, updated_at = NOW()
+                FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[]) AS t(mint, name, symbol)
+                WHERE m.address = t.mint AND NOT COALESCE(m.extensions ? 'TokenMetadata', FALSE)
+                "#,
+            )
+            .bind(column(&named, |(mint, _)| *mint))
+            .bind(column(&named, |(_, (name, _))| *name))
+            .bind(column(&named, |(_, (_, symbol))| *symbol))
+            .execute(&mut *db)
+            .await;
+
+            if let Err(e) = result {
+                error!("Failed to update names of {} mints: {e}", named.len());
+                self.db_error(&e);
+                return false;
+            }
+        }
+
+        let upserts = updates.iter().filter(|m| matches!(m.update, MetadataUpdate::Upsert(_)));
+        self.publish_all(upserts.map(|m| (format!("account_decoded:{}", m.metadata), m)));
+        true
+    }
+}
+
+/// Collection of each of `mints` that has one, from Metaplex metadata,
+/// compressed leaves or Core assets, in that order of precedence.
+async fn collections(db: &mut PgConnection, mints: Vec<String>) -> Result<HashMap<String, String>, sqlx::Error> {
+    let rows = sqlx::query_as::<_, (String, String)>(
+        r#"
+        SELECT mint, collection FROM (
+            SELECT 0 AS source, mint, collection FROM nft_metadata WHERE mint = ANY($1)
+            UNION ALL
+            SELECT 1, asset_id, collection FROM cnft_leaves WHERE asset_id = ANY($1)
+            UNION ALL
+            SELECT 2, address, collection FROM core_assets WHERE address = ANY($1)
+        ) c
+        WHERE collection IS NOT NULL
+        ORDER BY source DESC
+        "#,
+    )
+    .bind(mints)
+    .fetch_all(&mut *db)
+    .await?;
+    // Least preferred first, so later rows overwrite them.
+    Ok(rows.into_iter().collect())
+}
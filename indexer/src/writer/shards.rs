@@ -0,0 +1,200 @@
+//! Sharded writer pool.
+//!
+//! Events are routed to one of N `Writer` workers by a key that keeps writes
+//! which must stay ordered on one worker: the account for account state, the
+//! tree / asset for upserted NFT state, and the signature for everything else.
+//! Every block is followed by a `SlotDone` marker to all shards, and the
+//! coordinator advances the committed-slot watermark once each shard has
+//! committed everything it received before that marker.
+
+use super::Writer;
+use crate::config::AccountHistory;
+use crate::metrics::Metrics;
+use crate::parser::ParsedEvent;
+use redis::aio::ConnectionManager;
+use sqlx::PgPool;
+use std::collections::BTreeSet;
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc::{self, Receiver, Sender, UnboundedReceiver, UnboundedSender};
+use tracing::{error, info, warn};
+
+/// Per-shard channel capacity.
+const SHARD_CHANNEL_CAPACITY: usize = 4_096;
+
+// Nearly every message is an event, so boxing would only add an allocation.
+#[allow(clippy::large_enum_variant)]
+pub enum WriterMessage {
+    Event(ParsedEvent),
+    /// Every event of this slot has been sent.
+    SlotDone(u64),
+}
+
+/// Link from a shard back to the coordinator.
+pub(super) struct ShardLink {
+    pub id: usize,
+    pub progress: UnboundedSender<(usize, u64)>,
+}
+
+pub struct WriterPool {
+    shards: Vec<Sender<WriterMessage>>,
+}
+
+impl WriterPool {
+    /// Spawn `shards` writer workers and the watermark coordinator.
+    pub fn spawn(
+        pool: PgPool,
+        redis: ConnectionManager,
+        batch_size: usize,
+        shards: usize,
+        account_history: AccountHistory,
+        metrics: Arc<Metrics>,
+    ) -> Self {
+        let shards = shards.max(1);
+        // Shards hold transactions back until any shard has committed their block.
+        let written_blocks = Arc::new(Mutex::new(BTreeSet::new()));
+        let (progress_tx, progress_rx) = mpsc::unbounded_channel();
+
+        let senders = (0..shards)
+            .map(|id| {
+                let (tx, rx) = mpsc::channel(SHARD_CHANNEL_CAPACITY);
+                let writer = Writer::new(pool.clone(), redis.clone(), batch_size, Arc::clone(&metrics))
+                    .with_account_history(account_history.clone())
+                    .into_shard(
+                        ShardLink { id, progress: progress_tx.clone() },
+                        Arc::clone(&written_blocks),
+                    );
+                tokio::spawn(writer.run(rx));
+                tx
+            })
+            .collect();
+
+        tokio::spawn(coordinate(pool, shards, progress_rx, metrics));
+        info!("Started {shards} writer shards");
+        Self { shards: senders }
+    }
+
+    /// Route events from the indexer to the shards until the channel closes.
+    pub async fn run(self, mut rx: Receiver<ParsedEvent>) {
+        while let Some(event) = rx.recv().await {
+            let finished = match &event {
+                ParsedEvent::Block(b) => Some(b.slot),
+                _ => None,
+            };
+            let shard = shard_for(&event, self.shards.len());
+            if self.shards[shard].send(WriterMessage::Event(event)).await.is_err() {
+                error!("Writer shard {shard} stopped; shutting down writer pool");
+                return;
+            }
+            if let Some(slot) = finished {
+                for shard in &self.shards {
+                    let _ = shard.send(WriterMessage::SlotDone(slot)).await;
+                }
+            }
+        }
+        info!("Writer pool channel closed; exiting");
+    }
+}
+
+/// Shard an event is written by.
+fn shard_for(event: &ParsedEvent, shards: usize) -> usize {
+    let mut h = DefaultHasher::new();
+    match event {
+        ParsedEvent::AccountUpdate(e) => e.pubkey.hash(&mut h),
+        ParsedEvent::StakeAccount(e) => e.address.hash(&mut h),
+        ParsedEvent::Stake(e) => e.stake_account.hash(&mut h),
+        ParsedEvent::VoteAccount(e) => e.address.hash(&mut h),
+        ParsedEvent::Pool(e) => e.address.hash(&mut h),
+        ParsedEvent::TokenAccount(e) => e.address.hash(&mut h),
+        ParsedEvent::Mint(e) => e.address.hash(&mut h),
+        // With the mint, so a mint's supply changes follow its registration.
+        ParsedEvent::TokenSupply(e) => e.mint.hash(&mut h),
+        ParsedEvent::AnchorAccount(e) => e.address.hash(&mut h),
+        ParsedEvent::NftMetadata(e) => e.metadata.hash(&mut h),
+        ParsedEvent::Cnft(e) => e.tree.hash(&mut h),
+        ParsedEvent::CoreAsset(e) => e.address.hash(&mut h),
+        ParsedEvent::Block(e) => e.slot.hash(&mut h),
+        ParsedEvent::LeaderSchedule(e) => e.epoch.hash(&mut h),
+        ParsedEvent::Bundle(e) => e.tip_signature.hash(&mut h),
+        ParsedEvent::Mev(e) => e.signatures.first().hash(&mut h),
+        ParsedEvent::Transaction(e) => e.signature.hash(&mut h),
+        ParsedEvent::Instruction(e) => e.signature.hash(&mut h),
+        ParsedEvent::TokenTransfer(e) => e.signature.hash(&mut h),
+        ParsedEvent::SolTransfer(e) => e.signature.hash(&mut h),
+        ParsedEvent::SystemAccount(e) => e.signature.hash(&mut h),
+        ParsedEvent::StakePool(e) => e.signature.hash(&mut h),
+        ParsedEvent::Lending(e) => e.signature.hash(&mut h),
+        ParsedEvent::Vote(e) => e.signature.hash(&mut h),
+        ParsedEvent::Swap(e) => e.signature.hash(&mut h),
+        ParsedEvent::NftMint(e) => e.signature.hash(&mut h),
+        ParsedEvent::NftTransfer(e) => e.signature.hash(&mut h),
+        ParsedEvent::NftSale(e) => e.signature.hash(&mut h),
+    }
+    (h.finish() % shards as u64) as usize
+}
+
+/// Track per-shard progress and persist the slot every shard has committed.
+async fn coordinate(
+    pool: PgPool,
+    shards: usize,
+    mut progress: UnboundedReceiver<(usize, u64)>,
+    metrics: Arc<Metrics>,
+) {
+    let mut done = vec![0u64; shards];
+    let mut watermark = 0;
+    while let Some((shard, slot)) = progress.recv().await {
+        done[shard] = done[shard].max(slot);
+        let committed = done.iter().copied().min().unwrap_or(0);
+        if committed <= watermark {
+            continue;
+        }
+        watermark = committed;
+        metrics.committed_slot.set(committed as f64);
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO indexer_state (id, committed_slot, updated_at)
+            VALUES (TRUE, $1, NOW())
+            ON CONFLICT (id) DO UPDATE
+            SET committed_slot = GREATEST(indexer_state.committed_slot, EXCLUDED.committed_slot),
+                updated_at     = NOW()
+            "#,
+        )
+        .bind(committed as i64)
+        .execute(&pool)
+        .await;
+
+        if let Err(e) = result {
+            warn!("Failed to persist committed slot {committed}: {e}");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::receiver::AccountUpdate;
+
+    fn account(pubkey: &str, slot: u64) -> ParsedEvent {
+        ParsedEvent::AccountUpdate(AccountUpdate {
+            pubkey: pubkey.into(),
+            slot,
+            owner: "11111111111111111111111111111111".into(),
+            lamports: 1,
+            executable: false,
+            rent_epoch: 0,
+            data: Vec::new(),
+            write_version: 0,
+        })
+    }
+
+    #[test]
+    fn account_updates_stay_on_one_shard() {
+        let first = shard_for(&account("Acct1111", 1), 8);
+        for slot in 2..50 {
+            assert_eq!(shard_for(&account("Acct1111", slot), 8), first);
+        }
+        let spread: BTreeSet<usize> = (0..64).map(|i| shard_for(&account(&format!("Acct{i}"), 1), 8)).collect();
+        assert!(spread.len() > 1);
+    }
+}
@@ -0,0 +1,272 @@
+//! Stake instruction history, current delegation and liquid staking writes.
+
+use super::{column, newest, Writer};
+use crate::parser::{StakeAccountEvent, StakeAction, StakeEvent, StakePoolEvent};
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_stake_events(&mut self, db: &mut PgConnection, events: &[StakeEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO stake_events (signature, slot, block_time, epoch, instruction_index, inner_index,
+                                      action, stake_account, vote_account, staker, withdrawer,
+                                      related_account, lamports)
+            SELECT signature, slot, to_timestamp(block_time), epoch, instruction_index, inner_index,
+                   action, stake_account, vote_account, staker, withdrawer, related_account, lamports
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::BIGINT[], $5::INT[], $6::INT[],
+                        $7::TEXT[], $8::TEXT[], $9::TEXT[], $10::TEXT[], $11::TEXT[], $12::TEXT[],
+                        $13::BIGINT[])
+                AS t(signature, slot, block_time, epoch, instruction_index, inner_index, action,
+                     stake_account, vote_account, staker, withdrawer, related_account, lamports)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(events, |s| s.signature.clone()))
+        .bind(column(events, |s| s.slot as i64))
+        .bind(column(events, |s| s.block_time as f64))
+        .bind(column(events, |s| s.epoch as i64))
+        .bind(column(events, |s| s.instruction_index as i32))
+        .bind(column(events, |s| s.inner_index.map(|i| i as i32)))
+        .bind(column(events, |s| s.action.as_str()))
+        .bind(column(events, |s| s.stake_account.clone()))
+        .bind(column(events, |s| s.vote_account.clone()))
+        .bind(column(events, |s| s.staker.clone()))
+        .bind(column(events, |s| s.withdrawer.clone()))
+        .bind(column(events, |s| s.related_account.clone()))
+        .bind(column(events, |s| s.lamports.map(|l| l as i64)))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} stake events: {e}", events.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        // Delegation changes are applied one by one, in order: a batch can
+        // initialize, delegate and deactivate the same account, and each
+        // action builds on the state the previous one left.
+        for s in events {
+            // Apply what the instruction says about the delegation; amounts are
+            // left to the stake account updates.
+            let slot = s.slot as i64;
+            let epoch = s.epoch as i64;
+            let query = match s.action {
+                StakeAction::Initialize => sqlx::query(
+                    r#"
+                    INSERT INTO stake_delegations (stake_account, staker, withdrawer, updated_epoch, updated_slot)
+                    VALUES ($1, $4, $5, $3, $2)
+                    ON CONFLICT (stake_account) DO UPDATE
+                    SET staker        = EXCLUDED.staker,
+                        withdrawer    = EXCLUDED.withdrawer,
+                        updated_epoch = EXCLUDED.updated_epoch,
+                        updated_slot  = EXCLUDED.updated_slot,
+                        updated_at    = NOW()
+                    WHERE stake_delegations.updated_slot <= EXCLUDED.updated_slot
+                    "#,
+                )
+                .bind(&s.stake_account)
+                .bind(slot)
+                .bind(epoch)
+                .bind(&s.staker)
+                .bind(&s.withdrawer),
+                StakeAction::AuthorizeStaker | StakeAction::AuthorizeWithdrawer => sqlx::query(
+                    r#"
+                    UPDATE stake_delegations
+                    SET staker        = COALESCE($4, staker),
+                        withdrawer    = COALESCE($5, withdrawer),
+                        updated_epoch = $3,
+                        updated_slot  = $2,
+                        updated_at    = NOW()
+                    WHERE stake_account = $1 AND updated_slot <= $2
+                    "#,
+                )
+                .bind(&s.stake_account)
+                .bind(slot)
+                .bind(epoch)
+                .bind(&s.staker)
+                .bind(&s.withdrawer),
+                StakeAction::Delegate => sqlx::query(
+                    r#"
+                    INSERT INTO stake_delegations (stake_account, staker, vote_account, activation_epoch,
+                                                   updated_epoch, updated_slot)
+                    VALUES ($1, $4, $5, $3, $3, $2)
+                    ON CONFLICT (stake_account) DO UPDATE
+                    SET vote_account       = EXCLUDED.vote_account,
+                        activation_epoch   = EXCLUDED.activation_epoch,
+                        deactivation_epoch = NULL,
+                        staker             = COALESCE(stake_delegations.staker, EXCLUDED.staker),
+                        updated_epoch      = EXCLUDED.updated_epoch,
+                        updated_slot       = EXCLUDED.updated_slot,
+                        updated_at         = NOW()
+                    WHERE stake_delegations.updated_slot <= EXCLUDED.updated_slot
+                    "#,
+                )
+                .bind(&s.stake_account)
+                .bind(slot)
+                .bind(epoch)
+                .bind(&s.staker)
+                .bind(&s.vote_account),
+                StakeAction::Deactivate => sqlx::query(
+                    r#"
+                    UPDATE stake_delegations
+                    SET deactivation_epoch = $3,
+                        updated_epoch      = $3,
+                        updated_slot       = $2,
+                        updated_at         = NOW()
+                    WHERE stake_account = $1 AND updated_slot <= $2
+                    "#,
+                )
+                .bind(&s.stake_account)
+                .bind(slot)
+                .bind(epoch),
+                // The split-off account inherits the authorities and delegation.
+                StakeAction::Split => sqlx::query(
+                    r#"
+                    INSERT INTO stake_delegations (stake_account, staker, withdrawer, vote_account,
+                                                   activation_epoch, deactivation_epoch,
+                                                   updated_epoch, updated_slot)
+                    SELECT $4, staker, withdrawer, vote_account, activation_epoch, deactivation_epoch, $3, $2
+                    FROM stake_delegations
+                    WHERE stake_account = $1
+                    ON CONFLICT (stake_account) DO NOTHING
+                    "#,
+                )
+                .bind(&s.stake_account)
+                .bind(slot)
+                .bind(epoch)
+                .bind(&s.related_account),
+                // The merged source account is closed.
+                StakeAction::Merge => sqlx::query(
+                    r#"
+                    DELETE FROM stake_delegations
+                    WHERE stake_account = $1 AND updated_slot <= $2
+                    "#,
+                )
+                .bind(&s.related_account)
+                .bind(slot),
+                StakeAction::Withdraw => continue,
+            };
+            let result = query.execute(&mut *db).await;
+
+            if let Err(e) = result {
+                error!("Failed to apply stake {} to {}: {e}", s.action.as_str(), s.stake_account);
+                self.db_error(&e);
+                return false;
+            }
+
+        }
+
+        self.publish_all(events.iter().filter_map(|s| Some((format!("stake:{}", s.vote_account.as_ref()?), s))));
+        true
+    }
+
+    pub(super) async fn write_stake_accounts(&mut self, db: &mut PgConnection, accounts: &[StakeAccountEvent]) -> bool {
+        // Accounts without a staker were closed or never initialized.
+        let (live, closed): (Vec<&StakeAccountEvent>, Vec<&StakeAccountEvent>) =
+            newest(accounts, |a| (&a.address, a.slot)).into_iter().partition(|a| a.staker.is_some());
+
+        let result = sqlx::query(
+            r#"
+            DELETE FROM stake_delegations d
+            USING UNNEST($1::TEXT[], $2::BIGINT[]) AS t(stake_account, slot)
+            WHERE d.stake_account = t.stake_account AND d.updated_slot <= t.slot
+            "#,
+        )
+        .bind(column(&closed, |a| a.address.clone()))
+        .bind(column(&closed, |a| a.slot as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to remove {} closed stake accounts: {e}", closed.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        let result = sqlx::query(
+            r#"
+            INSERT INTO stake_delegations (stake_account, updated_slot, staker, withdrawer, vote_account,
+                                           delegated_stake, lamports, rent_exempt_reserve,
+                                           activation_epoch, deactivation_epoch, updated_epoch)
+            SELECT * FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::TEXT[], $4::TEXT[], $5::TEXT[], $6::BIGINT[],
+                                 $7::BIGINT[], $8::BIGINT[], $9::BIGINT[], $10::BIGINT[], $11::BIGINT[])
+            ON CONFLICT (stake_account) DO UPDATE
+            SET staker              = EXCLUDED.staker,
+                withdrawer          = EXCLUDED.withdrawer,
+                vote_account        = EXCLUDED.vote_account,
+                delegated_stake     = EXCLUDED.delegated_stake,
+                lamports            = EXCLUDED.lamports,
+                rent_exempt_reserve = EXCLUDED.rent_exempt_reserve,
+                activation_epoch    = EXCLUDED.activation_epoch,
+                deactivation_epoch  = EXCLUDED.deactivation_epoch,
+                updated_epoch       = EXCLUDED.updated_epoch,
+                updated_slot        = EXCLUDED.updated_slot,
+                updated_at          = NOW()
+            WHERE stake_delegations.updated_slot <= EXCLUDED.updated_slot
+            "#,
+        )
+        .bind(column(&live, |a| a.address.clone()))
+        .bind(column(&live, |a| a.slot as i64))
+        .bind(column(&live, |a| a.staker.clone()))
+        .bind(column(&live, |a| a.withdrawer.clone()))
+        .bind(column(&live, |a| a.vote_account.clone()))
+        .bind(column(&live, |a| a.delegated_stake.map(|s| s as i64)))
+        .bind(column(&live, |a| a.lamports as i64))
+        .bind(column(&live, |a| a.rent_exempt_reserve.map(|r| r as i64)))
+        .bind(column(&live, |a| a.activation_epoch.map(|e| e as i64)))
+        .bind(column(&live, |a| a.deactivation_epoch.map(|e| e as i64)))
+        .bind(column(&live, |a| crate::parser::stake::epoch_for_slot(a.slot) as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} stake accounts: {e}", live.len());
+            self.db_error(&e);
+            return false;
+        }
+        self.publish_all(accounts.iter().map(|a| (format!("account_decoded:{}", a.address), a)));
+        true
+    }
+
+    pub(super) async fn write_stake_pool_events(&mut self, db: &mut PgConnection, events: &[StakePoolEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO stake_pool_events (signature, slot, block_time, instruction_index, inner_index,
+                                           program, pool, action, user_account, sol_amount, lst_mint,
+                                           lst_amount)
+            SELECT signature, slot, to_timestamp(block_time), instruction_index, inner_index, program, pool,
+                   action, user_account, sol_amount, lst_mint, lst_amount
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::INT[], $5::INT[], $6::TEXT[],
+                        $7::TEXT[], $8::TEXT[], $9::TEXT[], $10::BIGINT[], $11::TEXT[], $12::BIGINT[])
+                AS t(signature, slot, block_time, instruction_index, inner_index, program, pool, action,
+                     user_account, sol_amount, lst_mint, lst_amount)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(events, |e| e.signature.clone()))
+        .bind(column(events, |e| e.slot as i64))
+        .bind(column(events, |e| e.block_time as f64))
+        .bind(column(events, |e| e.instruction_index as i32))
+        .bind(column(events, |e| e.inner_index.map(|i| i as i32)))
+        .bind(column(events, |e| e.program.clone()))
+        .bind(column(events, |e| e.pool.clone()))
+        .bind(column(events, |e| e.action.as_str()))
+        .bind(column(events, |e| e.user.clone()))
+        .bind(column(events, |e| e.sol_amount.map(|a| a as i64)))
+        .bind(column(events, |e| e.lst_mint.clone()))
+        .bind(column(events, |e| e.lst_amount.map(|a| a as i64)))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} stake pool events: {e}", events.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        self.publish_all(events.iter().map(|e| (format!("stake_pool:{}", e.pool), e)));
+        true
+    }
+}
@@ -0,0 +1,87 @@
+//! Native SOL transfer and System account event writes.
+
+use super::{column, Writer};
+use crate::parser::{SolTransferEvent, SystemAccountEvent};
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_sol_transfers(&mut self, db: &mut PgConnection, transfers: &[SolTransferEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO sol_transfers (signature, slot, block_time, instruction_index, inner_index,
+                                       source, destination, lamports)
+            SELECT signature, slot, to_timestamp(block_time), instruction_index, inner_index,
+                   source, destination, lamports
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::INT[], $5::INT[], $6::TEXT[],
+                        $7::TEXT[], $8::BIGINT[])
+                AS t(signature, slot, block_time, instruction_index, inner_index, source, destination, lamports)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(transfers, |t| t.signature.clone()))
+        .bind(column(transfers, |t| t.slot as i64))
+        .bind(column(transfers, |t| t.block_time as f64))
+        .bind(column(transfers, |t| t.instruction_index as i32))
+        .bind(column(transfers, |t| t.inner_index.map(|i| i as i32)))
+        .bind(column(transfers, |t| t.source.clone()))
+        .bind(column(transfers, |t| t.destination.clone()))
+        .bind(column(transfers, |t| t.lamports as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} SOL transfers: {e}", transfers.len());
+            self.db_error(&e);
+            return false;
+        }
+
+        self.publish_all(transfers.iter().flat_map(|t| {
+            [
+                (format!("sol_transfer:{}", t.source), t),
+                (format!("sol_transfer:{}", t.destination), t),
+            ]
+        }));
+        true
+    }
+
+    pub(super) async fn write_system_accounts(&mut self, db: &mut PgConnection, events: &[SystemAccountEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO system_account_events (signature, slot, block_time, instruction_index, inner_index,
+                                               action, account, owner, space, lamports, base, seed,
+                                               authority)
+            SELECT signature, slot, to_timestamp(block_time), instruction_index, inner_index,
+                   action, account, owner, space, lamports, base, seed, authority
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::INT[], $5::INT[], $6::TEXT[],
+                        $7::TEXT[], $8::TEXT[], $9::BIGINT[], $10::BIGINT[], $11::TEXT[], $12::TEXT[],
+                        $13::TEXT[])
+                AS t(signature, slot, block_time, instruction_index, inner_index, action, account,
+                     owner, space, lamports, base, seed, authority)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(events, |a| a.signature.clone()))
+        .bind(column(events, |a| a.slot as i64))
+        .bind(column(events, |a| a.block_time as f64))
+        .bind(column(events, |a| a.instruction_index as i32))
+        .bind(column(events, |a| a.inner_index.map(|i| i as i32)))
+        .bind(column(events, |a| a.action.as_str()))
+        .bind(column(events, |a| a.account.clone()))
+        .bind(column(events, |a| a.owner.clone()))
+        .bind(column(events, |a| a.space.map(|s| s as i64)))
+        .bind(column(events, |a| a.lamports.map(|l| l as i64)))
+        .bind(column(events, |a| a.base.clone()))
+        .bind(column(events, |a| a.seed.clone()))
+        .bind(column(events, |a| a.authority.clone()))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} system account events: {e}", events.len());
+            self.db_error(&e);
+            return false;
+        }
+        true
+    }
+}
@@ -0,0 +1,196 @@
+//! Decoded token account and mint writes. Token accounts keep
+//! `token_balances` current; closed accounts are removed from it. The `mints`
+//! registry follows mint accounts, `InitializeMint` and mint/burn amounts.
+
+use super::{column, newest, Writer};
+use crate::parser::{MintEvent, TokenAccountEvent, TokenSupplyEvent};
+use crate::receiver::AccountUpdate;
+use sqlx::types::Json;
+use sqlx::PgConnection;
+use std::collections::HashSet;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_token_balances(&mut self, db: &mut PgConnection, accounts: &[TokenAccountEvent]) -> bool {
+        let rows = newest(accounts, |a| (&a.address, a.slot));
+        let result = sqlx::query(
+            r#"
+            INSERT INTO token_balances (token_account, program, mint, owner, amount, delegate, delegated_amount,
+                                        state, is_native, close_authority, last_slot, updated_at)
+            SELECT *, NOW() FROM UNNEST($1::TEXT[], $2::TEXT[], $3::TEXT[], $4::TEXT[], $5::BIGINT[],
+                                        $6::TEXT[], $7::BIGINT[], $8::TEXT[], $9::BIGINT[], $10::TEXT[],
+                                        $11::BIGINT[])
+            ON CONFLICT (token_account) DO UPDATE
+            SET program = EXCLUDED.program, mint = EXCLUDED.mint, owner = EXCLUDED.owner,
+                amount = EXCLUDED.amount, delegate = EXCLUDED.delegate,
+                delegated_amount = EXCLUDED.delegated_amount, state = EXCLUDED.state,
+                is_native = EXCLUDED.is_native, close_authority = EXCLUDED.close_authority,
+                last_slot = EXCLUDED.last_slot, updated_at = NOW()
+            WHERE token_balances.last_slot <= EXCLUDED.last_slot
+            "#,
+        )
+        .bind(column(&rows, |a| a.address.clone()))
+        .bind(column(&rows, |a| a.program.clone()))
+        .bind(column(&rows, |a| a.mint.clone()))
+        .bind(column(&rows, |a| a.owner.clone()))
+        .bind(column(&rows, |a| a.amount as i64))
+        .bind(column(&rows, |a| a.delegate.clone()))
+        .bind(column(&rows, |a| a.delegated_amount as i64))
+        .bind(column(&rows, |a| a.state.as_str()))
+        .bind(column(&rows, |a| a.is_native.map(|n| n as i64)))
+        .bind(column(&rows, |a| a.close_authority.clone()))
+        .bind(column(&rows, |a| a.slot as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} token balances: {e}", rows.len());
+            self.db_error(&e);
+            return false;
+        }
+        self.publish_all(rows.iter().map(|a| (format!("account_decoded:{}", a.address), a)));
+        true
+    }
+
+    /// Drop balances of accounts drained to zero lamports, i.e. closed. The
+    /// slot guard keeps an account reopened later in the batch.
+    pub(super) async fn remove_closed_token_accounts(&mut self, db: &mut PgConnection, closed: &[AccountUpdate]) -> bool {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM token_balances b
+            USING UNNEST($1::TEXT[], $2::BIGINT[]) AS c(token_account, slot)
+            WHERE b.token_account = c.token_account AND b.last_slot <= c.slot
+            "#,
+        )
+        .bind(column(closed, |a| a.pubkey.clone()))
+        .bind(column(closed, |a| a.slot as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to remove {} closed token accounts: {e}", closed.len());
+            self.db_error(&e);
+            return false;
+        }
+        true
+    }
+
+    pub(super) async fn write_mints(&mut self, db: &mut PgConnection, mints: &[MintEvent]) -> bool {
+        // A mint account update supersedes `InitializeMint` of the same mint.
+        let decoded: HashSet<&str> = mints
+            .iter()
+            .filter(|m| m.extensions.is_some())
+            .map(|m| m.address.as_str())
+            .collect();
+        let mints: Vec<&MintEvent> = mints
+            .iter()
+            .filter(|m| m.extensions.is_some() || !decoded.contains(m.address.as_str()))
+            .collect();
+        let rows = newest(&mints, |m| (&m.address, m.slot));
+        let result = sqlx::query_as::<_, (String, i16, bool)>(
+            r#"
+            INSERT INTO mints (address, program, decimals, supply, mint_authority, freeze_authority,
+                               is_initialized, extensions, name, symbol, slot, supply_slot, updated_at)
+            SELECT t.address, t.program, t.decimals, t.supply, t.mint_authority, t.freeze_authority,
+                   t.is_initialized, t.extensions, COALESCE(t.name, md.name), COALESCE(t.symbol, md.symbol),
+                   t.slot, CASE WHEN t.extensions IS NOT NULL THEN t.slot END, NOW()
+            FROM UNNEST($1::TEXT[], $2::TEXT[], $3::SMALLINT[], $4::BIGINT[], $5::TEXT[], $6::TEXT[],
+                        $7::BOOL[], $8::JSONB[], $9::TEXT[], $10::TEXT[], $11::BIGINT[])
+                AS t(address, program, decimals, supply, mint_authority, freeze_authority,
+                     is_initialized, extensions, name, symbol, slot)
+            LEFT JOIN nft_metadata md ON md.mint = t.address
+            ON CONFLICT (address) DO UPDATE
+            SET program = EXCLUDED.program, decimals = EXCLUDED.decimals, supply = EXCLUDED.supply,
+                mint_authority = EXCLUDED.mint_authority, freeze_authority = EXCLUDED.freeze_authority,
+                is_initialized = EXCLUDED.is_initialized, extensions = EXCLUDED.extensions,
+                name = COALESCE(EXCLUDED.name, mints.name), symbol = COALESCE(EXCLUDED.symbol, mints.symbol),
+                slot = EXCLUDED.slot, supply_slot = EXCLUDED.supply_slot, updated_at = NOW()
+            -- InitializeMint (no extensions) only registers mints not seen yet
+            WHERE mints.slot <= EXCLUDED.slot AND EXCLUDED.extensions IS NOT NULL
+            RETURNING address, decimals, xmax = 0
+            "#,
+        )
+        .bind(column(&rows, |m| m.address.clone()))
+        .bind(column(&rows, |m| m.program.clone()))
+        .bind(column(&rows, |m| m.decimals as i16))
+        .bind(column(&rows, |m| m.supply as i64))
+        .bind(column(&rows, |m| m.mint_authority.clone()))
+        .bind(column(&rows, |m| m.freeze_authority.clone()))
+        .bind(column(&rows, |m| m.is_initialized))
+        .bind(column(&rows, |m| m.extensions.as_ref().map(Json)))
+        .bind(column(&rows, |m| m.name.clone()))
+        .bind(column(&rows, |m| m.symbol.clone()))
+        .bind(column(&rows, |m| m.slot as i64))
+        .fetch_all(&mut *db)
+        .await;
+
+        let written = match result {
+            Ok(written) => written,
+            Err(e) => {
+                error!("Failed to write {} mints: {e}", rows.len());
+                self.db_error(&e);
+                return false;
+            }
+        };
+
+        // Transfers recorded before their mint was known carry no decimals.
+        let (new_mints, decimals): (Vec<String>, Vec<i16>) = written
+            .into_iter()
+            .filter(|(_, decimals, inserted)| *inserted && *decimals > 0)
+            .map(|(mint, decimals, _)| (mint, decimals))
+            .unzip();
+        if !new_mints.is_empty() {
+            let result = sqlx::query(
+                r#"
+                UPDATE token_transfers t SET decimals = m.decimals
+                FROM UNNEST($1::TEXT[], $2::SMALLINT[]) AS m(mint, decimals)
+                WHERE t.mint = m.mint AND t.decimals = 0
+                "#,
+            )
+            .bind(&new_mints)
+            .bind(&decimals)
+            .execute(&mut *db)
+            .await;
+
+            if let Err(e) = result {
+                error!("Failed to backfill transfer decimals of {} mints: {e}", new_mints.len());
+                self.db_error(&e);
+                return false;
+            }
+        }
+
+        let decoded = rows.iter().filter(|m| m.extensions.is_some());
+        self.publish_all(decoded.map(|m| (format!("account_decoded:{}", m.address), m)));
+        true
+    }
+
+    /// Apply minted and burned amounts to `mints.supply`. Changes up to the
+    /// slot of the last mint account update are already part of its supply.
+    pub(super) async fn write_supply_changes(&mut self, db: &mut PgConnection, changes: &[TokenSupplyEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            UPDATE mints m SET supply = m.supply + d.delta, updated_at = NOW()
+            FROM (
+                SELECT t.mint, SUM(t.delta) AS delta
+                FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::BIGINT[]) AS t(mint, delta, slot)
+                JOIN mints x ON x.address = t.mint
+                WHERE x.supply_slot IS NULL OR t.slot > x.supply_slot
+                GROUP BY t.mint
+            ) d
+            WHERE m.address = d.mint
+            "#,
+        )
+        .bind(column(changes, |c| c.mint.clone()))
+        .bind(column(changes, |c| c.delta()))
+        .bind(column(changes, |c| c.slot as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to apply {} supply changes: {e}", changes.len());
+            self.db_error(&e);
+            return false;
+        }
+        true
+    }
+}
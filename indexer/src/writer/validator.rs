@@ -0,0 +1,144 @@
+//! Block reward, vote and leader schedule writes.
+
+use super::{column, newest, Writer};
+use crate::parser::{EpochCredits, LeaderScheduleEvent, VoteAccountEvent, VoteEvent};
+use crate::receiver::BlockUpdate;
+use sqlx::PgConnection;
+use tracing::error;
+
+impl Writer {
+    pub(super) async fn write_block_rewards(&mut self, db: &mut PgConnection, blocks: &[BlockUpdate]) -> bool {
+        let rewards: Vec<_> = blocks.iter().flat_map(|b| b.rewards.iter().map(move |r| (b, r))).collect();
+        if rewards.is_empty() {
+            return true;
+        }
+        let result = sqlx::query(
+            r#"
+            INSERT INTO block_rewards (slot, block_time, pubkey, reward_type, lamports, post_balance, commission)
+            SELECT slot, to_timestamp(block_time), pubkey, reward_type, lamports, post_balance, commission
+            FROM UNNEST($1::BIGINT[], $2::FLOAT8[], $3::TEXT[], $4::TEXT[], $5::BIGINT[], $6::BIGINT[],
+                        $7::SMALLINT[])
+                AS t(slot, block_time, pubkey, reward_type, lamports, post_balance, commission)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(&rewards, |(b, _)| b.slot as i64))
+        .bind(column(&rewards, |(b, _)| b.block_time as f64))
+        .bind(column(&rewards, |(_, r)| r.pubkey.clone()))
+        .bind(column(&rewards, |(_, r)| r.reward_type.map(|t| t.as_str())))
+        .bind(column(&rewards, |(_, r)| r.lamports))
+        .bind(column(&rewards, |(_, r)| r.post_balance as i64))
+        .bind(column(&rewards, |(_, r)| r.commission.map(|c| c as i16)))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} block rewards: {e}", rewards.len());
+            self.db_error(&e);
+            return false;
+        }
+        true
+    }
+
+    pub(super) async fn write_vote_events(&mut self, db: &mut PgConnection, votes: &[VoteEvent]) -> bool {
+        let result = sqlx::query(
+            r#"
+            INSERT INTO vote_events (signature, slot, block_time, vote_account, authority,
+                                     voted_slot, root_slot, latency)
+            SELECT signature, slot, to_timestamp(block_time), vote_account, authority,
+                   voted_slot, root_slot, latency
+            FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::FLOAT8[], $4::TEXT[], $5::TEXT[], $6::BIGINT[],
+                        $7::BIGINT[], $8::INT[])
+                AS t(signature, slot, block_time, vote_account, authority, voted_slot, root_slot, latency)
+            ON CONFLICT DO NOTHING
+            "#,
+        )
+        .bind(column(votes, |v| v.signature.clone()))
+        .bind(column(votes, |v| v.slot as i64))
+        .bind(column(votes, |v| v.block_time as f64))
+        .bind(column(votes, |v| v.vote_account.clone()))
+        .bind(column(votes, |v| v.authority.clone()))
+        .bind(column(votes, |v| v.voted_slot as i64))
+        .bind(column(votes, |v| v.root_slot.map(|s| s as i64)))
+        .bind(column(votes, |v| v.latency as i32))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write {} votes: {e}", votes.len());
+            self.db_error(&e);
+            return false;
+        }
+        true
+    }
+
+    pub(super) async fn write_vote_accounts(&mut self, db: &mut PgConnection, accounts: &[VoteAccountEvent]) -> bool {
+        let credited: Vec<(&VoteAccountEvent, EpochCredits)> = accounts
+            .iter()
+            .filter_map(|a| Some((a, a.epoch_credits?)))
+            .collect();
+        let rows = newest(&credited, |(a, c)| ((a.address.as_str(), c.epoch), a.slot));
+        let result = sqlx::query(
+            r#"
+            INSERT INTO validator_credits (vote_account, epoch, node, commission, credits, prev_credits,
+                                           root_slot, updated_slot)
+            SELECT * FROM UNNEST($1::TEXT[], $2::BIGINT[], $3::TEXT[], $4::SMALLINT[], $5::BIGINT[],
+                                 $6::BIGINT[], $7::BIGINT[], $8::BIGINT[])
+            ON CONFLICT (vote_account, epoch) DO UPDATE
+            SET node         = EXCLUDED.node,
+                commission   = EXCLUDED.commission,
+                credits      = EXCLUDED.credits,
+                prev_credits = EXCLUDED.prev_credits,
+                root_slot    = EXCLUDED.root_slot,
+                updated_slot = EXCLUDED.updated_slot,
+                updated_at   = NOW()
+            WHERE validator_credits.updated_slot <= EXCLUDED.updated_slot
+            "#,
+        )
+        .bind(column(&rows, |(a, _)| a.address.clone()))
+        .bind(column(&rows, |(_, c)| c.epoch as i64))
+        .bind(column(&rows, |(a, _)| a.node.clone()))
+        .bind(column(&rows, |(a, _)| a.commission as i16))
+        .bind(column(&rows, |(_, c)| c.credits as i64))
+        .bind(column(&rows, |(_, c)| c.prev_credits as i64))
+        .bind(column(&rows, |(a, _)| a.root_slot.map(|s| s as i64)))
+        .bind(column(&rows, |(a, _)| a.slot as i64))
+        .execute(&mut *db)
+        .await;
+
+        if let Err(e) = result {
+            error!("Failed to write credits of {} vote accounts: {e}", rows.len());
+            self.db_error(&e);
+            return false;
+        }
+        self.publish_all(accounts.iter().map(|a| (format!("account_decoded:{}", a.address), a)));
+        true
+    }
+
+    /// A full epoch is ~432k slots, so the schedule goes in as arrays.
+    pub(super) async fn write_leader_schedules(&mut self, db: &mut PgConnection, schedules: &[LeaderScheduleEvent]) -> bool {
+        for s in schedules {
+            let (slots, leaders): (Vec<i64>, Vec<&str>) =
+                s.leaders.iter().map(|(slot, leader)| (*slot as i64, leader.as_str())).unzip();
+            let result = sqlx::query(
+                r#"
+                INSERT INTO leader_schedule (slot, epoch, leader)
+                SELECT slot, $1, leader FROM UNNEST($2::BIGINT[], $3::TEXT[]) AS t(slot, leader)
+                ON CONFLICT (slot) DO NOTHING
+                "#,
+            )
+            .bind(s.epoch as i64)
+            .bind(&slots)
+            .bind(&leaders)
+            .execute(&mut *db)
+            .await;
+
+            if let Err(e) = result {
+                error!("Failed to write leader schedule for epoch {}: {e}", s.epoch);
+                self.db_error(&e);
+                return false;
+            }
+        }
+        true
+    }
+}